@@ -0,0 +1,56 @@
+//! Measure the conversions of every backend of `AdaptiveHilbert`, and of the vectorized kernels of
+//! the `simd` module, on a million distances and points at a few orders:
+//!
+//! `cargo run --release --example backends`
+
+use std::time::{Duration, Instant};
+
+use hilbert_curve::{simd, AdaptiveHilbert, Backend, HilbertCurve};
+
+const LEN: usize = 1 << 20;
+
+fn main() {
+    println!("order  backend   d to (x, y)  (x, y) to d");
+    for order in [4, 8, 16, HilbertCurve::MAX_ORDER] {
+        let curve = HilbertCurve::new(order);
+        // an odd multiplier visits the distances in a scattered order
+        let ds: Vec<usize> = (0..LEN)
+            .map(|i| i.wrapping_mul(0x9e37_79b9) % curve.len())
+            .collect();
+        let points: Vec<_> = ds.iter().map(|&d| curve.d_to_xy(d)).collect();
+
+        for backend in [Backend::Bitwise, Backend::Lut, Backend::Bmi2] {
+            let converter = AdaptiveHilbert::with_backend(order, backend).unwrap();
+            let to_points = time(|| converter.d_to_xy_batch(&ds) == points);
+            let to_ds = time(|| converter.xy_to_d_batch(&points) == ds);
+            report(order, &format!("{:?}", backend), to_points, to_ds);
+        }
+        let n = curve.side();
+        let to_points = time(|| simd::convert_1d_to_2d_batch(&ds, n) == points);
+        let to_ds = time(|| simd::convert_2d_to_1d_batch(&points, n) == ds);
+        report(order, "simd", to_points, to_ds);
+    }
+}
+
+// The fastest of a few runs of a conversion checking its results
+fn time(convert: impl Fn() -> bool) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            assert!(convert());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(order: u32, backend: &str, to_points: Duration, to_ds: Duration) {
+    let nanos = |time: Duration| time.as_nanos() as f64 / LEN as f64;
+    println!(
+        "{:>5}  {:<8} {:>9.2} ns {:>9.2} ns",
+        order,
+        backend,
+        nanos(to_points),
+        nanos(to_ds)
+    );
+}
@@ -0,0 +1,106 @@
+//! The `HilbertSortable` derive macro of the `hilbert_curve` crate, re-exported by it with the
+//! `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Index, Member};
+
+/// Derive a `hilbert_index(&self, order: u32) -> usize` method returning the distance of the
+/// struct along the Hilbert curve of the given order, using the integer fields marked with
+/// `#[hilbert(x)]` and `#[hilbert(y)]` as its coordinates.
+///
+/// The coordinates are converted with `as usize` and must be lower than `2^order`. The macro also
+/// implements `HilbertIndexable` with the same coordinates, so the struct can be passed to the
+/// sorting and packing helpers of the crate.
+///
+/// ```
+/// use hilbert_curve::HilbertSortable;
+///
+/// #[derive(HilbertSortable)]
+/// struct Sensor {
+///     name: &'static str,
+///     #[hilbert(x)]
+///     col: u16,
+///     #[hilbert(y)]
+///     row: u16,
+/// }
+///
+/// let sensor = Sensor { name: "a", col: 0, row: 3 };
+/// assert_eq!(sensor.hilbert_index(2), 5);
+///
+/// let mut sensors = [Sensor { name: "b", col: 3, row: 0 }, sensor];
+/// hilbert_curve::hilbert_sort(&mut sensors, 4);
+/// assert_eq!(sensors[0].name, "a");
+/// ```
+#[proc_macro_derive(HilbertSortable, attributes(hilbert))]
+pub fn derive_hilbert_sortable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "HilbertSortable can only be derived for structs",
+            ))
+        }
+    };
+
+    let (mut x, mut y) = (None, None);
+    for (i, field) in fields.iter().enumerate() {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("hilbert")) {
+            let axis: syn::Ident = attr.parse_args()?;
+            let slot = match axis.to_string().as_str() {
+                "x" => &mut x,
+                "y" => &mut y,
+                _ => return Err(Error::new(axis.span(), "expected `x` or `y`")),
+            };
+            if slot.is_some() {
+                return Err(Error::new(
+                    attr.span(),
+                    format!("duplicate `{}` field", axis),
+                ));
+            }
+            *slot = Some(match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            });
+        }
+    }
+    let (x, y) = match (x, y) {
+        (Some(x), Some(y)) => (x, y),
+        _ => {
+            let span = match fields {
+                Fields::Unit => input.span(),
+                _ => fields.span(),
+            };
+            return Err(Error::new(
+                span,
+                "HilbertSortable needs fields marked with `#[hilbert(x)]` and `#[hilbert(y)]`",
+            ));
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Get the distance of this value along the Hilbert curve of the given order.
+            pub fn hilbert_index(&self, order: u32) -> usize {
+                ::hilbert_curve::convert_2d_to_1d_order(self.#x as usize, self.#y as usize, order)
+            }
+        }
+
+        impl #impl_generics ::hilbert_curve::HilbertIndexable for #name #ty_generics #where_clause {
+            fn cell(&self, _n: usize) -> (usize, usize) {
+                (self.#x as usize, self.#y as usize)
+            }
+        }
+    })
+}
@@ -0,0 +1,132 @@
+//! A Postgres extension exposing the conversions and range decompositions of `hilbert_curve`.
+//!
+//! The extension is built with [pgrx](https://github.com/pgcentralfoundation/pgrx): after
+//! `cargo install cargo-pgrx --version 0.16.1 --locked` and `cargo pgrx init`, it can be
+//! installed into a Postgres instance with `cargo pgrx install --release --features pg17` (or the
+//! feature of another major version) and enabled with `CREATE EXTENSION hilbert_curve_pg`. It
+//! provides:
+//!
+//! - `hilbert_encode(x, y, n)`: the distance of the cell (x, y)
+//! - `hilbert_decode(d, n)`: the row `(x, y)` of the cell at distance `d`
+//! - `hilbert_ranges(x0, y0, x1, y1, n[, max_ranges])`: the rows `(range_start, range_end)` of the
+//!   half-open ranges of distances covering the rectangle spanning `x0..=x1` and `y0..=y1`
+//!
+//! All the values are `bigint`s and invalid arguments raise errors.
+
+use hilbert_curve::{try_convert_1d_to_2d, try_convert_2d_to_1d, try_ranges_for_rect};
+use pgrx::prelude::*;
+
+::pgrx::pg_module_magic!(name, version);
+
+/// Convert the coordinates of a cell to its distance.
+#[pg_extern(immutable, parallel_safe, strict)]
+fn hilbert_encode(x: i64, y: i64, n: i64) -> i64 {
+    to_distance(x, y, n).unwrap_or_else(|e| error!("{}", e))
+}
+
+/// Convert a distance to the coordinates of its cell.
+#[pg_extern(immutable, parallel_safe, strict)]
+fn hilbert_decode(d: i64, n: i64) -> TableIterator<'static, (name!(x, i64), name!(y, i64))> {
+    TableIterator::once(to_point(d, n).unwrap_or_else(|e| error!("{}", e)))
+}
+
+/// Decompose a rectangle into ranges of distances.
+#[pg_extern(immutable, parallel_safe, strict)]
+fn hilbert_ranges(
+    x0: i64,
+    y0: i64,
+    x1: i64,
+    y1: i64,
+    n: i64,
+    max_ranges: default!(i64, 9223372036854775807),
+) -> TableIterator<'static, (name!(range_start, i64), name!(range_end, i64))> {
+    TableIterator::new(ranges([x0, y0, x1, y1], n, max_ranges).unwrap_or_else(|e| error!("{}", e)))
+}
+
+// Postgres has no unsigned integers, so negative arguments are rejected before the conversions
+fn to_usize(value: i64) -> Result<usize, String> {
+    usize::try_from(value).map_err(|_| format!("{} is out of range", value))
+}
+
+fn to_point(d: i64, n: i64) -> Result<(i64, i64), String> {
+    let (x, y) = try_convert_1d_to_2d(to_usize(d)?, to_usize(n)?).map_err(|e| e.to_string())?;
+    Ok((x as i64, y as i64))
+}
+
+fn to_distance(x: i64, y: i64, n: i64) -> Result<i64, String> {
+    let d = try_convert_2d_to_1d(to_usize(x)?, to_usize(y)?, to_usize(n)?)
+        .map_err(|e| e.to_string())?;
+    Ok(d as i64)
+}
+
+fn ranges(rect: [i64; 4], n: i64, max_ranges: i64) -> Result<Vec<(i64, i64)>, String> {
+    let mut bounds = [0; 4];
+    for (bound, &c) in bounds.iter_mut().zip(&rect) {
+        *bound = to_usize(c)?;
+    }
+    let [x0, y0, x1, y1] = bounds;
+    // negative limits are rejected like 0, and limits beyond a usize are equivalent to the largest
+    let max_ranges = usize::try_from(max_ranges.max(0)).unwrap_or(usize::MAX);
+    let ranges =
+        try_ranges_for_rect(x0, y0, x1, y1, to_usize(n)?, max_ranges).map_err(|e| e.to_string())?;
+
+    Ok(ranges
+        .into_iter()
+        .map(|range| (range.start as i64, range.end as i64))
+        .collect())
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn functions() {
+        assert_eq!(
+            Spi::get_one("SELECT hilbert_encode(0, 3, 4)"),
+            Ok(Some(5i64))
+        );
+        assert_eq!(
+            Spi::get_two("SELECT x, y FROM hilbert_decode(5, 4)"),
+            Ok((Some(0i64), Some(3i64)))
+        );
+        assert_eq!(
+            Spi::get_one("SELECT count(*) FROM hilbert_ranges(0, 0, 0, 3, 4)"),
+            Ok(Some(2i64))
+        );
+        assert_eq!(
+            Spi::get_one("SELECT count(*) FROM hilbert_ranges(0, 0, 0, 3, 4, 1)"),
+            Ok(Some(1i64))
+        );
+    }
+
+    #[pg_test(error = "coordinates (4, 0) are out of range for a 4x4 grid")]
+    fn out_of_range() {
+        crate::hilbert_encode(4, 0, 4);
+    }
+
+    #[test]
+    fn helpers() {
+        assert_eq!(crate::to_point(5, 4), Ok((0, 3)));
+        assert!(crate::to_point(16, 4).is_err());
+        assert_eq!(crate::to_distance(0, 3, 4), Ok(5));
+        assert!(crate::to_distance(0, -1, 4).is_err());
+        assert_eq!(
+            crate::ranges([0, 0, 0, 3], 4, i64::MAX),
+            Ok(vec![(0, 1), (3, 6)])
+        );
+        assert!(crate::ranges([1, 0, 0, 0], 4, 8).is_err());
+    }
+}
+
+/// This module is required by `cargo pgrx test` invocations.
+#[cfg(test)]
+pub mod pg_test {
+    pub fn setup(_options: Vec<&str>) {}
+
+    #[must_use]
+    pub fn postgresql_conf_options() -> Vec<&'static str> {
+        vec![]
+    }
+}
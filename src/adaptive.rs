@@ -0,0 +1,268 @@
+//! A converter selecting the fastest available implementation at runtime.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{bmi2, HilbertCurve, HilbertError, LutHilbert};
+
+/// An implementation of the conversions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The bit-by-bit loop of [`convert_1d_to_2d`](crate::convert_1d_to_2d) and
+    /// [`convert_2d_to_1d`](crate::convert_2d_to_1d).
+    Bitwise,
+    /// The state tables of [`LutHilbert`].
+    Lut,
+    /// State tables over the Morton codes of the cells, whose bits are interleaved and split with
+    /// the BMI2 instructions PDEP and PEXT when the CPU supports them.
+    Bmi2,
+}
+
+impl Backend {
+    /// Select the fastest backend for a curve of the given order on the current CPU.
+    pub fn select(order: u32) -> Self {
+        Self::select_for(order, bmi2::is_supported())
+    }
+
+    // As measured by `examples/backends.rs` on x86-64, the tables are 5 to 9 times faster than the
+    // loop from order 8 on, and interleaving the bits with PDEP and PEXT makes them up to twice as
+    // fast; the vectorized kernels of the `simd` module, AVX2 included, only beat the plain tables
+    // at the lowest orders, where every backend takes a few nanoseconds, and are up to 3 times
+    // slower than the BMI2 ones, so they are not a backend. A single cell isn't worth a lookup
+    fn select_for(order: u32, bmi2: bool) -> Self {
+        match order {
+            0 => Backend::Bitwise,
+            _ if bmi2 => Backend::Bmi2,
+            _ => Backend::Lut,
+        }
+    }
+}
+
+/// A converter delegating to the [`Backend`] best suited to its curve, as chosen by
+/// [`Backend::select`], so that its callers benefit from newer backends without changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdaptiveHilbert {
+    curve: HilbertCurve,
+    backend: Backend,
+    // the tables, for the `Lut` backend
+    lut: Option<LutHilbert>,
+}
+
+impl AdaptiveHilbert {
+    /// Create a converter for a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        match Self::try_new(order) {
+            Ok(converter) => converter,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create a converter for a curve of the given order, returning an error if it is too large.
+    pub fn try_new(order: u32) -> Result<Self, HilbertError> {
+        Self::with_backend(order, Backend::select(order))
+    }
+
+    /// Create a converter for a curve of the given order using the given backend instead of the
+    /// selected one, returning an error if the curve is too large.
+    pub fn with_backend(order: u32, backend: Backend) -> Result<Self, HilbertError> {
+        let curve = HilbertCurve::try_new(order)?;
+        let lut = match backend {
+            Backend::Lut => Some(LutHilbert::try_new(order)?),
+            _ => None,
+        };
+
+        Ok(AdaptiveHilbert {
+            curve,
+            backend,
+            lut,
+        })
+    }
+
+    /// The backend performing the conversions.
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// The order of the curve, i.e. the number of bits per axis.
+    pub fn order(&self) -> u32 {
+        self.curve.order()
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        self.curve.side()
+    }
+
+    /// The number of cells visited by the curve.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.curve.len()
+    }
+
+    /// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is not lower than [`AdaptiveHilbert::len`].
+    pub fn d_to_xy(&self, d: usize) -> (usize, usize) {
+        match (&self.lut, self.backend) {
+            (Some(lut), _) => lut.d_to_xy(d),
+            (None, Backend::Bmi2) => {
+                assert!(
+                    d < self.len(),
+                    "d must be lower than the length of the curve"
+                );
+                bmi2::convert_1d_to_2d(d, self.order())
+            }
+            (None, _) => self.curve.d_to_xy(d),
+        }
+    }
+
+    /// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is not lower than [`AdaptiveHilbert::side`].
+    pub fn xy_to_d(&self, x: usize, y: usize) -> usize {
+        match (&self.lut, self.backend) {
+            (Some(lut), _) => lut.xy_to_d(x, y),
+            (None, Backend::Bmi2) => {
+                assert!(
+                    x < self.side() && y < self.side(),
+                    "coordinates must be lower than the side of the grid"
+                );
+                bmi2::convert_2d_to_1d(x, y, self.order())
+            }
+            (None, _) => self.curve.xy_to_d(x, y),
+        }
+    }
+
+    /// Convert a slice of one-dimensional distances to their (x, y) coordinates, written to `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is not as long as `ds` or any distance is out of range.
+    pub fn d_to_xy_into(&self, ds: &[usize], out: &mut [(usize, usize)]) {
+        assert_eq!(
+            ds.len(),
+            out.len(),
+            "the output must be as long as the input"
+        );
+        if self.backend == Backend::Bmi2 {
+            self.check_distances(ds);
+            bmi2::convert_1d_to_2d_into(ds, out, self.order());
+            return;
+        }
+        for (point, &d) in out.iter_mut().zip(ds) {
+            *point = self.d_to_xy(d);
+        }
+    }
+
+    /// Convert a slice of (x, y) coordinates to their one-dimensional distances, written to `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is not as long as `points` or any point is out of range.
+    pub fn xy_to_d_into(&self, points: &[(usize, usize)], out: &mut [usize]) {
+        assert_eq!(
+            points.len(),
+            out.len(),
+            "the output must be as long as the input"
+        );
+        if self.backend == Backend::Bmi2 {
+            self.check_points(points);
+            bmi2::convert_2d_to_1d_into(points, out, self.order());
+            return;
+        }
+        for (d, &(x, y)) in out.iter_mut().zip(points) {
+            *d = self.xy_to_d(x, y);
+        }
+    }
+
+    /// Convert a slice of one-dimensional distances to their (x, y) coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any distance is out of range.
+    #[cfg(feature = "alloc")]
+    pub fn d_to_xy_batch(&self, ds: &[usize]) -> Vec<(usize, usize)> {
+        let mut points = alloc::vec![(0, 0); ds.len()];
+        self.d_to_xy_into(ds, &mut points);
+        points
+    }
+
+    /// Convert a slice of (x, y) coordinates to their one-dimensional distances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any point is out of range.
+    #[cfg(feature = "alloc")]
+    pub fn xy_to_d_batch(&self, points: &[(usize, usize)]) -> Vec<usize> {
+        let mut ds = alloc::vec![0; points.len()];
+        self.xy_to_d_into(points, &mut ds);
+        ds
+    }
+
+    fn check_distances(&self, ds: &[usize]) {
+        assert!(
+            ds.iter().all(|&d| d < self.len()),
+            "distances must be lower than the length of the curve"
+        );
+    }
+
+    fn check_points(&self, points: &[(usize, usize)]) {
+        let n = self.side();
+        assert!(
+            points.iter().all(|&(x, y)| x < n && y < n),
+            "coordinates must be lower than the side of the grid"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+    #[test]
+    fn selection() {
+        assert_eq!(Backend::select_for(0, false), Backend::Bitwise);
+        assert_eq!(Backend::select_for(0, true), Backend::Bitwise);
+        assert_eq!(Backend::select_for(5, false), Backend::Lut);
+        assert_eq!(Backend::select_for(5, true), Backend::Bmi2);
+
+        let expected = Backend::select_for(5, bmi2::is_supported());
+        assert_eq!(AdaptiveHilbert::new(5).backend(), expected);
+        assert_eq!(AdaptiveHilbert::new(0).backend(), Backend::Bitwise);
+        assert!(AdaptiveHilbert::try_new(HilbertCurve::MAX_ORDER + 1).is_err());
+    }
+
+    #[test]
+    fn backends() {
+        for &backend in &[Backend::Bitwise, Backend::Lut, Backend::Bmi2] {
+            for order in [0, 1, 4, 9] {
+                let converter = AdaptiveHilbert::with_backend(order, backend).unwrap();
+                let n = converter.side();
+                let ds: Vec<usize> = (0..n * n).collect();
+                let points = converter.d_to_xy_batch(&ds);
+                for (d, &(x, y)) in points.iter().enumerate() {
+                    assert_eq!((x, y), convert_1d_to_2d(d, n));
+                    assert_eq!(converter.xy_to_d(x, y), convert_2d_to_1d(x, y, n));
+                }
+                assert_eq!(converter.xy_to_d_batch(&points), ds);
+
+                let mut out = vec![0; n * n];
+                converter.xy_to_d_into(&points, &mut out);
+                assert_eq!(out, ds);
+                let mut out = vec![(0, 0); n * n];
+                converter.d_to_xy_into(&ds, &mut out);
+                assert_eq!(out, points);
+            }
+        }
+    }
+}
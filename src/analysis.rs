@@ -0,0 +1,271 @@
+//! Measures of the locality of space-filling curves.
+//!
+//! The measures accept any square [`SpaceFillingCurve`], so the Hilbert curve can be compared to
+//! the other curves of the crate; [`compare_hilbert_morton`] does so for the Morton curve. The
+//! bounds of [`curve_distance_bounds`] and [`spatial_radius`] are specific to the Hilbert curve,
+//! which is Hölder continuous: cells `k` steps apart along it are at most `sqrt(6 * k)` cells
+//! apart in the grid.
+
+use core::ops::RangeInclusive;
+
+use crate::{morton::MortonCurve, GridPoint, HilbertCurve, SpaceFillingCurve};
+
+/// The differences between the distances of the pairs of cells adjacent in the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdjacencyStats {
+    /// The mean difference.
+    pub mean: f64,
+    /// The largest difference.
+    pub max: usize,
+}
+
+/// A summary of the locality of a curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalityReport {
+    /// The differences between the distances of adjacent cells.
+    pub adjacency: AdjacencyStats,
+    /// The mean clustering number of the sampled query rectangles.
+    pub mean_clustering: f64,
+    /// The largest clustering number of the sampled query rectangles.
+    pub max_clustering: usize,
+}
+
+/// Measure the differences between the distances of all the pairs of horizontally or vertically
+/// adjacent cells of the grid of a square curve.
+///
+/// # Panics
+///
+/// Panics if the length of the curve is not a square greater than 1.
+pub fn adjacency_stats(curve: &dyn SpaceFillingCurve) -> AdjacencyStats {
+    let n = side(curve);
+    assert!(n > 1, "the grid must have adjacent cells");
+    let (mut sum, mut max, mut pairs) = (0u128, 0, 0u128);
+
+    for y in 0..n {
+        for x in 0..n {
+            let d = curve.point_to_index(GridPoint::new(x, y));
+            let right = Some(x + 1).filter(|&x| x < n).map(|x| (x, y));
+            let up = Some(y + 1).filter(|&y| y < n).map(|y| (x, y));
+            for (nx, ny) in right.into_iter().chain(up) {
+                let diff = d.abs_diff(curve.point_to_index(GridPoint::new(nx, ny)));
+                sum += diff as u128;
+                max = max.max(diff);
+                pairs += 1;
+            }
+        }
+    }
+
+    AdjacencyStats {
+        mean: sum as f64 / pairs as f64,
+        max,
+    }
+}
+
+/// Get the clustering number of the rectangle spanning `x0..=x1` and `y0..=y1`, i.e. the number of
+/// contiguous segments of the curve its cells form.
+///
+/// # Panics
+///
+/// Panics if the length of the curve is not a square or the rectangle is not within the grid.
+pub fn clustering_number(
+    curve: &dyn SpaceFillingCurve,
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+) -> usize {
+    let n = side(curve);
+    assert!(
+        x0 <= x1 && y0 <= y1 && x1 < n && y1 < n,
+        "the rectangle must be within the grid"
+    );
+    let inside = |p: GridPoint| (x0..=x1).contains(&p.x) && (y0..=y1).contains(&p.y);
+
+    // every segment has a first cell whose predecessor is outside of the rectangle
+    let mut segments = 0;
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            let d = curve.point_to_index(GridPoint::new(x, y));
+            if d == 0 || !inside(curve.index_to_point(d - 1)) {
+                segments += 1;
+            }
+        }
+    }
+
+    segments
+}
+
+/// Measure the locality of a square curve: the [`adjacency_stats`] of its grid and the clustering
+/// numbers of `samples` query rectangles of `width` by `height` cells, placed pseudo-randomly
+/// based on `seed`.
+///
+/// # Panics
+///
+/// Panics if the length of the curve is not a square greater than 1, the query rectangles don't
+/// fit in the grid, or `samples` is 0.
+pub fn locality_report(
+    curve: &dyn SpaceFillingCurve,
+    width: usize,
+    height: usize,
+    samples: usize,
+    seed: u64,
+) -> LocalityReport {
+    let n = side(curve);
+    assert!(
+        0 < width && width <= n && 0 < height && height <= n,
+        "the query rectangles must fit in the grid"
+    );
+    assert!(samples > 0, "there must be at least one sample");
+
+    let mut state = seed;
+    let (mut sum, mut max) = (0, 0);
+    for _ in 0..samples {
+        let x0 = (splitmix64(&mut state) % (n - width + 1) as u64) as usize;
+        let y0 = (splitmix64(&mut state) % (n - height + 1) as u64) as usize;
+        let clustering = clustering_number(curve, x0, y0, x0 + width - 1, y0 + height - 1);
+        sum += clustering;
+        max = max.max(clustering);
+    }
+
+    LocalityReport {
+        adjacency: adjacency_stats(curve),
+        mean_clustering: sum as f64 / samples as f64,
+        max_clustering: max,
+    }
+}
+
+/// Compute the [`locality_report`] of the Hilbert and Morton curves of the given order with the
+/// same query rectangles, returning `(hilbert, morton)`.
+///
+/// # Panics
+///
+/// Panics if `order` is 0 or greater than [`HilbertCurve::MAX_ORDER`], or if the arguments of
+/// [`locality_report`] are invalid.
+pub fn compare_hilbert_morton(
+    order: u32,
+    width: usize,
+    height: usize,
+    samples: usize,
+    seed: u64,
+) -> (LocalityReport, LocalityReport) {
+    let hilbert = HilbertCurve::new(order);
+    let morton = MortonCurve::new(order);
+
+    (
+        locality_report(&hilbert, width, height, samples, seed),
+        locality_report(&morton, width, height, samples, seed),
+    )
+}
+
+/// Get bounds on the number of steps between the cells (x1, y1) and (x2, y2) along the curve of
+/// side `n`, without converting them: the locality of the curve gives a lower bound from their
+/// Euclidean distance, and the smallest aligned block of the grid containing both of them, a
+/// contiguous segment of the curve, gives an upper bound.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or either cell is outside the grid.
+pub fn curve_distance_bounds(
+    x1: usize,
+    y1: usize,
+    x2: usize,
+    y2: usize,
+    n: usize,
+) -> RangeInclusive<usize> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        x1 < n && y1 < n && x2 < n && y2 < n,
+        "coordinates must be lower than n"
+    );
+    let (dx, dy) = (x1.abs_diff(x2) as u128, y1.abs_diff(y2) as u128);
+    let lower = (dx * dx + dy * dy).div_ceil(6) as usize;
+    // the level of the block is the number of differing low bits of the coordinates
+    let level = usize::BITS - ((x1 ^ x2) | (y1 ^ y2)).leading_zeros();
+    let upper = ((1u128 << (2 * level)) - 1) as usize;
+
+    lower..=upper
+}
+
+/// Get a radius `r` such that the cells at most `delta` steps apart along the curve are at most
+/// `r` cells apart along each axis of the grid, i.e. within the square of side `2 * r + 1` centered
+/// on either of them; their Euclidean distance is also at most `sqrt(6 * delta)`.
+pub fn spatial_radius(delta: usize) -> usize {
+    (6 * delta as u128).isqrt() as usize
+}
+
+// The side of the square grid filled by the curve
+fn side(curve: &dyn SpaceFillingCurve) -> usize {
+    let len = curve.len();
+    let n = len.isqrt();
+    assert_eq!(n * n, len, "the curve must fill a square grid");
+
+    n
+}
+
+// The SplitMix64 generator, which is enough to place query rectangles
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peano::PeanoCurve;
+
+    #[test]
+    fn adjacency() {
+        let stats = adjacency_stats(&HilbertCurve::new(1));
+        assert_eq!(stats, AdjacencyStats { mean: 1.5, max: 3 });
+        let stats = adjacency_stats(&MortonCurve::new(1));
+        assert_eq!(stats, AdjacencyStats { mean: 1.5, max: 2 });
+        assert_eq!(adjacency_stats(&PeanoCurve::new(1)).max, 5);
+    }
+
+    #[test]
+    fn clustering() {
+        let hilbert = HilbertCurve::new(3);
+        assert_eq!(clustering_number(&hilbert, 0, 0, 7, 7), 1);
+        assert_eq!(clustering_number(&hilbert, 0, 0, 3, 3), 1);
+        assert_eq!(clustering_number(&hilbert, 0, 0, 0, 0), 1);
+        assert_eq!(clustering_number(&MortonCurve::new(3), 0, 0, 7, 1), 2);
+    }
+
+    #[test]
+    fn bounds() {
+        for order in 1..=4 {
+            let curve = HilbertCurve::new(order);
+            let n = curve.side();
+            for d1 in 0..curve.len() {
+                let (x1, y1) = curve.d_to_xy(d1);
+                for d2 in d1..curve.len() {
+                    let (x2, y2) = curve.d_to_xy(d2);
+                    assert!(curve_distance_bounds(x1, y1, x2, y2, n).contains(&(d2 - d1)));
+                    let r = spatial_radius(d2 - d1);
+                    assert!(x1.abs_diff(x2) <= r && y1.abs_diff(y2) <= r);
+                }
+            }
+        }
+        assert_eq!(curve_distance_bounds(3, 3, 3, 3, 8), 0..=0);
+        assert_eq!(curve_distance_bounds(3, 3, 4, 3, 8), 1..=63);
+        assert_eq!(curve_distance_bounds(0, 0, 1, 1, 8), 1..=3);
+        assert_eq!(spatial_radius(0), 0);
+    }
+
+    #[test]
+    fn comparison() {
+        let (hilbert, morton) = compare_hilbert_morton(5, 6, 4, 50, 7);
+        assert!(hilbert.mean_clustering < morton.mean_clustering);
+        assert!(hilbert.max_clustering < morton.max_clustering);
+        assert_eq!(
+            compare_hilbert_morton(5, 6, 4, 50, 7),
+            (hilbert, morton),
+            "the sampling is deterministic"
+        );
+    }
+}
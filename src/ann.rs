@@ -0,0 +1,143 @@
+//! Approximate nearest-neighbor search over the cells of a grid using Hilbert keys.
+
+use alloc::vec::Vec;
+
+use crate::convert_2d_to_1d;
+
+/// An index of points for approximate nearest-neighbor queries.
+///
+/// Every point is indexed by its distance along several curves filling a grid twice as large as
+/// the original one, each shifted diagonally by a different offset; the candidates for a query are
+/// the points nearest to the query along any of the curves. Points close in space but separated by
+/// the boundary of a large quadrant on one curve are usually close on another.
+#[derive(Debug, Clone)]
+pub struct AnnIndex {
+    n: usize,
+    points: Vec<(usize, usize)>,
+    // the offset of every curve and the (distance, point index) pairs sorted along it
+    curves: Vec<(usize, Vec<(usize, usize)>)>,
+}
+
+impl AnnIndex {
+    /// Index the given points of an n by n grid using `num_curves` shifted curves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a power of 2, `2 * n` overflows, any point is outside the grid, or
+    /// `num_curves` is 0.
+    pub fn new(points: &[(usize, usize)], n: usize, num_curves: usize) -> Self {
+        assert!((n & (n - 1)) == 0, "n must be a power of 2");
+        assert!(num_curves > 0, "there must be at least one curve");
+        let side = n.checked_mul(2).expect("2 * n must not overflow");
+        assert!(
+            points.iter().all(|&(x, y)| x < n && y < n),
+            "points must be within the grid"
+        );
+
+        let curves = (0..num_curves)
+            .map(|i| {
+                let offset = i * n / num_curves;
+                let mut keys: Vec<_> = points
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &(x, y))| (convert_2d_to_1d(x + offset, y + offset, side), index))
+                    .collect();
+                keys.sort_unstable();
+                (offset, keys)
+            })
+            .collect();
+
+        AnnIndex {
+            n,
+            points: points.to_vec(),
+            curves,
+        }
+    }
+
+    /// The number of indexed points.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether there are no indexed points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Get the indices of the candidate neighbors of the cell (x, y) in the points the index was
+    /// built from: up to `m` points on either side of the query along each curve, sorted and
+    /// without duplicates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is outside the grid.
+    pub fn candidates(&self, x: usize, y: usize, m: usize) -> Vec<usize> {
+        assert!(x < self.n && y < self.n, "coordinates must be lower than n");
+        let mut candidates = Vec::new();
+        for (offset, keys) in &self.curves {
+            let d = convert_2d_to_1d(x + offset, y + offset, 2 * self.n);
+            let at = keys.partition_point(|&(key, _)| key < d);
+            let (start, end) = (at.saturating_sub(m), (at + m).min(keys.len()));
+            candidates.extend(keys[start..end].iter().map(|&(_, index)| index));
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+    }
+
+    /// Get the index of the candidate neighbor of the cell (x, y), as found by
+    /// [`AnnIndex::candidates`], with the lowest Euclidean distance to it; returns `None` if
+    /// there are no points or `m` is 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is outside the grid.
+    pub fn nearest(&self, x: usize, y: usize, m: usize) -> Option<usize> {
+        self.candidates(x, y, m).into_iter().min_by_key(|&index| {
+            let (px, py) = self.points[index];
+            let (dx, dy) = (px.abs_diff(x) as u128, py.abs_diff(y) as u128);
+            dx * dx + dy * dy
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors() {
+        let n = 64;
+        let points: Vec<_> = (0..500)
+            .map(|i| ((i * 37) % n, (i * 23 + i / 7) % n))
+            .collect();
+        let index = AnnIndex::new(&points, n, 4);
+        assert_eq!(index.len(), 500);
+
+        let mut exact = 0;
+        for qx in (0..n).step_by(5) {
+            for qy in (0..n).step_by(5) {
+                let candidates = index.candidates(qx, qy, 4);
+                assert!(candidates.len() <= 4 * 8);
+                assert!(candidates.windows(2).all(|w| w[0] < w[1]));
+
+                let distance = |(x, y): (usize, usize)| {
+                    let (dx, dy) = (x.abs_diff(qx), y.abs_diff(qy));
+                    dx * dx + dy * dy
+                };
+                let found = index.nearest(qx, qy, 4).map(|i| distance(points[i]));
+                let best = points.iter().map(|&p| distance(p)).min();
+                if found == best {
+                    exact += 1;
+                }
+            }
+        }
+        // most of the 169 queries find their exact nearest neighbor
+        assert!(exact > 120, "{}", exact);
+
+        let on_point = index.nearest(points[10].0, points[10].1, 1).unwrap();
+        assert_eq!(points[on_point], points[10]);
+        assert_eq!(AnnIndex::new(&[], n, 2).nearest(1, 1, 3), None);
+    }
+}
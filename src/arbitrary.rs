@@ -0,0 +1,169 @@
+//! `arbitrary` implementations for structured fuzzing.
+//!
+//! Only valid values are generated: curves of any supported order and orientation, indices and
+//! points of the largest curve, and queries whose cells and distances are within their curves.
+
+use core::ops::Range;
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{CoordinateSystem, GridPoint, HilbertCurve, HilbertIndex, Orientation};
+
+impl<'a> Arbitrary<'a> for HilbertCurve {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let curve = HilbertCurve::new(u.int_in_range(0..=HilbertCurve::MAX_ORDER)?);
+        let coordinates = if bool::arbitrary(u)? {
+            CoordinateSystem::YDown
+        } else {
+            CoordinateSystem::YUp
+        };
+
+        Ok(curve
+            .with_orientation(*u.choose(&Orientation::ALL)?)
+            .with_coordinate_system(coordinates))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(3))
+    }
+}
+
+impl<'a> Arbitrary<'a> for HilbertIndex {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = HilbertCurve::new(HilbertCurve::MAX_ORDER).len();
+        Ok(HilbertIndex(u.int_in_range(0..=len - 1)? as u64))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(8))
+    }
+}
+
+impl<'a> Arbitrary<'a> for GridPoint {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let side = HilbertCurve::new(HilbertCurve::MAX_ORDER).side();
+        Ok(GridPoint::new(
+            u.int_in_range(0..=side - 1)?,
+            u.int_in_range(0..=side - 1)?,
+        ))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(8))
+    }
+}
+
+/// A curve with one of its distances and the cell at that distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveCell {
+    pub curve: HilbertCurve,
+    pub index: HilbertIndex,
+    pub point: GridPoint,
+}
+
+impl<'a> Arbitrary<'a> for CurveCell {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let curve = HilbertCurve::arbitrary(u)?;
+        let index = HilbertIndex(u.int_in_range(0..=curve.len() - 1)? as u64);
+        let point = curve.index_to_point(index);
+
+        Ok(CurveCell {
+            curve,
+            index,
+            point,
+        })
+    }
+}
+
+/// A rectangle spanning `x0..=x1` and `y0..=y1` within the grid of a curve, with a limit on the
+/// number of ranges, as taken by [`ranges_for_rect`](crate::ranges_for_rect).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RectQuery {
+    pub curve: HilbertCurve,
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+    pub max_ranges: usize,
+}
+
+impl<'a> Arbitrary<'a> for RectQuery {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let curve = HilbertCurve::arbitrary(u)?;
+        let last = curve.side() - 1;
+        let (x0, x1) = (u.int_in_range(0..=last)?, u.int_in_range(0..=last)?);
+        let (y0, y1) = (u.int_in_range(0..=last)?, u.int_in_range(0..=last)?);
+
+        Ok(RectQuery {
+            curve,
+            x0: x0.min(x1),
+            y0: y0.min(y1),
+            x1: x0.max(x1),
+            y1: y0.max(y1),
+            max_ranges: u.int_in_range(1..=usize::MAX)?,
+        })
+    }
+}
+
+/// A non-decreasing segment of the distances of a curve, as taken by [`HilbertCurve::range`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentQuery {
+    pub curve: HilbertCurve,
+    pub range: Range<usize>,
+}
+
+impl<'a> Arbitrary<'a> for SegmentQuery {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let curve = HilbertCurve::arbitrary(u)?;
+        let (start, end) = (
+            u.int_in_range(0..=curve.len())?,
+            u.int_in_range(0..=curve.len())?,
+        );
+
+        Ok(SegmentQuery {
+            curve,
+            range: start.min(end)..start.max(end),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranges_for_rect;
+
+    #[test]
+    fn valid_values() {
+        // a pseudorandom input of any length yields valid values
+        let bytes: Vec<u8> = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..50 {
+            let cell = CurveCell::arbitrary(&mut u).unwrap();
+            assert_eq!(cell.curve.point_to_index(cell.point), cell.index);
+            assert!(cell.point.x < cell.curve.side() && cell.point.y < cell.curve.side());
+
+            let rect = RectQuery::arbitrary(&mut u).unwrap();
+            assert!(rect.x0 <= rect.x1 && rect.y0 <= rect.y1 && rect.max_ranges > 0);
+            assert!(rect.x1 < rect.curve.side() && rect.y1 < rect.curve.side());
+
+            let segment = SegmentQuery::arbitrary(&mut u).unwrap();
+            assert!(segment.range.start <= segment.range.end);
+            assert!(segment.range.end <= segment.curve.len());
+
+            let point = GridPoint::arbitrary(&mut u).unwrap();
+            let side = HilbertCurve::new(HilbertCurve::MAX_ORDER).side();
+            assert!(point.x < side && point.y < side);
+        }
+
+        // an exhausted input still yields valid values
+        let mut u = Unstructured::new(&[]);
+        let rect = RectQuery::arbitrary(&mut u).unwrap();
+        assert_eq!(
+            ranges_for_rect(rect.x0, rect.y0, rect.x1, rect.y1, 1, rect.max_ranges),
+            vec![0..1]
+        );
+        assert_eq!(HilbertIndex::arbitrary(&mut u), Ok(HilbertIndex(0)));
+    }
+}
@@ -0,0 +1,204 @@
+//! Kernels converting whole Arrow columns of distances or coordinates.
+//!
+//! The kernels process the value buffers of the arrays at once, like the other batch conversions
+//! of the crate, instead of going through the elements one by one; a null in any input makes the
+//! corresponding output null, and the values behind nulls are neither validated nor meaningful.
+
+use alloc::{format, sync::Arc, vec::Vec};
+
+use arrow_array::{Array, ArrayRef, StructArray, UInt64Array};
+use arrow_buffer::NullBuffer;
+use arrow_schema::{ArrowError, DataType, Field};
+
+use crate::{convert_1d_to_2d_u64, convert_2d_to_1d_u64};
+
+/// Convert columns of x and y coordinates to a column of distances.
+///
+/// Returns an error if `n` is not a power of 2 of at most 2^32, the columns are not equally long
+/// or a valid coordinate is not lower than `n`.
+pub fn xy_to_d(x: &UInt64Array, y: &UInt64Array, n: u64) -> Result<UInt64Array, ArrowError> {
+    masked_xy_to_d(x, y, None, n)
+}
+
+// Convert the coordinates like `xy_to_d`, with the outputs also null where `nulls` are
+fn masked_xy_to_d(
+    x: &UInt64Array,
+    y: &UInt64Array,
+    nulls: Option<&NullBuffer>,
+    n: u64,
+) -> Result<UInt64Array, ArrowError> {
+    validate_side(n)?;
+    if x.len() != y.len() {
+        return Err(invalid("the coordinate columns must be equally long"));
+    }
+    let nulls = NullBuffer::union(NullBuffer::union(x.nulls(), y.nulls()).as_ref(), nulls);
+    check_range(
+        x.values(),
+        nulls.as_ref(),
+        n,
+        "coordinates must be lower than n",
+    )?;
+    check_range(
+        y.values(),
+        nulls.as_ref(),
+        n,
+        "coordinates must be lower than n",
+    )?;
+
+    // the masks keep the values behind nulls within the grid
+    let mask = n - 1;
+    let ds: Vec<u64> = x
+        .values()
+        .iter()
+        .zip(y.values().iter())
+        .map(|(&x, &y)| convert_2d_to_1d_u64(x & mask, y & mask, n))
+        .collect();
+
+    Ok(UInt64Array::new(ds.into(), nulls))
+}
+
+/// Convert a column of points, given as a struct with `UInt64` fields named `x` and `y`, to a
+/// column of distances; a point is null if the struct or either of its fields is null.
+///
+/// Returns an error if the fields are missing or have another type, or under the conditions of
+/// [`xy_to_d`].
+pub fn points_to_d(points: &StructArray, n: u64) -> Result<UInt64Array, ArrowError> {
+    let x = field(points, "x")?;
+    let y = field(points, "y")?;
+    masked_xy_to_d(x, y, points.nulls(), n)
+}
+
+/// Convert a column of distances to a column of points, given as a struct with `UInt64` fields
+/// named `x` and `y`; the points, as well as both of their fields, are null where the distances
+/// are.
+///
+/// Returns an error if `n` is not a power of 2 of at most 2^32 or a valid distance is not lower
+/// than n^2.
+pub fn d_to_xy(d: &UInt64Array, n: u64) -> Result<StructArray, ArrowError> {
+    validate_side(n)?;
+    let nulls = d.nulls().cloned();
+    let len = n as u128 * n as u128;
+    let out_of_range =
+        d.values().iter().enumerate().any(|(i, &d)| {
+            d as u128 >= len && nulls.as_ref().is_none_or(|nulls| nulls.is_valid(i))
+        });
+    if out_of_range {
+        return Err(invalid("distances must be lower than n^2"));
+    }
+
+    // the mask keeps the values behind nulls within the curve
+    let mask = (len - 1) as u64;
+    let (xs, ys): (Vec<u64>, Vec<u64>) = d
+        .values()
+        .iter()
+        .map(|&d| convert_1d_to_2d_u64(d & mask, n))
+        .unzip();
+    let x: ArrayRef = Arc::new(UInt64Array::new(xs.into(), nulls.clone()));
+    let y: ArrayRef = Arc::new(UInt64Array::new(ys.into(), nulls.clone()));
+    let fields = alloc::vec![
+        Field::new("x", DataType::UInt64, true),
+        Field::new("y", DataType::UInt64, true),
+    ];
+
+    StructArray::try_new(fields.into(), alloc::vec![x, y], nulls)
+}
+
+fn validate_side(n: u64) -> Result<(), ArrowError> {
+    if !n.is_power_of_two() || n > 1 << 32 {
+        return Err(invalid("n must be a power of 2 of at most 2^32"));
+    }
+    Ok(())
+}
+
+fn check_range(
+    values: &[u64],
+    nulls: Option<&NullBuffer>,
+    n: u64,
+    message: &str,
+) -> Result<(), ArrowError> {
+    let out_of_range = match nulls {
+        Some(nulls) => values
+            .iter()
+            .zip(nulls.iter())
+            .any(|(&v, valid)| valid && v >= n),
+        None => values.iter().any(|&v| v >= n),
+    };
+    if out_of_range {
+        return Err(invalid(message));
+    }
+    Ok(())
+}
+
+fn field<'a>(points: &'a StructArray, name: &str) -> Result<&'a UInt64Array, ArrowError> {
+    points
+        .column_by_name(name)
+        .ok_or_else(|| ArrowError::SchemaError(format!("the points have no field {}", name)))?
+        .as_any()
+        .downcast_ref()
+        .ok_or_else(|| ArrowError::SchemaError(format!("the field {} must be a UInt64", name)))
+}
+
+fn invalid(message: &str) -> ArrowError {
+    ArrowError::InvalidArgumentError(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+    #[test]
+    fn columns() {
+        let n = 16;
+        let ds: UInt64Array = (0..n * n).map(|d| (d % 7 != 3).then_some(d)).collect();
+        let points = d_to_xy(&ds, n).unwrap();
+        assert_eq!(points.null_count(), ds.null_count());
+        let x = field(&points, "x").unwrap();
+        let y = field(&points, "y").unwrap();
+        for d in 0..(n * n) as usize {
+            if ds.is_valid(d) {
+                let (cx, cy) = convert_1d_to_2d(d, n as usize);
+                assert_eq!((x.value(d), y.value(d)), (cx as u64, cy as u64));
+            } else {
+                assert!(points.is_null(d) && x.is_null(d) && y.is_null(d));
+            }
+        }
+        assert_eq!(points_to_d(&points, n).unwrap(), ds);
+        assert_eq!(xy_to_d(x, y, n).unwrap(), ds);
+    }
+
+    #[test]
+    fn nulls_and_errors() {
+        let x = UInt64Array::from(vec![Some(0), None, Some(3), Some(99)]);
+        let y = UInt64Array::from(vec![Some(3), Some(1), None, None]);
+        let ds = xy_to_d(&x, &y, 4).unwrap();
+        let expected = UInt64Array::from(vec![
+            Some(convert_2d_to_1d(0, 3, 4) as u64),
+            None,
+            None,
+            None,
+        ]);
+        assert_eq!(ds, expected);
+
+        // the values behind a null point are not validated
+        let fields = vec![
+            Field::new("x", DataType::UInt64, true),
+            Field::new("y", DataType::UInt64, true),
+        ];
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from(vec![1, 99])),
+            Arc::new(UInt64Array::from(vec![2, 0])),
+        ];
+        let nulls = NullBuffer::from(vec![true, false]);
+        let points = StructArray::try_new(fields.into(), columns, Some(nulls)).unwrap();
+        let expected = UInt64Array::from(vec![Some(convert_2d_to_1d(1, 2, 4) as u64), None]);
+        assert_eq!(points_to_d(&points, 4).unwrap(), expected);
+
+        assert!(xy_to_d(&x, &UInt64Array::from(vec![1]), 4).is_err());
+        assert!(xy_to_d(&UInt64Array::from(vec![4]), &UInt64Array::from(vec![0]), 4).is_err());
+        assert!(xy_to_d(&UInt64Array::from(vec![0]), &UInt64Array::from(vec![0]), 3).is_err());
+        assert!(d_to_xy(&UInt64Array::from(vec![16]), 4).is_err());
+        assert!(d_to_xy(&UInt64Array::from(vec![None, Some(15)]), 4).is_ok());
+        assert!(d_to_xy(&UInt64Array::from(vec![u64::MAX]), 1 << 32).is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+//! Short, sortable and URL-safe string identifiers of cells, similar to geohashes.
+//!
+//! The distance of a cell along the curve of order k is written with the 2k bits of its base-4
+//! digits, most significant first, 5 bits per character of the geohash alphabet, and the last
+//! character padded with zero bits. The alphabet is in ASCII order, so strings of the same length
+//! sort in curve order, and the first m characters of a string identify the cell containing it on
+//! the curve of order `5m / 2` whenever `5m` is even.
+
+use alloc::string::String;
+
+use crate::HilbertCurve;
+
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The number of characters of the strings of the cells of the curve of the given order.
+pub const fn string_len(order: u32) -> usize {
+    (2 * order as usize).div_ceil(5)
+}
+
+/// Encode the distance `d` along the curve of the given order as a string of
+/// [`string_len(order)`](string_len) characters.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than
+/// `4^order`.
+pub fn encode_string(d: usize, order: u32) -> String {
+    check_order(order);
+    assert!(d >> (2 * order) == 0, "d must be lower than 4^order");
+    let len = string_len(order);
+    // the padding bits are appended below the distance
+    let bits = (d as u128) << (5 * len - 2 * order as usize);
+
+    (0..len)
+        .rev()
+        .map(|i| char::from(ALPHABET[(bits >> (5 * i)) as usize & 31]))
+        .collect()
+}
+
+/// Decode a string created by [`encode_string`] with the given order back to the distance of its
+/// cell; returns `None` if the string has the wrong length, contains a character outside the
+/// alphabet or has nonzero padding bits.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn decode_string(s: &str, order: u32) -> Option<usize> {
+    check_order(order);
+    let len = string_len(order);
+    if s.len() != len {
+        return None;
+    }
+
+    let mut bits = 0u128;
+    for c in s.bytes() {
+        let value = ALPHABET.iter().position(|&a| a == c)?;
+        bits = bits << 5 | value as u128;
+    }
+
+    let padding = 5 * len - 2 * order as usize;
+    if bits & ((1 << padding) - 1) != 0 {
+        return None;
+    }
+
+    Some((bits >> padding) as usize)
+}
+
+fn check_order(order: u32) {
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::promote;
+
+    #[test]
+    fn round_trip() {
+        for order in 0..=7 {
+            let len = 1 << (2 * order);
+            let strings: Vec<_> = (0..len).map(|d| encode_string(d, order)).collect();
+            assert!(strings.windows(2).all(|w| w[0] < w[1]));
+            for (d, s) in strings.iter().enumerate() {
+                assert_eq!(s.len(), string_len(order));
+                assert_eq!(decode_string(s, order), Some(d));
+            }
+        }
+        assert_eq!(encode_string(0, 0), "");
+        assert_eq!(encode_string(0b11_01_10, 3), "v0");
+        assert_eq!(decode_string("v1", 3), None);
+        assert_eq!(decode_string("va", 3), None);
+        assert_eq!(decode_string("v", 3), None);
+    }
+
+    #[test]
+    fn prefixes() {
+        let order = 10;
+        for &d in &[0, 12345, (1 << 20) - 1] {
+            let s = encode_string(d, order);
+            assert_eq!(
+                decode_string(&s[..2], 5),
+                Some(promote(d, order, 5)),
+                "{}",
+                s
+            );
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Conversions of whole slices of distances or coordinates.
+//!
+//! The `_into` variants write into buffers provided by the caller and don't allocate.
+//!
+//! The conversions use state tables over the Morton codes of the cells, built with the BMI2
+//! instructions PDEP and PEXT when the CPU supports them.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{bmi2, convert_1d_to_2d, convert_2d_to_1d, HilbertCurve};
+
+/// Convert a slice of one-dimensional distances to their (x, y) coordinates.
+#[cfg(feature = "alloc")]
+pub fn convert_1d_to_2d_batch(ds: &[usize], n: usize) -> Vec<(usize, usize)> {
+    let mut points = alloc::vec![(0, 0); ds.len()];
+    convert_1d_to_2d_into(ds, &mut points, n);
+    points
+}
+
+/// Convert a slice of (x, y) coordinates to their one-dimensional distances.
+#[cfg(feature = "alloc")]
+pub fn convert_2d_to_1d_batch(points: &[(usize, usize)], n: usize) -> Vec<usize> {
+    let mut ds = alloc::vec![0; points.len()];
+    convert_2d_to_1d_into(points, &mut ds, n);
+    ds
+}
+
+/// Convert a slice of one-dimensional distances to their (x, y) coordinates, written to `out`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `out` is not as long as `ds`.
+pub fn convert_1d_to_2d_into(ds: &[usize], out: &mut [(usize, usize)], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert_eq!(
+        ds.len(),
+        out.len(),
+        "the output must be as long as the input"
+    );
+    match table_order(n) {
+        Some(order) => bmi2::convert_1d_to_2d_into(ds, out, order),
+        None => {
+            for (point, &d) in out.iter_mut().zip(ds) {
+                *point = convert_1d_to_2d(d, n);
+            }
+        }
+    }
+}
+
+/// Convert a slice of (x, y) coordinates to their one-dimensional distances, written to `out`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `out` is not as long as `points`.
+pub fn convert_2d_to_1d_into(points: &[(usize, usize)], out: &mut [usize], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert_eq!(
+        points.len(),
+        out.len(),
+        "the output must be as long as the input"
+    );
+    match table_order(n) {
+        Some(order) => bmi2::convert_2d_to_1d_into(points, out, order),
+        None => {
+            for (d, &(x, y)) in out.iter_mut().zip(points) {
+                *d = convert_2d_to_1d(x, y, n);
+            }
+        }
+    }
+}
+
+/// Convert a slice of one-dimensional distances to their x and y coordinates, written to the
+/// separate slices `xs` and `ys`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `xs` and `ys` are not as long as `ds`.
+pub fn convert_1d_to_2d_split_into(ds: &[usize], xs: &mut [usize], ys: &mut [usize], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        ds.len() == xs.len() && ds.len() == ys.len(),
+        "the outputs must be as long as the input"
+    );
+    let order = table_order(n);
+    for ((x, y), &d) in xs.iter_mut().zip(ys.iter_mut()).zip(ds) {
+        (*x, *y) = match order {
+            Some(order) => bmi2::convert_1d_to_2d(d, order),
+            None => convert_1d_to_2d(d, n),
+        };
+    }
+}
+
+/// Convert the coordinates held in the separate slices `xs` and `ys` to their one-dimensional
+/// distances, written to `out`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `xs`, `ys` and `out` are not all equally long.
+pub fn convert_2d_to_1d_split_into(xs: &[usize], ys: &[usize], out: &mut [usize], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        xs.len() == ys.len() && xs.len() == out.len(),
+        "the inputs and the output must be equally long"
+    );
+    let order = table_order(n);
+    for ((d, &x), &y) in out.iter_mut().zip(xs).zip(ys) {
+        *d = match order {
+            Some(order) => bmi2::convert_2d_to_1d(x, y, order),
+            None => convert_2d_to_1d(x, y, n),
+        };
+    }
+}
+
+// The order of a curve with a side of `n` if the state tables can convert its distances
+fn table_order(n: usize) -> Option<u32> {
+    let order = n.trailing_zeros();
+    (order <= HilbertCurve::MAX_ORDER).then_some(order)
+}
+
+/// Get `k` cells whose distances are evenly spaced along the curve, each one in the middle of its
+/// share of the curve, in curve order.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `k` is greater than n^2.
+#[cfg(feature = "alloc")]
+pub fn sample_points(k: usize, n: usize) -> Vec<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let len = n as u128 * n as u128;
+    assert!(k as u128 <= len, "k must not be greater than n^2");
+
+    (0..k as u128)
+        .map(|i| convert_1d_to_2d(((2 * i + 1) * len / (2 * k as u128)) as usize, n))
+        .collect()
+}
+
+/// Lay a series of values out on an n by n grid in row-major order, the value at index `d` being
+/// placed at the cell `convert_1d_to_2d(d, n)`, i.e. at index `y * n + x` of the result; the cells
+/// past the end of the series are set to `fill`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or there are more than n^2 values.
+#[cfg(feature = "alloc")]
+pub fn layout_heatmap<T: Clone>(values: &[T], n: usize, fill: T) -> Vec<T> {
+    let curve = HilbertCurve::try_from_side(n).expect("n must be a valid side");
+    assert!(values.len() <= n * n, "there must be at most n^2 values");
+    let mut grid = alloc::vec![fill; n * n];
+    for (value, (_, x, y)) in values.iter().zip(curve.iter()) {
+        grid[y * n + x] = value.clone();
+    }
+
+    grid
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        let n = 32;
+        let ds: Vec<usize> = (0..n * n).rev().collect();
+        let points = convert_1d_to_2d_batch(&ds, n);
+        assert_eq!(points[0], convert_1d_to_2d(n * n - 1, n));
+        assert_eq!(convert_2d_to_1d_batch(&points, n), ds);
+    }
+
+    #[test]
+    fn buffers() {
+        let n = 16;
+        let ds: Vec<usize> = (0..n * n).collect();
+        let mut points = vec![(0, 0); n * n];
+        convert_1d_to_2d_into(&ds, &mut points, n);
+        assert_eq!(points, convert_1d_to_2d_batch(&ds, n));
+        let mut out = vec![0; n * n];
+        convert_2d_to_1d_into(&points, &mut out, n);
+        assert_eq!(out, ds);
+
+        let (mut xs, mut ys) = (vec![0; n * n], vec![0; n * n]);
+        convert_1d_to_2d_split_into(&ds, &mut xs, &mut ys, n);
+        let zipped: Vec<_> = xs.iter().copied().zip(ys.iter().copied()).collect();
+        assert_eq!(zipped, points);
+        let mut out = vec![0; n * n];
+        convert_2d_to_1d_split_into(&xs, &ys, &mut out, n);
+        assert_eq!(out, ds);
+    }
+
+    #[test]
+    fn sampling() {
+        let n = 16;
+        assert_eq!(sample_points(0, n), []);
+        assert_eq!(sample_points(1, n), [convert_1d_to_2d(128, n)]);
+        assert_eq!(
+            sample_points(4, n),
+            convert_1d_to_2d_batch(&[32, 96, 160, 224], n)
+        );
+
+        let all = sample_points(n * n, n);
+        let expected: Vec<usize> = (0..n * n).collect();
+        assert_eq!(convert_2d_to_1d_batch(&all, n), expected);
+
+        // the length of the curve doesn't fit in a usize
+        let n = 1 << (usize::BITS / 2);
+        let quarter = (n / 2) * (n / 2);
+        assert_eq!(
+            sample_points(2, n),
+            [
+                convert_1d_to_2d(quarter, n),
+                convert_1d_to_2d(3 * quarter, n)
+            ]
+        );
+    }
+
+    #[test]
+    fn heatmap() {
+        assert_eq!(layout_heatmap(&[1, 2, 3], 2, 0), [1, 0, 2, 3]);
+        let n = 8;
+        let values: Vec<usize> = (0..50).collect();
+        let grid = layout_heatmap(&values, n, usize::MAX);
+        for (i, &value) in grid.iter().enumerate() {
+            let d = convert_2d_to_1d(i % n, i / n, n);
+            assert_eq!(value, if d < 50 { d } else { usize::MAX });
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Arbitrary-precision conversions, available with the `bigint` feature.
+//!
+//! The curve is described by its order, i.e. the number of bits per axis, which may exceed 64.
+
+use num_bigint::BigUint;
+
+use crate::lut::{descend, REVERSE, SWAP};
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates on a curve of the given
+/// order.
+pub fn convert_1d_to_2d(d: &BigUint, order: u64) -> (BigUint, BigUint) {
+    assert!(d.bits() <= 2 * order, "d must be lower than 2^(2 * order)");
+    let (mut x, mut y) = (BigUint::default(), BigUint::default());
+    let mut state = 0;
+
+    for level in (0..order).rev() {
+        let digit = (d.bit(2 * level + 1) as usize) << 1 | d.bit(2 * level) as usize;
+        let (mut qx, mut qy) = match digit {
+            0 => (false, false),
+            1 => (false, true),
+            2 => (true, true),
+            _ => (true, false),
+        };
+        if state & SWAP != 0 {
+            core::mem::swap(&mut qx, &mut qy);
+        }
+        if state & REVERSE != 0 {
+            qx = !qx;
+            qy = !qy;
+        }
+        x.set_bit(level, qx);
+        y.set_bit(level, qy);
+        state ^= descend(digit);
+    }
+
+    (x, y)
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance on a curve of the given
+/// order.
+pub fn convert_2d_to_1d(x: &BigUint, y: &BigUint, order: u64) -> BigUint {
+    assert!(
+        x.bits() <= order && y.bits() <= order,
+        "coordinates must be lower than 2^order"
+    );
+    let mut d = BigUint::default();
+    let mut state = 0;
+
+    for level in (0..order).rev() {
+        let (mut qx, mut qy) = (x.bit(level), y.bit(level));
+        if state & REVERSE != 0 {
+            qx = !qx;
+            qy = !qy;
+        }
+        if state & SWAP != 0 {
+            core::mem::swap(&mut qx, &mut qy);
+        }
+        let digit = (3 * qx as usize) ^ qy as usize;
+        d.set_bit(2 * level + 1, digit & 2 != 0);
+        d.set_bit(2 * level, digit & 1 != 0);
+        state ^= descend(digit);
+    }
+
+    d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_usize() {
+        for order in 0..=6u64 {
+            let n = 1 << order;
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(&BigUint::from(d), order);
+                let (ex, ey) = crate::convert_1d_to_2d(d, n);
+                assert_eq!(
+                    (x.clone(), y.clone()),
+                    (BigUint::from(ex), BigUint::from(ey))
+                );
+                assert_eq!(convert_2d_to_1d(&x, &y, order), BigUint::from(d));
+            }
+        }
+    }
+
+    #[test]
+    fn beyond_u128() {
+        let order = 100;
+        let d = (BigUint::from(1u8) << 200) - 12345u32;
+        let (x, y) = convert_1d_to_2d(&d, order);
+        assert_eq!(convert_2d_to_1d(&x, &y, order), d);
+    }
+}
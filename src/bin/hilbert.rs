@@ -0,0 +1,207 @@
+//! A command-line interface to the conversions, range decompositions and renderings of the crate.
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    process,
+};
+
+use hilbert_curve::{
+    convert_1d_to_2d, convert_2d_to_1d, ranges_for_rect, render::render_ascii, svg::curve_svg,
+    HilbertCurve,
+};
+
+const USAGE: &str = "\
+usage:
+    hilbert d2xy <n> <d>...                  convert distances to coordinates
+    hilbert xy2d <n> <x> <y>                 convert coordinates to a distance
+    hilbert batch <n> d2xy|xy2d              convert the CSV lines of stdin
+    hilbert ranges <n> <x0> <y0> <x1> <y1> [max_ranges]
+                                             decompose an inclusive rectangle into ranges
+    hilbert ascii <order>                    draw the curve with box-drawing characters
+    hilbert svg <order> [cell_size]          draw the curve as an SVG polyline";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let stdout = io::stdout();
+    if let Err(e) = run(&args, &mut stdout.lock()) {
+        eprintln!("error: {}\n\n{}", e, USAGE);
+        process::exit(2);
+    }
+}
+
+fn run(args: &[String], out: &mut impl Write) -> Result<(), String> {
+    let (command, args) = args.split_first().ok_or("missing command")?;
+    let io = |e: io::Error| e.to_string();
+
+    match command.as_str() {
+        "d2xy" => {
+            let n = side(args.first())?;
+            if args.len() < 2 {
+                return Err("missing distance".into());
+            }
+            for d in &args[1..] {
+                let (x, y) = d2xy(parse(d)?, n)?;
+                writeln!(out, "{} {}", x, y).map_err(io)?;
+            }
+        }
+        "xy2d" => {
+            let n = side(args.first())?;
+            let [x, y] = numbers::<2>(&args[1..])?;
+            writeln!(out, "{}", xy2d(x, y, n)?).map_err(io)?;
+        }
+        "batch" => {
+            let n = side(args.first())?;
+            let direction = args.get(1).ok_or("missing direction")?;
+            if direction != "d2xy" && direction != "xy2d" {
+                return Err(format!("unknown direction {}", direction));
+            }
+            for (i, line) in io::stdin().lock().lines().enumerate() {
+                let line = line.map_err(io)?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let converted = convert_line(&line, n, direction == "d2xy")
+                    .map_err(|e| format!("line {}: {}", i + 1, e))?;
+                writeln!(out, "{}", converted).map_err(io)?;
+            }
+        }
+        "ranges" => {
+            let n = side(args.first())?;
+            let (rect, max) = args[1..].split_at(4.min(args.len() - 1));
+            let [x0, y0, x1, y1] = numbers::<4>(rect)?;
+            let max_ranges = max
+                .first()
+                .map(|m| parse(m))
+                .transpose()?
+                .unwrap_or(usize::MAX);
+            if !(x0 <= x1 && y0 <= y1 && x1 < n && y1 < n) {
+                return Err("the rectangle must be within the grid".into());
+            }
+            if max_ranges == 0 {
+                return Err("max_ranges must be positive".into());
+            }
+            for range in ranges_for_rect(x0, y0, x1, y1, n, max_ranges) {
+                writeln!(out, "{}..{}", range.start, range.end).map_err(io)?;
+            }
+        }
+        "ascii" => {
+            let order = order(args.first())?;
+            write!(out, "{}", render_ascii(order)).map_err(io)?;
+        }
+        "svg" => {
+            let order = order(args.first())?;
+            let cell_size = args.get(1).map(|s| parse(s)).transpose()?.unwrap_or(8);
+            if cell_size == 0 {
+                return Err("cell_size must be positive".into());
+            }
+            writeln!(out, "{}", curve_svg(order, cell_size, &[])).map_err(io)?;
+        }
+        _ => return Err(format!("unknown command {}", command)),
+    }
+
+    Ok(())
+}
+
+// Convert a line of comma- or whitespace-separated numbers
+fn convert_line(line: &str, n: usize, d2xy_direction: bool) -> Result<String, String> {
+    let fields: Vec<&str> = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty())
+        .collect();
+
+    if d2xy_direction {
+        let [d] = numbers::<1>(&fields)?;
+        let (x, y) = d2xy(d, n)?;
+        Ok(format!("{},{}", x, y))
+    } else {
+        let [x, y] = numbers::<2>(&fields)?;
+        Ok(xy2d(x, y, n)?.to_string())
+    }
+}
+
+fn d2xy(d: usize, n: usize) -> Result<(usize, usize), String> {
+    if d >= n * n {
+        return Err(format!("distance {} is out of range", d));
+    }
+    Ok(convert_1d_to_2d(d, n))
+}
+
+fn xy2d(x: usize, y: usize, n: usize) -> Result<usize, String> {
+    if x >= n || y >= n {
+        return Err(format!("coordinates ({}, {}) are out of range", x, y));
+    }
+    Ok(convert_2d_to_1d(x, y, n))
+}
+
+fn numbers<const N: usize>(args: &[impl AsRef<str>]) -> Result<[usize; N], String> {
+    if args.len() != N {
+        return Err(format!("expected {} numbers, got {}", N, args.len()));
+    }
+    let mut numbers = [0; N];
+    for (number, arg) in numbers.iter_mut().zip(args) {
+        *number = parse(arg.as_ref())?;
+    }
+    Ok(numbers)
+}
+
+fn parse(arg: &str) -> Result<usize, String> {
+    arg.parse()
+        .map_err(|_| format!("{} is not a non-negative integer", arg))
+}
+
+fn side(arg: Option<&String>) -> Result<usize, String> {
+    let n = parse(arg.ok_or("missing n")?)?;
+    HilbertCurve::try_from_side(n)
+        .map(|curve| curve.side())
+        .map_err(|e| e.to_string())
+}
+
+fn order(arg: Option<&String>) -> Result<u32, String> {
+    let order = parse(arg.ok_or("missing order")?)?;
+    // the renderings grow with 4^order, so only small orders are useful
+    if order > 16 {
+        return Err(format!("order {} is too large to render", order));
+    }
+    Ok(order as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(args: &[&str]) -> Result<String, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut out = Vec::new();
+        run(&args, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(output(&["d2xy", "4", "0", "5"]).unwrap(), "0 0\n0 3\n");
+        assert_eq!(output(&["xy2d", "4", "0", "3"]).unwrap(), "5\n");
+        assert_eq!(
+            output(&["ranges", "4", "0", "0", "1", "1"]).unwrap(),
+            "0..4\n"
+        );
+        assert_eq!(output(&["ascii", "1"]).unwrap(), "┌┐\n╵╵\n");
+        assert!(output(&["svg", "2", "4"]).unwrap().starts_with("<svg"));
+
+        assert!(output(&[]).is_err());
+        assert!(output(&["d2xy", "3", "1"]).is_err());
+        assert!(output(&["d2xy", "4", "16"]).is_err());
+        assert!(output(&["xy2d", "4", "1"]).is_err());
+        assert!(output(&["ranges", "4", "0", "0", "4", "1"]).is_err());
+        assert!(output(&["frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(convert_line("5", 4, true).unwrap(), "0,3");
+        assert_eq!(convert_line(" 0, 3 ", 4, false).unwrap(), "5");
+        assert_eq!(convert_line("0 3", 4, false).unwrap(), "5");
+        assert!(convert_line("1,2,3", 4, false).is_err());
+        assert!(convert_line("x", 4, true).is_err());
+    }
+}
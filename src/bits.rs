@@ -0,0 +1,136 @@
+//! Bit-interleaving primitives, as used by the Morton curve, and bit deposit and extraction,
+//! which use the BMI2 instructions PDEP and PEXT when the CPU supports them.
+
+/// Interleave the bits of `x` and `y`, with the bits of `x` in the even positions and the bits of
+/// `y` in the odd ones.
+pub const fn interleave_bits(x: u32, y: u32) -> u64 {
+    spread(x) | spread(y) << 1
+}
+
+/// Split the even and odd bits of `z` into a pair of (x, y) values; the inverse of
+/// [`interleave_bits`].
+pub const fn deinterleave_bits(z: u64) -> (u32, u32) {
+    (compact(z), compact(z >> 1))
+}
+
+/// Deposit the lowest bits of `value` into the positions of the set bits of `mask`, from the
+/// lowest one up; the other bits of the result are cleared.
+pub fn deposit_bits(value: u64, mask: u64) -> u64 {
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("bmi2") {
+            // SAFETY: the CPU supports BMI2
+            return unsafe { deposit_bits_bmi2(value, mask) };
+        }
+    }
+
+    let (mut mask, mut result) = (mask, 0);
+    let mut bit = 1;
+    while mask != 0 {
+        if value & bit != 0 {
+            result |= mask & mask.wrapping_neg();
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+
+    result
+}
+
+/// Extract the bits of `value` at the positions of the set bits of `mask` into the lowest bits of
+/// the result, from the lowest one up; the inverse of [`deposit_bits`].
+pub fn extract_bits(value: u64, mask: u64) -> u64 {
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("bmi2") {
+            // SAFETY: the CPU supports BMI2
+            return unsafe { extract_bits_bmi2(value, mask) };
+        }
+    }
+
+    let (mut mask, mut result) = (mask, 0);
+    let mut bit = 1;
+    while mask != 0 {
+        if value & mask & mask.wrapping_neg() != 0 {
+            result |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+
+    result
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn deposit_bits_bmi2(value: u64, mask: u64) -> u64 {
+    core::arch::x86_64::_pdep_u64(value, mask)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn extract_bits_bmi2(value: u64, mask: u64) -> u64 {
+    core::arch::x86_64::_pext_u64(value, mask)
+}
+
+// Move the bits of `v` to the even bits
+const fn spread(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | v << 16) & 0x0000_ffff_0000_ffff;
+    v = (v | v << 8) & 0x00ff_00ff_00ff_00ff;
+    v = (v | v << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    v = (v | v << 2) & 0x3333_3333_3333_3333;
+    (v | v << 1) & 0x5555_5555_5555_5555
+}
+
+// Gather the even bits of `v`
+const fn compact(v: u64) -> u32 {
+    let mut v = v & 0x5555_5555_5555_5555;
+    v = (v | v >> 1) & 0x3333_3333_3333_3333;
+    v = (v | v >> 2) & 0x0f0f_0f0f_0f0f_0f0f;
+    v = (v | v >> 4) & 0x00ff_00ff_00ff_00ff;
+    v = (v | v >> 8) & 0x0000_ffff_0000_ffff;
+    ((v | v >> 16) & 0xffff_ffff) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleaving() {
+        assert_eq!(interleave_bits(0b11, 0b00), 0b0101);
+        assert_eq!(interleave_bits(0b00, 0b11), 0b1010);
+        assert_eq!(interleave_bits(u32::MAX, 0), 0x5555_5555_5555_5555);
+        for &(x, y) in &[
+            (0, 0),
+            (1, 2),
+            (0xdead_beef, 0x1234_5678),
+            (u32::MAX, u32::MAX),
+        ] {
+            assert_eq!(deinterleave_bits(interleave_bits(x, y)), (x, y));
+        }
+    }
+
+    #[test]
+    fn deposit_and_extract() {
+        assert_eq!(deposit_bits(0b101, 0b1101_0000), 0b1001_0000);
+        assert_eq!(extract_bits(0b1011_0110, 0b1101_0000), 0b101);
+        assert_eq!(
+            deposit_bits(u64::MAX, 0x5555_5555_5555_5555),
+            0x5555_5555_5555_5555
+        );
+        for &mask in &[0, 1, 0xf0f0, 0x8000_0000_0000_0001, u64::MAX] {
+            for &value in &[0, 0x1234_5678_9abc_def0, u64::MAX] {
+                let bits = mask.count_ones();
+                let low = if bits == 64 {
+                    u64::MAX
+                } else {
+                    (1 << bits) - 1
+                };
+                assert_eq!(extract_bits(deposit_bits(value, mask), mask), value & low);
+                assert_eq!(deposit_bits(extract_bits(value, mask), mask), value & mask);
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Packed streams of distances with a fixed number of bits each.
+//!
+//! A [`BitWriter`] packs values into the bytes of an [`io::Write`] and a [`BitReader`] unpacks
+//! them from an [`io::Read`], most significant bit first, without intermediate buffers; a curve
+//! of order `k` needs `2 * k` bits per distance. The streams perform a call per byte, so the
+//! inner reader or writer should be buffered.
+
+use std::io;
+
+use crate::{convert_1d_to_2d_u64, convert_2d_to_1d_u64};
+
+/// A writer packing values of any width up to 64 bits into an [`io::Write`].
+#[derive(Debug)]
+pub struct BitWriter<W: io::Write> {
+    inner: W,
+    // the pending bits, fewer than 8
+    acc: u8,
+    len: u32,
+}
+
+impl<W: io::Write> BitWriter<W> {
+    /// Create a writer packing values into `inner`.
+    pub fn new(inner: W) -> Self {
+        BitWriter {
+            inner,
+            acc: 0,
+            len: 0,
+        }
+    }
+
+    /// Write the lowest `width` bits of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is greater than 64 or `value` doesn't fit in `width` bits.
+    pub fn write_bits(&mut self, value: u64, width: u32) -> io::Result<()> {
+        assert!(width <= 64, "the width must not be greater than 64");
+        assert!(
+            width == 64 || value >> width == 0,
+            "the value must fit in the width"
+        );
+
+        let mut remaining = width;
+        while remaining > 0 {
+            let take = (8 - self.len).min(remaining);
+            let bits = (value >> (remaining - take)) as u8 & low_mask(take);
+            self.acc = self.acc.checked_shl(take).unwrap_or(0) | bits;
+            self.len += take;
+            remaining -= take;
+            if self.len == 8 {
+                self.inner.write_all(&[self.acc])?;
+                self.acc = 0;
+                self.len = 0;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the distance `d` along the curve of the given order, using `2 * order` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than 32 or `d` is not lower than 4^order.
+    pub fn write_index(&mut self, d: u64, order: u32) -> io::Result<()> {
+        assert!(order <= 32, "the order must not be greater than 32");
+        self.write_bits(d, 2 * order)
+    }
+
+    /// Write the cell (x, y) of the curve of the given order as its distance, using `2 * order`
+    /// bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than 32 or the coordinates are not lower than 2^order.
+    pub fn write_point(&mut self, x: u64, y: u64, order: u32) -> io::Result<()> {
+        assert!(order <= 32, "the order must not be greater than 32");
+        let n = 1 << order;
+        assert!(x < n && y < n, "coordinates must be lower than n");
+        self.write_index(convert_2d_to_1d_u64(x, y, n), order)
+    }
+
+    /// Write the pending bits, padded with zeros to a whole byte, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.len > 0 {
+            self.inner.write_all(&[self.acc << (8 - self.len)])?;
+        }
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+}
+
+/// A reader unpacking values of any width up to 64 bits from an [`io::Read`].
+///
+/// The stream ends when it has fewer bits left than the requested width; since a [`BitWriter`]
+/// pads the last byte with zeros, the number of values narrower than 8 bits must be known to
+/// tell them apart from the padding.
+#[derive(Debug)]
+pub struct BitReader<R: io::Read> {
+    inner: R,
+    // the unread bits of the last byte read
+    acc: u8,
+    len: u32,
+}
+
+impl<R: io::Read> BitReader<R> {
+    /// Create a reader unpacking values from `inner`.
+    pub fn new(inner: R) -> Self {
+        BitReader {
+            inner,
+            acc: 0,
+            len: 0,
+        }
+    }
+
+    /// Read a value of `width` bits; returns `None` if the stream ends first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is greater than 64.
+    pub fn read_bits(&mut self, width: u32) -> io::Result<Option<u64>> {
+        assert!(width <= 64, "the width must not be greater than 64");
+
+        let mut value = 0u64;
+        let mut remaining = width;
+        while remaining > 0 {
+            if self.len == 0 {
+                let mut byte = [0];
+                match self.inner.read_exact(&mut byte) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(e) => return Err(e),
+                }
+                self.acc = byte[0];
+                self.len = 8;
+            }
+            let take = self.len.min(remaining);
+            let bits = (self.acc >> (self.len - take)) & low_mask(take);
+            value = value << take | bits as u64;
+            self.len -= take;
+            remaining -= take;
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Read a distance along the curve of the given order, stored in `2 * order` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than 32.
+    pub fn read_index(&mut self, order: u32) -> io::Result<Option<u64>> {
+        assert!(order <= 32, "the order must not be greater than 32");
+        self.read_bits(2 * order)
+    }
+
+    /// Read a distance along the curve of the given order, stored in `2 * order` bits, and
+    /// convert it to the coordinates of its cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than 32.
+    pub fn read_point(&mut self, order: u32) -> io::Result<Option<(u64, u64)>> {
+        Ok(self
+            .read_index(order)?
+            .map(|d| convert_1d_to_2d_u64(d, 1 << order)))
+    }
+
+    /// Get an iterator over the distances along the curve of the given order until the stream
+    /// ends, as given by [`read_index`](Self::read_index).
+    pub fn indices(self, order: u32) -> Indices<R> {
+        assert!(order <= 32, "the order must not be greater than 32");
+        Indices {
+            reader: self,
+            order,
+        }
+    }
+
+    /// Return the inner reader, discarding the unread bits of the current byte.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// An iterator over the distances read by a [`BitReader`], created by [`BitReader::indices`].
+#[derive(Debug)]
+pub struct Indices<R: io::Read> {
+    reader: BitReader<R>,
+    order: u32,
+}
+
+impl<R: io::Read> Iterator for Indices<R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_index(self.order).transpose()
+    }
+}
+
+// A mask with the lowest `bits` bits set, for up to 8 bits
+fn low_mask(bits: u32) -> u8 {
+    (0xffu16 >> (8 - bits)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut writer = BitWriter::new(Vec::new());
+        for (value, width) in [(5, 3), (0, 0), (0x1ff, 9), (u64::MAX, 64), (1, 1)] {
+            writer.write_bits(value, width).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes.len(), 10);
+        assert_eq!(bytes[..2], [0b1011_1111, 0b1111_1111]);
+
+        let mut reader = BitReader::new(&bytes[..]);
+        for (value, width) in [(5, 3), (0, 0), (0x1ff, 9), (u64::MAX, 64), (1, 1)] {
+            assert_eq!(reader.read_bits(width).unwrap(), Some(value));
+        }
+        assert_eq!(reader.read_bits(8).unwrap(), None);
+    }
+
+    #[test]
+    fn indices() {
+        let order = 5;
+        let mut writer = BitWriter::new(Vec::new());
+        for d in 0..1024 {
+            writer.write_index(d * 7 % 1024, order).unwrap();
+        }
+        writer.write_point(31, 0, order).unwrap();
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes.len(), 1025 * 10 / 8 + 1);
+
+        let mut reader = BitReader::new(&bytes[..]);
+        assert_eq!(reader.read_point(order).unwrap(), Some((0, 0)));
+        let ds: Vec<u64> = reader.indices(order).map(Result::unwrap).collect();
+        assert_eq!(ds.len(), 1024);
+        assert_eq!(ds[..2], [7, 14]);
+        assert_eq!(ds[1023], 1023);
+    }
+}
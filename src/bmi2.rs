@@ -0,0 +1,301 @@
+//! Conversions built on the BMI2 instructions PDEP and PEXT, which interleave the bits of a pair of
+//! coordinates into their Morton code, and split them back out, in one instruction per axis.
+//!
+//! The Hilbert distance of a cell is computed from its Morton code: state tables like the ones of
+//! [`LutHilbert`](crate::LutHilbert) map 8 bits of the Morton code, i.e. a base-4 digit of each
+//! of 4 levels, to 8 bits of the distance and back. Without BMI2 the Morton code is built with the
+//! portable shifts and masks of [`bits`].
+
+use crate::{
+    bits,
+    lut::{start, walk_d_to_xy, walk_xy_to_d, ENTRIES, LEVELS},
+};
+
+// The positions of the bits of x and y in a Morton code
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+const EVEN: u64 = 0x5555_5555_5555_5555;
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+const ODD: u64 = 0xaaaa_aaaa_aaaa_aaaa;
+
+// Return the result of the BMI2 variant of a conversion if the CPU supports it; the conversions of
+// whole slices check only once, so the instructions are inlined into their loops
+macro_rules! dispatch {
+    ($bmi2:ident($($arg:ident),*)) => {
+        #[cfg(all(target_arch = "x86_64", feature = "std"))]
+        {
+            if is_x86_feature_detected!("bmi2") {
+                // SAFETY: the CPU supports BMI2
+                return unsafe { $bmi2($($arg),*) };
+            }
+        }
+    };
+}
+
+// entry = converted bits | next state << 8
+struct Tables {
+    d_to_z: [u16; 4 * ENTRIES],
+    z_to_d: [u16; 4 * ENTRIES],
+}
+
+static TABLES: Tables = Tables::new();
+
+impl Tables {
+    const fn new() -> Self {
+        let mut tables = Tables {
+            d_to_z: [0; 4 * ENTRIES],
+            z_to_d: [0; 4 * ENTRIES],
+        };
+
+        let mut state = 0;
+        while state < 4 {
+            let mut bits = 0;
+            while bits < ENTRIES {
+                let (xy, next) = walk_d_to_xy(state, bits);
+                tables.d_to_z[state * ENTRIES + bits] = (interleave_packed(xy) | next << 8) as u16;
+                let (d, next) = walk_xy_to_d(state, split_packed(bits));
+                tables.z_to_d[state * ENTRIES + bits] = (d | next << 8) as u16;
+                bits += 1;
+            }
+            state += 1;
+        }
+
+        tables
+    }
+}
+
+// Convert `LEVELS` bits of each coordinate, packed as x << LEVELS | y by the table walks, to their
+// Morton code, and back
+const fn interleave_packed(xy: usize) -> usize {
+    bits::interleave_bits((xy >> LEVELS) as u32, (xy & 0xf) as u32) as usize
+}
+
+const fn split_packed(z: usize) -> usize {
+    let (x, y) = bits::deinterleave_bits(z as u64);
+    (x as usize) << LEVELS | y as usize
+}
+
+/// Whether the CPU supports BMI2; the conversions below fall back to portable code if it doesn't.
+pub(crate) fn is_supported() -> bool {
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        is_x86_feature_detected!("bmi2")
+    }
+    #[cfg(not(all(target_arch = "x86_64", feature = "std")))]
+    {
+        false
+    }
+}
+
+// Convert the Morton code of a cell to its distance along a curve of the given order
+#[inline]
+fn z_to_d(z: usize, order: u32) -> usize {
+    let (chunks, mut state) = start(order);
+    let mut d = 0;
+
+    for chunk in (0..chunks).rev() {
+        let bits = (z >> (2 * LEVELS * chunk)) & (ENTRIES - 1);
+        let entry = TABLES.z_to_d[state * ENTRIES + bits] as usize;
+        d = (d << (2 * LEVELS)) | entry & 0xff;
+        state = entry >> 8;
+    }
+
+    d
+}
+
+// Convert a distance along a curve of the given order to the Morton code of its cell
+#[inline]
+fn d_to_z(d: usize, order: u32) -> usize {
+    let (chunks, mut state) = start(order);
+    let mut z = 0;
+
+    for chunk in (0..chunks).rev() {
+        let bits = (d >> (2 * LEVELS * chunk)) & (ENTRIES - 1);
+        let entry = TABLES.d_to_z[state * ENTRIES + bits] as usize;
+        z = (z << (2 * LEVELS)) | entry & 0xff;
+        state = entry >> 8;
+    }
+
+    z
+}
+
+#[inline]
+fn interleave(x: usize, y: usize) -> usize {
+    bits::interleave_bits(x as u32, y as u32) as usize
+}
+
+#[inline]
+fn deinterleave(z: usize) -> (usize, usize) {
+    let (x, y) = bits::deinterleave_bits(z as u64);
+    (x as usize, y as usize)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[inline]
+#[target_feature(enable = "bmi2")]
+unsafe fn interleave_bmi2(x: usize, y: usize) -> usize {
+    use core::arch::x86_64::_pdep_u64;
+    (_pdep_u64(x as u64, EVEN) | _pdep_u64(y as u64, ODD)) as usize
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[inline]
+#[target_feature(enable = "bmi2")]
+unsafe fn deinterleave_bmi2(z: usize) -> (usize, usize) {
+    use core::arch::x86_64::_pext_u64;
+    (
+        _pext_u64(z as u64, EVEN) as usize,
+        _pext_u64(z as u64, ODD) as usize,
+    )
+}
+
+/// Interleave the bits of `x` and `y` into their Morton code.
+pub(crate) fn morton_2d_to_1d(x: usize, y: usize) -> usize {
+    dispatch!(interleave_bmi2(x, y));
+    interleave(x, y)
+}
+
+/// Split a Morton code into its (x, y) coordinates.
+pub(crate) fn morton_1d_to_2d(z: usize) -> (usize, usize) {
+    dispatch!(deinterleave_bmi2(z));
+    deinterleave(z)
+}
+
+/// Convert a distance along a curve of the given order to a pair of (x, y) coordinates.
+pub(crate) fn convert_1d_to_2d(d: usize, order: u32) -> (usize, usize) {
+    morton_1d_to_2d(d_to_z(d, order))
+}
+
+/// Convert a pair of (x, y) coordinates to a distance along a curve of the given order.
+pub(crate) fn convert_2d_to_1d(x: usize, y: usize, order: u32) -> usize {
+    z_to_d(morton_2d_to_1d(x, y), order)
+}
+
+/// Convert distances along a curve of the given order to their coordinates, written to `out`.
+pub(crate) fn convert_1d_to_2d_into(ds: &[usize], out: &mut [(usize, usize)], order: u32) {
+    dispatch!(convert_1d_to_2d_into_bmi2(ds, out, order));
+    for (point, &d) in out.iter_mut().zip(ds) {
+        *point = deinterleave(d_to_z(d, order));
+    }
+}
+
+/// Convert coordinates to their distances along a curve of the given order, written to `out`.
+pub(crate) fn convert_2d_to_1d_into(points: &[(usize, usize)], out: &mut [usize], order: u32) {
+    dispatch!(convert_2d_to_1d_into_bmi2(points, out, order));
+    for (d, &(x, y)) in out.iter_mut().zip(points) {
+        *d = z_to_d(interleave(x, y), order);
+    }
+}
+
+/// Convert Morton codes to their coordinates, written to `out`.
+pub(crate) fn morton_1d_to_2d_into(zs: &[usize], out: &mut [(usize, usize)]) {
+    dispatch!(morton_1d_to_2d_into_bmi2(zs, out));
+    for (point, &z) in out.iter_mut().zip(zs) {
+        *point = deinterleave(z);
+    }
+}
+
+/// Convert coordinates to their Morton codes, written to `out`.
+pub(crate) fn morton_2d_to_1d_into(points: &[(usize, usize)], out: &mut [usize]) {
+    dispatch!(morton_2d_to_1d_into_bmi2(points, out));
+    for (z, &(x, y)) in out.iter_mut().zip(points) {
+        *z = interleave(x, y);
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn convert_1d_to_2d_into_bmi2(ds: &[usize], out: &mut [(usize, usize)], order: u32) {
+    for (point, &d) in out.iter_mut().zip(ds) {
+        *point = deinterleave_bmi2(d_to_z(d, order));
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn convert_2d_to_1d_into_bmi2(points: &[(usize, usize)], out: &mut [usize], order: u32) {
+    for (d, &(x, y)) in out.iter_mut().zip(points) {
+        *d = z_to_d(interleave_bmi2(x, y), order);
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn morton_1d_to_2d_into_bmi2(zs: &[usize], out: &mut [(usize, usize)]) {
+    for (point, &z) in out.iter_mut().zip(zs) {
+        *point = deinterleave_bmi2(z);
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn morton_2d_to_1d_into_bmi2(points: &[(usize, usize)], out: &mut [usize]) {
+    for (z, &(x, y)) in out.iter_mut().zip(points) {
+        *z = interleave_bmi2(x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HilbertCurve;
+
+    #[test]
+    fn matches_portable() {
+        for order in 0..=7 {
+            let n = 1 << order;
+            let ds: Vec<usize> = (0..n * n).collect();
+            let points: Vec<_> = ds.iter().map(|&d| crate::convert_1d_to_2d(d, n)).collect();
+            for (&d, &(x, y)) in ds.iter().zip(&points) {
+                assert_eq!(convert_1d_to_2d(d, order), (x, y));
+                assert_eq!(convert_2d_to_1d(x, y, order), d);
+                assert_eq!(deinterleave(d_to_z(d, order)), (x, y));
+                assert_eq!(z_to_d(interleave(x, y), order), d);
+            }
+
+            let mut out = vec![(0, 0); ds.len()];
+            convert_1d_to_2d_into(&ds, &mut out, order);
+            assert_eq!(out, points);
+            let mut out = vec![0; ds.len()];
+            convert_2d_to_1d_into(&points, &mut out, order);
+            assert_eq!(out, ds);
+
+            let mut out = vec![(0, 0); ds.len()];
+            morton_1d_to_2d_into(&ds, &mut out);
+            assert!(out
+                .iter()
+                .zip(&ds)
+                .all(|(&p, &z)| p == crate::morton::convert_1d_to_2d(z, n)));
+            let mut zs = vec![0; ds.len()];
+            morton_2d_to_1d_into(&out, &mut zs);
+            assert_eq!(zs, ds);
+        }
+
+        let order = HilbertCurve::MAX_ORDER;
+        let n = 1 << order;
+        for &(x, y) in &[(0, n - 1), (n - 1, n / 3), (n / 5, n - 2)] {
+            let d = crate::convert_2d_to_1d(x, y, n);
+            assert_eq!(convert_2d_to_1d(x, y, order), d);
+            assert_eq!(convert_1d_to_2d(d, order), (x, y));
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    #[test]
+    fn instructions() {
+        if !is_supported() {
+            return;
+        }
+        for &(x, y) in &[
+            (0, 0),
+            (1, 2),
+            (0xdead_beef, 0x1234_5678),
+            (u32::MAX as usize, 0),
+        ] {
+            // SAFETY: the CPU supports BMI2
+            let z = unsafe { interleave_bmi2(x, y) };
+            assert_eq!(z, interleave(x, y));
+            // SAFETY: as above
+            assert_eq!(unsafe { deinterleave_bmi2(z) }, (x, y));
+        }
+    }
+}
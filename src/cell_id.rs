@@ -0,0 +1,216 @@
+//! A hierarchical cell identifier encoding both a cell and its level in a single integer.
+
+use core::ops::RangeInclusive;
+
+use crate::{convert_1d_to_2d_u64, convert_2d_to_1d_u64};
+
+/// A cell of the Hilbert curve of order `level`, for levels up to [`CellId::MAX_LEVEL`], encoded
+/// in a single `u64` in the same way as S2 cell ids.
+///
+/// The distance of the cell is followed by a marker bit and then by `2 * (MAX_LEVEL - level)`
+/// zero bits, so the ids of all the levels are ordered along the curve: the ids of the leaf cells
+/// contained in a cell span [`CellId::range_min`] to [`CellId::range_max`] and the cell itself
+/// lies in the middle of that range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CellId(u64);
+
+impl CellId {
+    /// The level of the leaf cells, which are the smallest ones.
+    pub const MAX_LEVEL: u32 = 31;
+
+    /// Create the id of the cell at distance `d` on the curve of order `level`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is greater than [`CellId::MAX_LEVEL`] or `d` is not lower than
+    /// `4^level`.
+    pub fn from_distance(d: u64, level: u32) -> Self {
+        assert!(
+            level <= Self::MAX_LEVEL,
+            "level must not exceed CellId::MAX_LEVEL"
+        );
+        assert!(d >> (2 * level) == 0, "d must be lower than 4^level");
+        CellId((2 * d + 1) << (2 * (Self::MAX_LEVEL - level)))
+    }
+
+    /// Create the id of the cell (x, y) on the curve of order `level`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is greater than [`CellId::MAX_LEVEL`] or either coordinate is not lower
+    /// than `2^level`.
+    pub fn from_point(x: u32, y: u32, level: u32) -> Self {
+        assert!(
+            level <= Self::MAX_LEVEL,
+            "level must not exceed CellId::MAX_LEVEL"
+        );
+        let n = 1 << level;
+        assert!(
+            u64::from(x) < n && u64::from(y) < n,
+            "coordinates must be lower than 2^level"
+        );
+        Self::from_distance(convert_2d_to_1d_u64(x.into(), y.into(), n), level)
+    }
+
+    /// Create a cell id from its raw value; returns `None` if the value is not a valid id.
+    pub fn from_raw(id: u64) -> Option<Self> {
+        // the marker must be at an even position from the top of the leaf level
+        if id != 0 && id.trailing_zeros() % 2 == 0 && id >> (2 * Self::MAX_LEVEL + 1) == 0 {
+            Some(CellId(id))
+        } else {
+            None
+        }
+    }
+
+    /// The raw value of the id.
+    pub fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    // The lowest set bit of the id, which is its marker
+    fn lsb(self) -> u64 {
+        self.0 & self.0.wrapping_neg()
+    }
+
+    /// The level of the cell.
+    pub fn level(self) -> u32 {
+        Self::MAX_LEVEL - self.0.trailing_zeros() / 2
+    }
+
+    /// Whether the cell is at [`CellId::MAX_LEVEL`].
+    pub fn is_leaf(self) -> bool {
+        self.0 & 1 != 0
+    }
+
+    /// The distance of the cell along the curve of its level.
+    pub fn distance(self) -> u64 {
+        self.0 >> (self.0.trailing_zeros() + 1)
+    }
+
+    /// The (x, y) coordinates of the cell within the grid of its level.
+    pub fn point(self) -> (u32, u32) {
+        let (x, y) = convert_1d_to_2d_u64(self.distance(), 1 << self.level());
+        (x as u32, y as u32)
+    }
+
+    /// The cell containing this one at the previous level; returns `None` at level 0.
+    pub fn parent(self) -> Option<Self> {
+        self.level()
+            .checked_sub(1)
+            .map(|level| self.parent_at(level))
+    }
+
+    /// The cell containing this one at the given level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is greater than the level of the cell.
+    pub fn parent_at(self, level: u32) -> Self {
+        assert!(
+            level <= self.level(),
+            "level must not exceed the level of the cell"
+        );
+        let lsb = 1u64 << (2 * (Self::MAX_LEVEL - level));
+        CellId((self.0 & lsb.wrapping_neg()) | lsb)
+    }
+
+    /// The 4 cells contained in this one at the next level, in curve order; returns `None` for a
+    /// leaf cell.
+    pub fn children(self) -> Option<[Self; 4]> {
+        if self.is_leaf() {
+            return None;
+        }
+
+        let lsb = self.lsb() >> 2;
+        let first = self.0 - self.lsb() + lsb;
+        Some([0, 1, 2, 3].map(|i| CellId(first + 2 * i * lsb)))
+    }
+
+    /// The lowest id of the leaf cells contained in this one.
+    pub fn range_min(self) -> Self {
+        CellId(self.0 - (self.lsb() - 1))
+    }
+
+    /// The highest id of the leaf cells contained in this one.
+    pub fn range_max(self) -> Self {
+        CellId(self.0 + (self.lsb() - 1))
+    }
+
+    /// The ids of the leaf cells contained in this one, from [`CellId::range_min`] to
+    /// [`CellId::range_max`].
+    pub fn leaf_range(self) -> RangeInclusive<Self> {
+        self.range_min()..=self.range_max()
+    }
+
+    /// Whether the other cell is this one or one of its descendants.
+    pub fn contains(self, other: CellId) -> bool {
+        self.range_min() <= other && other <= self.range_max()
+    }
+
+    /// Whether this cell and the other one have at least one leaf cell in common, i.e. one of
+    /// them contains the other.
+    pub fn intersects(self, other: CellId) -> bool {
+        other.range_min() <= self.range_max() && other.range_max() >= self.range_min()
+    }
+}
+
+// the raw value is validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CellId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = u64::deserialize(deserializer)?;
+        CellId::from_raw(id).ok_or_else(|| serde::de::Error::custom("invalid cell id"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hierarchy() {
+        let root = CellId::from_distance(0, 0);
+        assert_eq!(root.level(), 0);
+        assert_eq!(root.parent(), None);
+        assert_eq!(
+            root.range_min(),
+            CellId::from_distance(0, CellId::MAX_LEVEL)
+        );
+        assert_eq!(
+            root.range_max(),
+            CellId::from_distance((1 << 62) - 1, CellId::MAX_LEVEL)
+        );
+
+        let level = 3;
+        for d in 0..64 {
+            let cell = CellId::from_distance(d, level);
+            assert_eq!((cell.distance(), cell.level()), (d, level));
+            assert_eq!(CellId::from_raw(cell.to_raw()), Some(cell));
+            let (x, y) = cell.point();
+            assert_eq!(CellId::from_point(x, y, level), cell);
+            assert_eq!(cell.parent_at(0), root);
+            assert!(root.contains(cell) && !cell.contains(root));
+
+            let children = cell.children().unwrap();
+            for (i, child) in children.iter().enumerate() {
+                assert_eq!(child.distance(), 4 * d + i as u64);
+                assert_eq!(child.parent(), Some(cell));
+                assert!(cell.contains(*child) && cell.intersects(*child));
+                let (cx, cy) = child.point();
+                assert_eq!((cx / 2, cy / 2), (x, y));
+            }
+            assert_eq!(children[0].range_min(), cell.range_min());
+            assert_eq!(children[3].range_max(), cell.range_max());
+            assert!(children[1] < cell && cell < children[2]);
+        }
+
+        let leaf = CellId::from_point(5, 7, CellId::MAX_LEVEL);
+        assert!(leaf.is_leaf());
+        assert_eq!(leaf.children(), None);
+        assert_eq!(leaf.leaf_range(), leaf..=leaf);
+        assert!(!CellId::from_distance(1, 1).intersects(CellId::from_distance(2, 1)));
+        assert_eq!(CellId::from_raw(0), None);
+        assert_eq!(CellId::from_raw(2), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! Clustering of points by the gaps between their distances along the curve.
+
+use alloc::vec::Vec;
+
+use crate::{convert_2d_to_1d, hilbert_argsort, HilbertIndexable};
+
+/// Group the points of an n by n grid into clusters of points consecutive in curve order,
+/// splitting wherever the distances of two consecutive points differ by more than `max_gap`; each
+/// cluster holds the indices of its points in curve order and the clusters are in curve order as
+/// well.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or any point is outside the grid.
+pub fn cluster_by_gap<T: HilbertIndexable>(
+    points: &[T],
+    n: usize,
+    max_gap: usize,
+) -> Vec<Vec<usize>> {
+    let (sorted, keys) = sorted_keys(points, n);
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    for i in 1..=sorted.len() {
+        if i == sorted.len() || keys[i] - keys[i - 1] > max_gap {
+            clusters.push(sorted[start..i].to_vec());
+            start = i;
+        }
+    }
+
+    clusters
+}
+
+/// Group the points of an n by n grid into clusters of at most `max_size` points consecutive in
+/// curve order, splitting the clusters that are too large at the largest gap between the
+/// distances of their points, as given by [`cluster_by_gap`].
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2, `max_size` is 0, or any point is outside the grid.
+pub fn cluster_by_size<T: HilbertIndexable>(
+    points: &[T],
+    n: usize,
+    max_size: usize,
+) -> Vec<Vec<usize>> {
+    assert!(max_size > 0, "max_size must be positive");
+    let (sorted, keys) = sorted_keys(points, n);
+    let mut clusters = Vec::new();
+    if !sorted.is_empty() {
+        split(&sorted, &keys, max_size, &mut clusters);
+    }
+
+    clusters
+}
+
+// Split the cluster at its largest gaps until its parts are small enough
+fn split(indices: &[usize], keys: &[usize], max_size: usize, clusters: &mut Vec<Vec<usize>>) {
+    if indices.len() <= max_size {
+        clusters.push(indices.to_vec());
+        return;
+    }
+
+    // the first of the largest gaps, which is before the element at `at`
+    let at = (1..keys.len())
+        .rev()
+        .max_by_key(|&i| keys[i] - keys[i - 1])
+        .expect("the cluster has more than one point");
+    split(&indices[..at], &keys[..at], max_size, clusters);
+    split(&indices[at..], &keys[at..], max_size, clusters);
+}
+
+// The indices of the points in curve order and their distances
+fn sorted_keys<T: HilbertIndexable>(points: &[T], n: usize) -> (Vec<usize>, Vec<usize>) {
+    let sorted = hilbert_argsort(points, n);
+    let keys = sorted
+        .iter()
+        .map(|&i| {
+            let (x, y) = points[i].cell(n);
+            convert_2d_to_1d(x, y, n)
+        })
+        .collect();
+
+    (sorted, keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn gaps() {
+        let n = 16;
+        let ds = [200, 3, 0, 100, 1, 103, 250];
+        let points: Vec<_> = ds.iter().map(|&d| convert_1d_to_2d(d, n)).collect();
+        assert_eq!(
+            cluster_by_gap(&points, n, 5),
+            [vec![2, 4, 1], vec![3, 5], vec![0], vec![6]]
+        );
+        assert_eq!(cluster_by_gap(&points, n, 100), [vec![2, 4, 1, 3, 5, 0, 6]]);
+        assert!(cluster_by_gap::<(usize, usize)>(&[], n, 5).is_empty());
+    }
+
+    #[test]
+    fn sizes() {
+        let n = 16;
+        let ds = [200, 3, 0, 100, 1, 103, 250];
+        let points: Vec<_> = ds.iter().map(|&d| convert_1d_to_2d(d, n)).collect();
+        assert_eq!(
+            cluster_by_size(&points, n, 3),
+            [vec![2, 4, 1], vec![3, 5], vec![0, 6]]
+        );
+        assert_eq!(cluster_by_size(&points, n, 7).len(), 1);
+        assert_eq!(cluster_by_size(&points, n, 1).len(), 7);
+    }
+}
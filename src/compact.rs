@@ -0,0 +1,234 @@
+//! Compact Hilbert indices, based on Hamilton and Rau-Chaplin ("Compact Hilbert indices: Space-
+//! filling curves for domains with unequal side lengths", Information Processing Letters 105,
+//! 2008).
+//!
+//! Every axis `j` has its own precision of `precisions[j]` bits, and the index uses exactly the
+//! sum of the precisions as its number of bits; points are ordered like on a regular Hilbert curve
+//! covering the whole bounding hypercube.
+
+use crate::{
+    bits::extract_bits,
+    gray::{gray_decode, gray_encode},
+};
+
+/// Convert a point whose coordinates have the given per-axis precisions to its compact Hilbert
+/// index.
+pub fn convert_nd_to_compact(coords: &[usize], precisions: &[u32]) -> usize {
+    assert_precisions(precisions);
+    assert_eq!(
+        coords.len(),
+        precisions.len(),
+        "there must be one precision per coordinate"
+    );
+    assert!(
+        coords
+            .iter()
+            .zip(precisions)
+            .all(|(&c, &m)| m == usize::BITS || c >> m == 0),
+        "coordinates must be lower than 2^precision"
+    );
+    let dims = coords.len() as u32;
+    let max = precisions.iter().copied().max().unwrap_or(0);
+    let (mut entry, mut dir) = (0, 0);
+    let mut h = 0;
+
+    for i in (0..max).rev() {
+        let mask = rotate_right(extract_mask(precisions, i), dir + 1, dims);
+        let mut l = 0;
+        for (j, &c) in coords.iter().enumerate() {
+            l |= ((c >> i) & 1) << j;
+        }
+        l = rotate_right(l ^ entry, dir + 1, dims);
+        let w = gray_decode(l);
+
+        // the rank of `w` among the values allowed by the fixed bits consists of its masked bits
+        let r = extract_bits(w as u64, mask as u64) as usize;
+
+        entry ^= rotate_left(entry_point(w), dir + 1, dims);
+        dir = (dir + direction(w, dims) + 1) % dims;
+        h = h << mask.count_ones() | r;
+    }
+
+    h
+}
+
+/// Convert a compact Hilbert index to the coordinates of the point it represents, given the
+/// per-axis precisions.
+#[cfg(feature = "alloc")]
+pub fn convert_compact_to_nd(h: usize, precisions: &[u32]) -> alloc::vec::Vec<usize> {
+    assert_precisions(precisions);
+    let dims = precisions.len() as u32;
+    let total: u32 = precisions.iter().sum();
+    assert!(
+        total == usize::BITS || h >> total == 0,
+        "h must be lower than 2^(sum of precisions)"
+    );
+    let max = precisions.iter().copied().max().unwrap_or(0);
+    let mut coords = alloc::vec![0; precisions.len()];
+    let (mut entry, mut dir) = (0, 0);
+    let mut remaining = total;
+
+    for i in (0..max).rev() {
+        let mask = rotate_right(extract_mask(precisions, i), dir + 1, dims);
+        let fixed = rotate_right(entry, dir + 1, dims) & !mask;
+        let len = mask.count_ones();
+        remaining -= len;
+        let r = if len == 0 {
+            0
+        } else {
+            (h >> remaining) & (usize::MAX >> (usize::BITS - len))
+        };
+
+        // restore `w` from the top; its masked bits come from the rank and the others are
+        // implied by the fixed bits of its Gray code
+        let (mut w, mut j) = (0, len);
+        for k in (0..dims).rev() {
+            let above = if k + 1 < dims { w >> (k + 1) & 1 } else { 0 };
+            let bit = if mask >> k & 1 == 1 {
+                j -= 1;
+                r >> j & 1
+            } else {
+                (fixed >> k & 1) ^ above
+            };
+            w |= bit << k;
+        }
+
+        let l = rotate_left(gray_encode(w), dir + 1, dims) ^ entry;
+        for (j, c) in coords.iter_mut().enumerate() {
+            *c |= ((l >> j) & 1) << i;
+        }
+
+        entry ^= rotate_left(entry_point(w), dir + 1, dims);
+        dir = (dir + direction(w, dims) + 1) % dims;
+    }
+
+    coords
+}
+
+fn assert_precisions(precisions: &[u32]) {
+    assert!(
+        !precisions.is_empty() && precisions.len() <= usize::BITS as usize,
+        "the number of dimensions must be between 1 and the width of usize"
+    );
+    assert!(
+        precisions.iter().sum::<u32>() <= usize::BITS,
+        "the sum of precisions must not exceed the width of usize"
+    );
+}
+
+// The axes which still have bits at the given level
+fn extract_mask(precisions: &[u32], level: u32) -> usize {
+    precisions
+        .iter()
+        .enumerate()
+        .filter(|&(_, &m)| m > level)
+        .fold(0, |mask, (j, _)| mask | 1 << j)
+}
+
+// The entry corner of the sub-hypercube with the given Gray code rank
+fn entry_point(w: usize) -> usize {
+    if w == 0 {
+        0
+    } else {
+        gray_encode(2 * ((w - 1) / 2))
+    }
+}
+
+// The axis along which the sub-hypercube with the given Gray code rank is traversed
+fn direction(w: usize, dims: u32) -> u32 {
+    if w == 0 {
+        0
+    } else if w % 2 == 0 {
+        (w - 1).trailing_ones() % dims
+    } else {
+        w.trailing_ones() % dims
+    }
+}
+
+fn rotate_right(x: usize, shift: u32, bits: u32) -> usize {
+    let shift = shift % bits;
+    let mask = low_mask(bits);
+    if shift == 0 {
+        x & mask
+    } else {
+        ((x >> shift) | (x << (bits - shift))) & mask
+    }
+}
+
+fn rotate_left(x: usize, shift: u32, bits: u32) -> usize {
+    rotate_right(x, bits - shift % bits, bits)
+}
+
+fn low_mask(bits: u32) -> usize {
+    if bits >= usize::BITS {
+        usize::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    // all the points of a box with the given precisions
+    fn points(precisions: &[u32]) -> Vec<Vec<usize>> {
+        let total: u32 = precisions.iter().sum();
+        (0..1usize << total)
+            .map(|mut k| {
+                precisions
+                    .iter()
+                    .map(|&m| {
+                        let c = k & ((1 << m) - 1);
+                        k >>= m;
+                        c
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reversibility() {
+        for precisions in &[
+            &[3][..],
+            &[2, 2],
+            &[3, 1],
+            &[0, 4],
+            &[2, 3, 1],
+            &[1, 1, 2, 2],
+        ] {
+            for point in points(precisions) {
+                let h = convert_nd_to_compact(&point, precisions);
+                assert_eq!(convert_compact_to_nd(h, precisions), point);
+            }
+        }
+    }
+
+    #[test]
+    fn full_curve_adjacency() {
+        for precisions in &[&[4, 4][..], &[3, 3, 3], &[2, 2, 2, 2]] {
+            let total: u32 = precisions.iter().sum();
+            let mut prev = convert_compact_to_nd(0, precisions);
+            for h in 1..1 << total {
+                let curr = convert_compact_to_nd(h, precisions);
+                let dist: usize = prev.iter().zip(&curr).map(|(&a, &b)| a.abs_diff(b)).sum();
+                assert_eq!(dist, 1);
+                prev = curr;
+            }
+        }
+    }
+
+    #[test]
+    fn same_order_as_full_curve() {
+        for precisions in &[&[3, 1][..], &[4, 2], &[1, 3, 2]] {
+            let max = *precisions.iter().max().unwrap();
+            let full = vec![max; precisions.len()];
+            let mut points = points(precisions);
+            points.sort_by_key(|point| convert_nd_to_compact(point, &full));
+            for (rank, point) in points.iter().enumerate() {
+                assert_eq!(convert_nd_to_compact(point, precisions), rank);
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Mapping between the unit interval and the unit square, approximating the limit of the Hilbert
+//! curve with a curve of a given order.
+//!
+//! A distance `t` in [0, 1) is located on the polyline connecting the centers of consecutive
+//! cells, so that the mapping is continuous and converges to the limit curve as the order grows.
+
+use crate::{next_point, HilbertCurve};
+
+/// The highest order for which the distances of all the cells are exactly representable.
+pub const MAX_ORDER: u32 = (f64::MANTISSA_DIGITS - 1) / 2;
+
+/// Map a distance `t` in [0, 1) to a point in [0, 1)², using a curve of the given order.
+///
+/// # Panics
+///
+/// Panics if `t` is outside [0, 1) or `order` is greater than [`MAX_ORDER`].
+pub fn convert_1d_to_2d_f64(t: f64, order: u32) -> (f64, f64) {
+    assert!((0.0..1.0).contains(&t), "t must be within [0, 1)");
+    assert!(order <= MAX_ORDER, "order is too large for f64 precision");
+    let curve = HilbertCurve::new(order);
+    let side = curve.side() as f64;
+
+    let pos = t * curve.len() as f64;
+    let d = (pos as usize).min(curve.len() - 1);
+    let frac = pos - d as f64;
+    let (x, y) = curve.d_to_xy(d);
+    let (nx, ny) = next_point(x, y, d, curve.side()).unwrap_or((x, y));
+
+    let x = x as f64 + 0.5 + frac * (nx as f64 - x as f64);
+    let y = y as f64 + 0.5 + frac * (ny as f64 - y as f64);
+    (x / side, y / side)
+}
+
+/// Map a point in [0, 1)² to a distance in [0, 1), using a curve of the given order; the result
+/// is the distance of the center of the cell containing the point.
+///
+/// # Panics
+///
+/// Panics if either coordinate is outside [0, 1) or `order` is greater than [`MAX_ORDER`].
+pub fn convert_2d_to_1d_f64(x: f64, y: f64, order: u32) -> f64 {
+    assert!(
+        (0.0..1.0).contains(&x) && (0.0..1.0).contains(&y),
+        "coordinates must be within [0, 1)"
+    );
+    assert!(order <= MAX_ORDER, "order is too large for f64 precision");
+    let curve = HilbertCurve::new(order);
+    let side = curve.side() as f64;
+
+    let cx = ((x * side) as usize).min(curve.side() - 1);
+    let cy = ((y * side) as usize).min(curve.side() - 1);
+    curve.xy_to_d(cx, cy) as f64 / curve.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_centers() {
+        let order = 3;
+        let curve = HilbertCurve::new(order);
+        for (d, x, y) in curve.iter() {
+            let t = d as f64 / curve.len() as f64;
+            let (px, py) = convert_1d_to_2d_f64(t, order);
+            assert_eq!((px, py), ((x as f64 + 0.5) / 8.0, (y as f64 + 0.5) / 8.0));
+            assert_eq!(convert_2d_to_1d_f64(px, py, order), t);
+        }
+    }
+
+    #[test]
+    fn interpolation() {
+        // halfway between (0, 0) and (1, 0) on a 4x4 grid
+        assert_eq!(convert_1d_to_2d_f64(0.5 / 16.0, 2), (0.25, 0.125));
+        assert_eq!(convert_1d_to_2d_f64(0.0, 0), (0.5, 0.5));
+    }
+}
@@ -0,0 +1,362 @@
+//! A Hilbert curve with a fixed, validated size.
+
+use core::{convert::TryFrom, ops::Range};
+
+use crate::{
+    convert_1d_to_2d, convert_2d_to_1d, iter, CoordinateSystem, Direction, GridPoint, HilbertError,
+    HilbertIndex, Iter, Orientation,
+};
+
+/// A Hilbert curve filling a square grid of `2^order` by `2^order` cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HilbertCurve {
+    order: u32,
+    orientation: Orientation,
+    coordinates: CoordinateSystem,
+}
+
+impl HilbertCurve {
+    /// The highest supported order; the length of the curve must fit in a `usize`.
+    pub const MAX_ORDER: u32 = usize::BITS / 2 - 1;
+
+    /// Create a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        match Self::try_new(order) {
+            Ok(curve) => curve,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create a curve of the given order; equivalent to [`HilbertCurve::new`].
+    pub fn from_order(order: u32) -> Self {
+        Self::new(order)
+    }
+
+    /// Create a curve filling an n by n grid, returning an error if `n` is not a power of 2 or
+    /// the curve would be too large.
+    pub fn try_from_side(n: usize) -> Result<Self, HilbertError> {
+        if !n.is_power_of_two() {
+            return Err(HilbertError::NotPowerOfTwo { n });
+        }
+
+        Self::try_new(n.trailing_zeros())
+    }
+
+    /// Create a curve of the given order, returning an error if it is too large.
+    pub fn try_new(order: u32) -> Result<Self, HilbertError> {
+        if order > Self::MAX_ORDER {
+            return Err(HilbertError::OrderTooLarge { order });
+        }
+
+        Ok(HilbertCurve {
+            order,
+            orientation: Orientation::default(),
+            coordinates: CoordinateSystem::default(),
+        })
+    }
+
+    /// Use the given orientation instead of the default one.
+    pub fn with_orientation(self, orientation: Orientation) -> Self {
+        HilbertCurve {
+            orientation,
+            ..self
+        }
+    }
+
+    /// The orientation of the curve.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Use the given coordinate system instead of the default one.
+    pub fn with_coordinate_system(self, coordinates: CoordinateSystem) -> Self {
+        HilbertCurve {
+            coordinates,
+            ..self
+        }
+    }
+
+    /// The coordinate system of the curve.
+    pub fn coordinate_system(&self) -> CoordinateSystem {
+        self.coordinates
+    }
+
+    /// The order of the curve, i.e. the number of bits per axis.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        1 << self.order
+    }
+
+    /// The number of cells visited by the curve.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        1 << (2 * self.order)
+    }
+
+    /// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is not lower than [`HilbertCurve::len`].
+    pub fn d_to_xy(&self, d: usize) -> (usize, usize) {
+        assert!(
+            d < self.len(),
+            "d must be lower than the length of the curve"
+        );
+        let (x, y) = convert_1d_to_2d(d, self.side());
+        let (x, y) = self.orientation.apply(x, y, self.side());
+        self.coordinates.convert(x, y, self.side())
+    }
+
+    /// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is not lower than [`HilbertCurve::side`].
+    pub fn xy_to_d(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.side() && y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        let (x, y) = self.coordinates.convert(x, y, self.side());
+        let (x, y) = self.orientation.revert(x, y, self.side());
+        convert_2d_to_1d(x, y, self.side())
+    }
+
+    /// Convert a [`HilbertIndex`] to the [`GridPoint`] it corresponds to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not lower than [`HilbertCurve::len`].
+    pub fn index_to_point(&self, index: HilbertIndex) -> GridPoint {
+        let d = usize::try_from(index.0).expect("index must be lower than the length of the curve");
+        self.d_to_xy(d).into()
+    }
+
+    /// Convert a [`GridPoint`] to its [`HilbertIndex`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate is not lower than [`HilbertCurve::side`].
+    pub fn point_to_index(&self, point: GridPoint) -> HilbertIndex {
+        HilbertIndex(self.xy_to_d(point.x, point.y) as u64)
+    }
+
+    // The offset from the cell at distance `d` to the next one
+    pub(crate) fn step(&self, d: usize) -> (isize, isize) {
+        let step = self.orientation.apply_step(iter::step(d, self.order));
+        self.coordinates.convert_step(step)
+    }
+
+    /// Get the direction in which the curve moves from the cell at distance `d` to the next one;
+    /// returns `None` if `d` is the end of the curve.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is not lower than [`HilbertCurve::len`].
+    pub fn heading_at(&self, d: usize) -> Option<Direction> {
+        assert!(
+            d < self.len(),
+            "d must be lower than the length of the curve"
+        );
+        if d + 1 == self.len() {
+            return None;
+        }
+
+        Some(Direction::from_step(self.step(d)))
+    }
+
+    /// Iterate over all the cells of the grid in curve order, yielding `(d, x, y)`.
+    pub fn iter(&self) -> Iter {
+        Iter::new(self, 0..self.len())
+    }
+
+    /// Iterate over the cells of the given segment of the curve, yielding `(d, x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past [`HilbertCurve::len`].
+    pub fn range(&self, range: Range<usize>) -> Iter {
+        Iter::new(self, range)
+    }
+}
+
+// the order is validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HilbertCurve {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "HilbertCurve")]
+        struct Raw {
+            order: u32,
+            orientation: Orientation,
+            coordinates: CoordinateSystem,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        let curve = HilbertCurve::try_new(raw.order).map_err(serde::de::Error::custom)?;
+        Ok(curve
+            .with_orientation(raw.orientation)
+            .with_coordinate_system(raw.coordinates))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, Corner};
+
+    #[test]
+    fn dimensions() {
+        let curve = HilbertCurve::new(3);
+        assert_eq!(curve.order(), 3);
+        assert_eq!(curve.side(), 8);
+        assert_eq!(curve.len(), 64);
+        assert_eq!(HilbertCurve::new(0).len(), 1);
+        assert_eq!(
+            HilbertCurve::try_new(HilbertCurve::MAX_ORDER + 1),
+            Err(HilbertError::OrderTooLarge {
+                order: HilbertCurve::MAX_ORDER + 1
+            })
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(HilbertCurve::from_order(5), HilbertCurve::new(5));
+        assert_eq!(HilbertCurve::try_from_side(32), Ok(HilbertCurve::new(5)));
+        assert_eq!(HilbertCurve::try_from_side(1), Ok(HilbertCurve::new(0)));
+        assert_eq!(
+            HilbertCurve::try_from_side(12),
+            Err(HilbertError::NotPowerOfTwo { n: 12 })
+        );
+    }
+
+    #[test]
+    fn orientation() {
+        let side = 8;
+        for &start in &[
+            Corner::LowerLeft,
+            Corner::LowerRight,
+            Corner::UpperLeft,
+            Corner::UpperRight,
+        ] {
+            for &towards in &[Axis::X, Axis::Y] {
+                let curve = HilbertCurve::new(3).with_orientation(Orientation::new(start, towards));
+                let first = curve.d_to_xy(0);
+                let last = curve.d_to_xy(curve.len() - 1);
+                let corner = match start {
+                    Corner::LowerLeft => (0, 0),
+                    Corner::LowerRight => (side - 1, 0),
+                    Corner::UpperLeft => (0, side - 1),
+                    Corner::UpperRight => (side - 1, side - 1),
+                };
+                assert_eq!(first, corner);
+                match towards {
+                    Axis::X => assert_eq!((last.0 + first.0, last.1), (side - 1, first.1)),
+                    Axis::Y => assert_eq!((last.0, last.1 + first.1), (first.0, side - 1)),
+                }
+                for (d, x, y) in curve.iter() {
+                    assert_eq!(curve.d_to_xy(d), (x, y));
+                    assert_eq!(curve.xy_to_d(x, y), d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn coordinate_system() {
+        let up = HilbertCurve::new(3);
+        let down = up.with_coordinate_system(CoordinateSystem::YDown);
+        for ((d, x, y), (_, dx, dy)) in up.iter().zip(down.iter()) {
+            assert_eq!((dx, dy), (x, 7 - y));
+            assert_eq!(down.xy_to_d(dx, dy), d);
+        }
+
+        let image = up
+            .with_orientation(Orientation::new(Corner::UpperLeft, Axis::X))
+            .with_coordinate_system(CoordinateSystem::YDown);
+        assert_eq!(image.d_to_xy(0), (0, 0));
+        assert_eq!(image.d_to_xy(image.len() - 1), (7, 0));
+        assert_eq!(image.heading_at(0), Some(Direction::Up));
+        assert_eq!(image.heading_at(image.len() - 1), None);
+    }
+
+    #[test]
+    fn typed() {
+        let curve = HilbertCurve::new(2);
+        let point = curve.index_to_point(HilbertIndex(5));
+        assert_eq!(point, GridPoint::new(0, 3));
+        assert_eq!(curve.point_to_index(point), HilbertIndex(5));
+        assert_eq!(
+            curve.index_to_point(HilbertIndex(5) + 1),
+            GridPoint::new(1, 3)
+        );
+        assert_eq!(HilbertIndex(9) - HilbertIndex(5), 4);
+    }
+
+    #[test]
+    fn byte_keys() {
+        assert_eq!(HilbertIndex::byte_width(0), 0);
+        assert_eq!(HilbertIndex::byte_width(4), 1);
+        assert_eq!(HilbertIndex::byte_width(5), 2);
+        assert_eq!(HilbertIndex(0x0102).to_be_bytes::<3>(), [0, 1, 2]);
+        assert_eq!(HilbertIndex::from_be_bytes([0, 1, 2]), HilbertIndex(0x0102));
+
+        let curve = HilbertCurve::new(5);
+        let mut keys: Vec<[u8; 2]> = (0..curve.len())
+            .rev()
+            .map(|d| HilbertIndex(d as u64).to_be_bytes())
+            .collect();
+        keys.sort_unstable();
+        for (d, key) in keys.into_iter().enumerate() {
+            assert_eq!(HilbertIndex::from_be_bytes(key), HilbertIndex(d as u64));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use crate::{CellId, HilbertKey};
+
+        let curve =
+            HilbertCurve::new(5).with_orientation(Orientation::new(Corner::UpperRight, Axis::Y));
+        let json = serde_json::to_string(&curve).unwrap();
+        assert_eq!(serde_json::from_str::<HilbertCurve>(&json).unwrap(), curve);
+        let invalid = json.replace(":5,", ":40,");
+        assert!(serde_json::from_str::<HilbertCurve>(&invalid).is_err());
+
+        let key = HilbertKey::new(3, 1, 2);
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(serde_json::from_str::<HilbertKey>(&json).unwrap(), key);
+        assert!(serde_json::from_str::<HilbertKey>(r#"{"order":1,"d":4}"#).is_err());
+
+        let cell = CellId::from_distance(7, 3);
+        let json = serde_json::to_string(&cell).unwrap();
+        assert_eq!(serde_json::from_str::<CellId>(&json).unwrap(), cell);
+        assert!(serde_json::from_str::<CellId>("2").is_err());
+
+        let point = GridPoint::new(4, 2);
+        assert_eq!(serde_json::to_string(&point).unwrap(), r#"{"x":4,"y":2}"#);
+        assert_eq!(serde_json::to_string(&HilbertIndex(9)).unwrap(), "9");
+    }
+
+    #[test]
+    fn reversibility() {
+        let curve = HilbertCurve::new(4);
+        for d in 0..curve.len() {
+            let (x, y) = curve.d_to_xy(d);
+            assert_eq!((x, y), convert_1d_to_2d(d, 16));
+            assert_eq!(curve.xy_to_d(x, y), d);
+        }
+    }
+}
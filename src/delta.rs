@@ -0,0 +1,115 @@
+//! Delta compression of sequences of curve distances.
+//!
+//! Every distance is stored as its difference to the previous one (the first one to 0), zigzag
+//! coded so that small decreases stay small too, as an LEB128 varint of 7 bits per byte. The
+//! differences of sorted distances of nearby cells are small, so they mostly take a byte or two
+//! each; any sequence can be encoded, but unsorted ones compress worse.
+
+use alloc::vec::Vec;
+
+/// Encode a sequence of distances, ideally sorted, into bytes.
+pub fn encode_keys(keys: &[u64]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(keys.len());
+    encode_keys_into(keys, &mut bytes);
+    bytes
+}
+
+/// Encode a sequence of distances like [`encode_keys`], appending the bytes to `out`.
+pub fn encode_keys_into(keys: &[u64], out: &mut Vec<u8>) {
+    let mut previous = 0u64;
+    for &key in keys {
+        // the wrapping difference is exact modulo 2^64, so it restores any key
+        let delta = key.wrapping_sub(previous) as i64;
+        let mut zigzag = ((delta << 1) ^ (delta >> 63)) as u64;
+        while zigzag >= 0x80 {
+            out.push(zigzag as u8 | 0x80);
+            zigzag >>= 7;
+        }
+        out.push(zigzag as u8);
+        previous = key;
+    }
+}
+
+/// Decode the bytes created by [`encode_keys`] back to the distances; returns `None` if they end
+/// in the middle of a varint or a varint overflows 64 bits.
+pub fn decode_keys(bytes: &[u8]) -> Option<Vec<u64>> {
+    let mut keys = Vec::new();
+    let (mut previous, mut zigzag, mut shift) = (0u64, 0u64, 0);
+    for &byte in bytes {
+        let bits = (byte & 0x7f) as u64;
+        if shift == 63 && bits > 1 {
+            return None;
+        }
+        zigzag |= bits << shift;
+        if byte & 0x80 != 0 {
+            shift += 7;
+            if shift > 63 {
+                return None;
+            }
+            continue;
+        }
+
+        let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        previous = previous.wrapping_add(delta as u64);
+        keys.push(previous);
+        (zigzag, shift) = (0, 0);
+    }
+
+    if shift != 0 {
+        return None;
+    }
+    Some(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranges_for_rect;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(encode_keys(&[]), Vec::<u8>::new());
+        assert_eq!(decode_keys(&[]), Some(Vec::new()));
+        assert_eq!(encode_keys(&[0, 1, 3, 2, 66]), [0, 2, 4, 1, 0x80, 1]);
+
+        let keys = [5, u64::MAX, 0, 1 << 63, 17, 16, 16];
+        assert_eq!(decode_keys(&encode_keys(&keys)), Some(keys.to_vec()));
+        // a wrapping difference of -1 takes a single byte
+        assert_eq!(encode_keys(&[u64::MAX]).len(), 1);
+        assert_eq!(encode_keys(&[1 << 63]).len(), 10);
+
+        let mut out = vec![9];
+        encode_keys_into(&[3], &mut out);
+        assert_eq!(out, [9, 6]);
+
+        assert_eq!(decode_keys(&[0x80]), None);
+        assert_eq!(decode_keys(&[0xff; 10]), None);
+        assert_eq!(
+            decode_keys(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 2]),
+            None
+        );
+        assert_eq!(
+            decode_keys(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 1]),
+            Some(vec![1 << 63])
+        );
+    }
+
+    #[test]
+    fn compression() {
+        // the cells in a block of the grid take little more than a byte each
+        let n = 1 << 16;
+        let mut keys: Vec<u64> = ranges_for_rect(1000, 2000, 1099, 2099, n, usize::MAX)
+            .into_iter()
+            .flatten()
+            .map(|d| d as u64)
+            .collect();
+        assert_eq!(keys.len(), 100 * 100);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        let bytes = encode_keys(&keys);
+        assert!(bytes.len() < keys.len() * 5 / 4);
+        assert_eq!(decode_keys(&bytes), Some(keys.clone()));
+
+        keys.reverse();
+        assert_eq!(decode_keys(&encode_keys(&keys)), Some(keys));
+    }
+}
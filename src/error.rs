@@ -0,0 +1,55 @@
+//! The error type returned by the fallible conversions.
+
+use core::{error, fmt};
+
+/// An error caused by invalid conversion inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HilbertError {
+    /// The side length `n` is not a power of 2.
+    NotPowerOfTwo { n: usize },
+    /// The distance `d` is not lower than `n^2`.
+    DistanceOutOfRange { d: usize, n: usize },
+    /// At least one of the coordinates is not lower than `n`.
+    CoordinateOutOfRange { x: usize, y: usize, n: usize },
+    /// The curve order is too large for its length to fit in a `usize`.
+    OrderTooLarge { order: u32 },
+    /// The rectangle spanning `x0..=x1` and `y0..=y1` has no cells.
+    EmptyRect {
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+    },
+    /// The maximum number of ranges of a decomposition is 0.
+    ZeroMaxRanges,
+}
+
+impl fmt::Display for HilbertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HilbertError::NotPowerOfTwo { n } => write!(f, "{} is not a power of 2", n),
+            HilbertError::DistanceOutOfRange { d, n } => {
+                write!(f, "distance {} is out of range for a {}x{} grid", d, n, n)
+            }
+            HilbertError::CoordinateOutOfRange { x, y, n } => {
+                write!(
+                    f,
+                    "coordinates ({}, {}) are out of range for a {}x{} grid",
+                    x, y, n, n
+                )
+            }
+            HilbertError::OrderTooLarge { order } => write!(f, "order {} is too large", order),
+            HilbertError::EmptyRect { x0, y0, x1, y1 } => {
+                write!(
+                    f,
+                    "the rectangle from ({}, {}) to ({}, {}) is empty",
+                    x0, y0, x1, y1
+                )
+            }
+            HilbertError::ZeroMaxRanges => write!(f, "the maximum number of ranges is 0"),
+        }
+    }
+}
+
+impl error::Error for HilbertError {}
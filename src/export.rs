@@ -0,0 +1,95 @@
+//! Streaming exports of the whole mapping from distances to cells.
+//!
+//! The cells are produced in curve order and written as they go, so exporting a curve of any
+//! order takes constant memory.
+
+use std::io::{self, BufWriter, Write};
+
+use crate::HilbertCurve;
+
+/// The layout of an exported mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MappingFormat {
+    /// A `d,x,y` header followed by a `d,x,y` line per distance.
+    Csv,
+    /// The little-endian x and y coordinates of every distance in curve order, each taking
+    /// [`coordinate_width`] bytes; the distances are implied by the positions of the cells.
+    Binary,
+}
+
+/// Get the number of bytes taken by each coordinate in the [`MappingFormat::Binary`] export of a
+/// curve of the given order, i.e. the least of 1, 2 or 4 bytes holding `order` bits.
+pub fn coordinate_width(order: u32) -> usize {
+    match order {
+        0..=8 => 1,
+        9..=16 => 2,
+        _ => 4,
+    }
+}
+
+/// Write the coordinates of every distance of the curve of the given order to `writer` in the
+/// given format; the writes are buffered internally.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn write_mapping<W: Write>(order: u32, format: MappingFormat, writer: W) -> io::Result<()> {
+    let curve = HilbertCurve::new(order);
+    let mut writer = BufWriter::new(writer);
+
+    match format {
+        MappingFormat::Csv => {
+            writeln!(writer, "d,x,y")?;
+            for (d, x, y) in curve.iter() {
+                writeln!(writer, "{},{},{}", d, x, y)?;
+            }
+        }
+        MappingFormat::Binary => {
+            let width = coordinate_width(order);
+            for (_, x, y) in curve.iter() {
+                writer.write_all(&(x as u32).to_le_bytes()[..width])?;
+                writer.write_all(&(y as u32).to_le_bytes()[..width])?;
+            }
+        }
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn csv() {
+        let mut out = Vec::new();
+        write_mapping(1, MappingFormat::Csv, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "d,x,y\n0,0,0\n1,0,1\n2,1,1\n3,1,0\n"
+        );
+    }
+
+    #[test]
+    fn binary() {
+        for order in [3, 9] {
+            let mut out = Vec::new();
+            write_mapping(order, MappingFormat::Binary, &mut out).unwrap();
+            let width = coordinate_width(order);
+            let n = 1 << order;
+            assert_eq!(out.len(), n * n * 2 * width);
+            for (d, cell) in out.chunks_exact(2 * width).enumerate() {
+                let read = |bytes: &[u8]| {
+                    bytes
+                        .iter()
+                        .rev()
+                        .fold(0, |value, &byte| value << 8 | byte as usize)
+                };
+                let (x, y) = cell.split_at(width);
+                assert_eq!((read(x), read(y)), convert_1d_to_2d(d, n));
+            }
+        }
+    }
+}
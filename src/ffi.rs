@@ -0,0 +1,248 @@
+//! A C interface to the conversions and range decompositions.
+//!
+//! The functions return [`HILBERT_OK`] on success and one of the negative `HILBERT_ERR_*` codes
+//! otherwise, writing their results through the given pointers (the outputs of the batch
+//! functions may be partially written when they fail); the coordinates of the batch functions
+//! are interleaved as `[x0, y0, x1, y1, ...]`. The crate can be built as a C library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`), and the signatures
+//! are plain enough for `cbindgen` to generate a header from this module.
+
+use alloc::vec::Vec;
+use core::{convert::TryFrom, ops::Range, slice};
+
+use crate::{try_convert_1d_to_2d, try_convert_2d_to_1d, try_ranges_for_rect, HilbertError};
+
+/// The call succeeded.
+pub const HILBERT_OK: i32 = 0;
+/// A required pointer is null.
+pub const HILBERT_ERR_NULL: i32 = -1;
+/// The side `n` is not a power of 2 or is too large.
+pub const HILBERT_ERR_INVALID_SIDE: i32 = -2;
+/// A distance or coordinate is outside the curve or the grid.
+pub const HILBERT_ERR_OUT_OF_RANGE: i32 = -3;
+/// The output buffer is too small; the required number of elements is reported.
+pub const HILBERT_ERR_BUFFER_TOO_SMALL: i32 = -4;
+/// Another argument is invalid.
+pub const HILBERT_ERR_INVALID_ARGUMENT: i32 = -5;
+
+/// Convert a distance `d` to the coordinates of its cell, written to `x` and `y`.
+///
+/// # Safety
+///
+/// `x` and `y` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn hilbert_d2xy(d: u64, n: u64, x: *mut u64, y: *mut u64) -> i32 {
+    if x.is_null() || y.is_null() {
+        return HILBERT_ERR_NULL;
+    }
+    let mut xy = [0; 2];
+    let status = hilbert_d2xy_batch(&d, 1, n, xy.as_mut_ptr());
+    if status == HILBERT_OK {
+        *x = xy[0];
+        *y = xy[1];
+    }
+    status
+}
+
+/// Convert the coordinates of a cell to its distance, written to `d`.
+///
+/// # Safety
+///
+/// `d` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn hilbert_xy2d(x: u64, y: u64, n: u64, d: *mut u64) -> i32 {
+    hilbert_xy2d_batch([x, y].as_ptr(), 1, n, d)
+}
+
+/// Convert `len` distances to the interleaved coordinates of their cells, written to the `2 * len`
+/// elements of `xy`.
+///
+/// # Safety
+///
+/// `ds` must be null or valid for reads of `len` elements and `xy` must be null or valid for
+/// writes of `2 * len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn hilbert_d2xy_batch(
+    ds: *const u64,
+    len: usize,
+    n: u64,
+    xy: *mut u64,
+) -> i32 {
+    if ds.is_null() || xy.is_null() {
+        return HILBERT_ERR_NULL;
+    }
+    let ds = slice::from_raw_parts(ds, len);
+    let xy = slice::from_raw_parts_mut(xy, 2 * len);
+    status(d2xy_batch(ds, n, xy))
+}
+
+/// Convert `len` pairs of interleaved coordinates to the distances of their cells, written to the
+/// `len` elements of `ds`.
+///
+/// # Safety
+///
+/// `xy` must be null or valid for reads of `2 * len` elements and `ds` must be null or valid for
+/// writes of `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn hilbert_xy2d_batch(
+    xy: *const u64,
+    len: usize,
+    n: u64,
+    ds: *mut u64,
+) -> i32 {
+    if xy.is_null() || ds.is_null() {
+        return HILBERT_ERR_NULL;
+    }
+    let xy = slice::from_raw_parts(xy, 2 * len);
+    let ds = slice::from_raw_parts_mut(ds, len);
+    status(xy2d_batch(xy, n, ds))
+}
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into ranges of distances, as
+/// described by [`ranges_for_rect`](crate::ranges_for_rect), written to `ranges` as interleaved
+/// `[start, end)` bounds; the number of ranges is written to `count`, also when `capacity` (the
+/// number of ranges `ranges` can hold) is too small and [`HILBERT_ERR_BUFFER_TOO_SMALL`] is
+/// returned.
+///
+/// # Safety
+///
+/// `ranges` must be null or valid for writes of `2 * capacity` elements and `count` must be null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn hilbert_ranges_for_rect(
+    x0: u64,
+    y0: u64,
+    x1: u64,
+    y1: u64,
+    n: u64,
+    max_ranges: usize,
+    ranges: *mut u64,
+    capacity: usize,
+    count: *mut usize,
+) -> i32 {
+    if ranges.is_null() || count.is_null() {
+        return HILBERT_ERR_NULL;
+    }
+    let decomposition = match rect_ranges([x0, y0, x1, y1], n, max_ranges) {
+        Ok(decomposition) => decomposition,
+        Err(status) => return status,
+    };
+    *count = decomposition.len();
+    if decomposition.len() > capacity {
+        return HILBERT_ERR_BUFFER_TOO_SMALL;
+    }
+
+    let ranges = slice::from_raw_parts_mut(ranges, 2 * decomposition.len());
+    for (range, out) in decomposition.iter().zip(ranges.chunks_exact_mut(2)) {
+        out.copy_from_slice(&[range.start as u64, range.end as u64]);
+    }
+    HILBERT_OK
+}
+
+fn d2xy_batch(ds: &[u64], n: u64, xy: &mut [u64]) -> Result<(), i32> {
+    let n = side(n)?;
+    for (&d, out) in ds.iter().zip(xy.chunks_exact_mut(2)) {
+        let (x, y) = try_convert_1d_to_2d(index(d)?, n).map_err(code)?;
+        out.copy_from_slice(&[x as u64, y as u64]);
+    }
+    Ok(())
+}
+
+fn xy2d_batch(xy: &[u64], n: u64, ds: &mut [u64]) -> Result<(), i32> {
+    let n = side(n)?;
+    for (pair, d) in xy.chunks_exact(2).zip(ds) {
+        let (x, y) = (index(pair[0])?, index(pair[1])?);
+        *d = try_convert_2d_to_1d(x, y, n).map_err(code)? as u64;
+    }
+    Ok(())
+}
+
+fn rect_ranges(rect: [u64; 4], n: u64, max_ranges: usize) -> Result<Vec<Range<usize>>, i32> {
+    let n = side(n)?;
+    let mut bounds = [0; 4];
+    for (bound, &c) in bounds.iter_mut().zip(&rect) {
+        *bound = index(c)?;
+    }
+    let [x0, y0, x1, y1] = bounds;
+    try_ranges_for_rect(x0, y0, x1, y1, n, max_ranges).map_err(code)
+}
+
+// The arguments are 64-bit on every target, so the values beyond a usize are rejected first
+fn side(n: u64) -> Result<usize, i32> {
+    usize::try_from(n).map_err(|_| HILBERT_ERR_INVALID_SIDE)
+}
+
+fn index(value: u64) -> Result<usize, i32> {
+    usize::try_from(value).map_err(|_| HILBERT_ERR_OUT_OF_RANGE)
+}
+
+fn status(result: Result<(), i32>) -> i32 {
+    result.err().unwrap_or(HILBERT_OK)
+}
+
+// The status code of an error of the validated conversions
+fn code(error: HilbertError) -> i32 {
+    match error {
+        HilbertError::NotPowerOfTwo { .. } | HilbertError::OrderTooLarge { .. } => {
+            HILBERT_ERR_INVALID_SIDE
+        }
+        HilbertError::DistanceOutOfRange { .. } | HilbertError::CoordinateOutOfRange { .. } => {
+            HILBERT_ERR_OUT_OF_RANGE
+        }
+        HilbertError::EmptyRect { .. } | HilbertError::ZeroMaxRanges => {
+            HILBERT_ERR_INVALID_ARGUMENT
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ptr;
+
+    #[test]
+    fn calls() {
+        unsafe {
+            let (mut x, mut y, mut d) = (0, 0, 0);
+            assert_eq!(hilbert_d2xy(5, 4, &mut x, &mut y), HILBERT_OK);
+            assert_eq!((x, y), (0, 3));
+            assert_eq!(hilbert_xy2d(x, y, 4, &mut d), HILBERT_OK);
+            assert_eq!(d, 5);
+            assert_eq!(
+                hilbert_d2xy(16, 4, &mut x, &mut y),
+                HILBERT_ERR_OUT_OF_RANGE
+            );
+            assert_eq!(hilbert_xy2d(1, 1, 3, &mut d), HILBERT_ERR_INVALID_SIDE);
+            assert_eq!(hilbert_xy2d(1, 1, 4, ptr::null_mut()), HILBERT_ERR_NULL);
+
+            let ds = [0, 1, 2, 3];
+            let mut xy = [0; 8];
+            assert_eq!(
+                hilbert_d2xy_batch(ds.as_ptr(), 4, 2, xy.as_mut_ptr()),
+                HILBERT_OK
+            );
+            assert_eq!(xy, [0, 0, 0, 1, 1, 1, 1, 0]);
+            let mut back = [0; 4];
+            assert_eq!(
+                hilbert_xy2d_batch(xy.as_ptr(), 4, 2, back.as_mut_ptr()),
+                HILBERT_OK
+            );
+            assert_eq!(back, ds);
+
+            let (mut ranges, mut count) = ([0; 4], 0);
+            let status =
+                hilbert_ranges_for_rect(0, 0, 1, 3, 4, 8, ranges.as_mut_ptr(), 2, &mut count);
+            assert_eq!((status, count), (HILBERT_OK, 1));
+            assert_eq!(ranges[..2], [0, 8]);
+            let status =
+                hilbert_ranges_for_rect(0, 0, 3, 0, 4, 8, ranges.as_mut_ptr(), 1, &mut count);
+            assert_eq!((status, count), (HILBERT_ERR_BUFFER_TOO_SMALL, 2));
+            let status =
+                hilbert_ranges_for_rect(0, 0, 4, 0, 4, 8, ranges.as_mut_ptr(), 2, &mut count);
+            assert_eq!(status, HILBERT_ERR_OUT_OF_RANGE);
+            let status =
+                hilbert_ranges_for_rect(1, 0, 0, 0, 4, 8, ranges.as_mut_ptr(), 2, &mut count);
+            assert_eq!(status, HILBERT_ERR_INVALID_ARGUMENT);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Mapping of fixed-point coordinates onto the grid using integer arithmetic only.
+//!
+//! A Qm.n value is an integer holding the value multiplied by 2^n, with m bits for its integral
+//! part and n for its fractional part; the whole range of a format can be mapped onto the grid
+//! with shifts alone, and arbitrary bounds given in a common format with an integer division.
+
+use crate::{convert_2d_to_1d, quantize::OutOfRange, HilbertCurve};
+
+/// A Qm.n fixed-point format, whose values are held by raw `i64` integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QFormat {
+    int_bits: u32,
+    frac_bits: u32,
+    signed: bool,
+}
+
+impl QFormat {
+    /// A signed format with `int_bits` integral bits and `frac_bits` fractional bits, as well as a
+    /// sign bit; its values are in [−2^m, 2^m).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format takes more than 64 bits.
+    pub fn signed(int_bits: u32, frac_bits: u32) -> Self {
+        assert!(
+            int_bits + frac_bits < 64,
+            "signed formats must take at most 64 bits"
+        );
+        QFormat {
+            int_bits,
+            frac_bits,
+            signed: true,
+        }
+    }
+
+    /// An unsigned format with `int_bits` integral bits and `frac_bits` fractional bits; its
+    /// values are in [0, 2^m).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format takes more than 63 bits.
+    pub fn unsigned(int_bits: u32, frac_bits: u32) -> Self {
+        assert!(
+            int_bits + frac_bits < 64,
+            "unsigned formats must take at most 63 bits"
+        );
+        QFormat {
+            int_bits,
+            frac_bits,
+            signed: false,
+        }
+    }
+
+    /// The number of integral bits.
+    pub fn int_bits(&self) -> u32 {
+        self.int_bits
+    }
+
+    /// The number of fractional bits.
+    pub fn frac_bits(&self) -> u32 {
+        self.frac_bits
+    }
+
+    /// Whether the format has a sign bit.
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// The number of bits taken by the format.
+    pub fn width(&self) -> u32 {
+        self.int_bits + self.frac_bits + self.signed as u32
+    }
+
+    // The offset bringing the lowest value of the format to 0
+    fn offset(&self) -> i128 {
+        if self.signed {
+            1 << (self.width() - 1)
+        } else {
+            0
+        }
+    }
+}
+
+/// Map the raw values (x, y) of the given format onto the grid of the curve of the given order,
+/// its whole range being split into equal cells; returns `None` if a value is out of the range of
+/// the format.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn fixed_to_cell(x: i64, y: i64, format: QFormat, order: u32) -> Option<(usize, usize)> {
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+    let axis = |v: i64| {
+        let unit = v as i128 + format.offset();
+        if !(0..1 << format.width()).contains(&unit) {
+            return None;
+        }
+        let width = format.width();
+        // the lowest bits are dropped, or padded if the grid is finer than the format
+        Some(if width >= order {
+            (unit >> (width - order)) as usize
+        } else {
+            (unit << (order - width)) as usize
+        })
+    };
+
+    Some((axis(x)?, axis(y)?))
+}
+
+/// Map the raw values (x, y) of the given format onto the grid like [`fixed_to_cell`] and convert
+/// the resulting cell to its one-dimensional distance.
+pub fn fixed_to_d(x: i64, y: i64, format: QFormat, order: u32) -> Option<usize> {
+    fixed_to_cell(x, y, format, order).map(|(cx, cy)| convert_2d_to_1d(cx, cy, 1 << order))
+}
+
+/// Get the raw values of the given format at the lower left corner of the cell (x, y) of the grid
+/// of the curve of the given order; the inverse of [`fixed_to_cell`] for the values it maps to
+/// that corner.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or the cell is outside the grid.
+pub fn cell_to_fixed(x: usize, y: usize, format: QFormat, order: u32) -> (i64, i64) {
+    let curve = HilbertCurve::new(order);
+    assert!(
+        x < curve.side() && y < curve.side(),
+        "coordinates must be lower than the side of the grid"
+    );
+    let axis = |c: usize| {
+        let width = format.width();
+        let unit = if width >= order {
+            (c as i128) << (width - order)
+        } else {
+            (c as i128) >> (order - width)
+        };
+        (unit - format.offset()) as i64
+    };
+
+    (axis(x), axis(y))
+}
+
+/// An axis-aligned rectangle of raw fixed-point values sharing a format, mapped onto the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedBounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl FixedBounds {
+    /// Create bounds with the given corners.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bounds are empty.
+    pub fn new(min_x: i64, min_y: i64, max_x: i64, max_y: i64) -> Self {
+        assert!(
+            min_x < max_x && min_y < max_y,
+            "the bounds must have a positive width and height"
+        );
+
+        FixedBounds {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+}
+
+/// Quantize the raw fixed-point values (x, y) to the coordinates of a cell of an n by n grid
+/// covering the given bounds, which are split into n equal, half-open cells per axis as with
+/// [`Rounding::Floor`](crate::quantize::Rounding::Floor); returns `None` if a coordinate is
+/// rejected as out of range. Since the values and the bounds share a format, the number of
+/// fractional bits doesn't matter.
+pub fn quantize_fixed(
+    x: i64,
+    y: i64,
+    bounds: &FixedBounds,
+    n: usize,
+    out_of_range: OutOfRange,
+) -> Option<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let cx = quantize_axis(x, bounds.min_x, bounds.max_x, n, out_of_range)?;
+    let cy = quantize_axis(y, bounds.min_y, bounds.max_y, n, out_of_range)?;
+
+    Some((cx, cy))
+}
+
+/// Quantize the raw fixed-point values (x, y) like [`quantize_fixed`] and convert the resulting
+/// cell to its one-dimensional distance.
+pub fn fixed_point_to_d(
+    x: i64,
+    y: i64,
+    bounds: &FixedBounds,
+    n: usize,
+    out_of_range: OutOfRange,
+) -> Option<usize> {
+    quantize_fixed(x, y, bounds, n, out_of_range).map(|(cx, cy)| convert_2d_to_1d(cx, cy, n))
+}
+
+fn quantize_axis(v: i64, min: i64, max: i64, n: usize, out_of_range: OutOfRange) -> Option<usize> {
+    if !(min..max).contains(&v) && out_of_range == OutOfRange::Reject {
+        return None;
+    }
+    let v = v.clamp(min, max - 1);
+    // the product fits, since the span takes at most 64 bits and n at most 32
+    Some(((v as i128 - min as i128) * n as i128 / (max as i128 - min as i128)) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_range() {
+        // Q3.4: values in [-8, 8) with a resolution of 1/16, i.e. 256 raw values
+        let format = QFormat::signed(3, 4);
+        assert_eq!(format.width(), 8);
+        assert_eq!(fixed_to_cell(-128, 127, format, 4), Some((0, 15)));
+        assert_eq!(fixed_to_cell(0, -1, format, 4), Some((8, 7)));
+        assert_eq!(fixed_to_cell(128, 0, format, 4), None);
+        assert_eq!(fixed_to_cell(-129, 0, format, 4), None);
+        // a grid finer than the format spreads the values out
+        assert_eq!(fixed_to_cell(-127, 0, format, 10), Some((4, 512)));
+        assert_eq!(
+            fixed_to_d(0, 0, format, 4),
+            Some(convert_2d_to_1d(8, 8, 16))
+        );
+
+        let format = QFormat::unsigned(8, 8);
+        for order in [0, 5, 16, 20] {
+            for &(x, y) in &[(0, 0), (1000, 65535), (40000, 12)] {
+                let (cx, cy) = fixed_to_cell(x, y, format, order).unwrap();
+                let (lx, ly) = cell_to_fixed(cx, cy, format, order);
+                assert!(lx <= x && ly <= y);
+                assert_eq!(fixed_to_cell(lx, ly, format, order), Some((cx, cy)));
+            }
+        }
+        assert_eq!(cell_to_fixed(0, 15, QFormat::signed(3, 4), 4), (-128, 112));
+    }
+
+    #[test]
+    fn bounds() {
+        // Q16.16 bounds from -1.5 to 2.5 on both axes
+        let bounds = FixedBounds::new(-3 << 15, -3 << 15, 5 << 15, 5 << 15);
+        let n = 8;
+        assert_eq!(
+            quantize_fixed(-3 << 15, 0, &bounds, n, OutOfRange::Reject),
+            Some((0, 3))
+        );
+        assert_eq!(
+            quantize_fixed(5 << 15, 0, &bounds, n, OutOfRange::Reject),
+            None
+        );
+        assert_eq!(
+            quantize_fixed(5 << 15, i64::MIN, &bounds, n, OutOfRange::Clamp),
+            Some((7, 0))
+        );
+        assert_eq!(
+            fixed_point_to_d(1 << 16, 1 << 16, &bounds, n, OutOfRange::Reject),
+            Some(convert_2d_to_1d(5, 5, n))
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Asynchronous streams of curve points and range decompositions.
+//!
+//! The streams yield their items in chunks, each one computed when the stream is polled, so the
+//! work is paced by the consumer and a slow sink holds it back. Since the chunks are always ready,
+//! a consumer polling the stream in a loop would never give up its thread; a stream therefore
+//! returns [`Poll::Pending`] once after every few chunks, waking its task right away, so that the
+//! executor can run the other tasks in between.
+
+use alloc::vec::Vec;
+use core::{
+    ops::Range,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use ::futures_core::{FusedStream, Stream};
+
+use crate::{rect_ranges, HilbertCurve, Iter, RectRanges};
+
+/// The default number of items per chunk.
+pub const DEFAULT_CHUNK_LEN: usize = 4096;
+
+/// The default number of chunks yielded between two returns of [`Poll::Pending`].
+pub const DEFAULT_YIELD_EVERY: usize = 16;
+
+/// A stream of the items of an iterator, in chunks.
+#[derive(Debug, Clone)]
+pub struct ChunkStream<I> {
+    iter: I,
+    chunk_len: usize,
+    yield_every: usize,
+    // the number of chunks yielded since the stream last returned `Poll::Pending`
+    streak: usize,
+    done: bool,
+}
+
+impl<I: Iterator> ChunkStream<I> {
+    /// Create a stream of the items of the given iterator, in chunks of [`DEFAULT_CHUNK_LEN`].
+    pub fn new(iter: I) -> Self {
+        ChunkStream {
+            iter,
+            chunk_len: DEFAULT_CHUNK_LEN,
+            yield_every: DEFAULT_YIELD_EVERY,
+            streak: 0,
+            done: false,
+        }
+    }
+
+    /// Use chunks of the given number of items; all the chunks but the last one are full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_len` is 0.
+    pub fn with_chunk_len(self, chunk_len: usize) -> Self {
+        assert!(chunk_len > 0, "chunks must not be empty");
+        ChunkStream { chunk_len, ..self }
+    }
+
+    /// Return [`Poll::Pending`] once after every `yield_every` chunks instead of
+    /// [`DEFAULT_YIELD_EVERY`]; 0 never does.
+    pub fn with_yield_every(self, yield_every: usize) -> Self {
+        ChunkStream {
+            yield_every,
+            ..self
+        }
+    }
+}
+
+impl<I: Iterator + Unpin> Stream for ChunkStream<I> {
+    type Item = Vec<I::Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        if self.yield_every != 0 && self.streak == self.yield_every {
+            self.streak = 0;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let chunk_len = self.chunk_len;
+        let chunk: Vec<_> = self.iter.by_ref().take(chunk_len).collect();
+        if chunk.is_empty() {
+            self.done = true;
+            return Poll::Ready(None);
+        }
+        self.streak += 1;
+
+        Poll::Ready(Some(chunk))
+    }
+}
+
+impl<I: Iterator + Unpin> FusedStream for ChunkStream<I> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// Stream the cells of the given segment of a curve, as `(d, x, y)`.
+///
+/// # Panics
+///
+/// Panics if the range is decreasing or extends past [`HilbertCurve::len`].
+pub fn point_stream(curve: &HilbertCurve, range: Range<usize>) -> ChunkStream<Iter> {
+    ChunkStream::new(curve.range(range))
+}
+
+/// Stream the ranges of distances covering the rectangle spanning `x0..=x1` and `y0..=y1`, as
+/// computed by [`rect_ranges`].
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or the rectangle is not within the grid.
+pub fn range_stream(
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    n: usize,
+) -> ChunkStream<RectRanges> {
+    ChunkStream::new(rect_ranges(x0, y0, x1, y1, n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranges_for_rect;
+    use core::task::Waker;
+
+    // poll the stream to its end, counting the returns of `Poll::Pending`
+    fn drain<S: Stream + Unpin>(mut stream: S) -> (Vec<S::Item>, usize) {
+        let mut cx = Context::from_waker(Waker::noop());
+        let (mut items, mut pending) = (Vec::new(), 0);
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(item)) => items.push(item),
+                Poll::Ready(None) => return (items, pending),
+                Poll::Pending => pending += 1,
+            }
+        }
+    }
+
+    #[test]
+    fn points() {
+        let curve = HilbertCurve::new(5);
+        let stream = point_stream(&curve, 10..1000)
+            .with_chunk_len(100)
+            .with_yield_every(3);
+        let (chunks, pending) = drain(stream);
+        assert_eq!(chunks.len(), 10);
+        assert_eq!(chunks[9].len(), 90);
+        assert_eq!(pending, 3);
+        let points: Vec<_> = chunks.into_iter().flatten().collect();
+        assert_eq!(points, curve.range(10..1000).collect::<Vec<_>>());
+
+        let (chunks, pending) = drain(point_stream(&curve, 0..0));
+        assert_eq!((chunks.len(), pending), (0, 0));
+        let (chunks, pending) = drain(point_stream(&curve, 0..1024).with_yield_every(0));
+        assert_eq!((chunks.len(), pending), (1, 0));
+    }
+
+    #[test]
+    fn ranges() {
+        let mut stream = range_stream(1, 0, 30, 17, 32).with_chunk_len(2);
+        let (chunks, _) = drain(&mut stream);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 2));
+        let ranges: Vec<_> = chunks.into_iter().flatten().collect();
+        assert_eq!(ranges, ranges_for_rect(1, 0, 30, 17, 32, usize::MAX));
+        assert!(stream.is_terminated());
+    }
+}
@@ -0,0 +1,137 @@
+//! Hilbert curve mapping generic over the integer type, so that the index width can be chosen
+//! independently of the target's `usize`.
+
+use num_traits::{PrimInt, Unsigned, WrappingSub};
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+pub fn convert_1d_to_2d<T: PrimInt + Unsigned + WrappingSub>(d: T, n: T) -> (T, T) {
+    assert!((n & (n - T::one())).is_zero(), "n must be a power of 2");
+    let one = T::one();
+    let mut s = one;
+    let mut t = d;
+    let (mut x, mut y) = (T::zero(), T::zero());
+    let (mut rx, mut ry);
+
+    while s < n {
+        rx = one & (t >> 1);
+        ry = one & (t ^ rx);
+        rotate(s, &mut x, &mut y, rx, ry);
+        x = x + s * rx;
+        y = y + s * ry;
+        t = t >> 2;
+        s = s << 1;
+    }
+
+    (x, y)
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+pub fn convert_2d_to_1d<T: PrimInt + Unsigned + WrappingSub>(x: T, y: T, n: T) -> T {
+    assert!((n & (n - T::one())).is_zero(), "n must be a power of 2");
+    let mut d = T::zero();
+    let mut s = n >> 1;
+    let (mut x, mut y) = (x, y);
+    let (mut rx, mut ry);
+
+    while !s.is_zero() {
+        rx = if (x & s).is_zero() {
+            T::zero()
+        } else {
+            T::one()
+        };
+        ry = if (y & s).is_zero() {
+            T::zero()
+        } else {
+            T::one()
+        };
+        d = d + s * s * ((T::from(3).unwrap() * rx) ^ ry);
+        rotate(s, &mut x, &mut y, rx, ry);
+        s = s >> 1;
+    }
+
+    d
+}
+
+/// Convert a `u32` distance `d` to a pair of (x, y) coordinates, with the same results on every
+/// target.
+pub fn convert_1d_to_2d_u32(d: u32, n: u32) -> (u32, u32) {
+    convert_1d_to_2d(d, n)
+}
+
+/// Convert a pair of (x, y) coordinates to a `u32` distance, with the same results on every
+/// target.
+pub fn convert_2d_to_1d_u32(x: u32, y: u32, n: u32) -> u32 {
+    convert_2d_to_1d(x, y, n)
+}
+
+/// Convert a `u64` distance `d` to a pair of (x, y) coordinates, with the same results on every
+/// target.
+pub fn convert_1d_to_2d_u64(d: u64, n: u64) -> (u64, u64) {
+    convert_1d_to_2d(d, n)
+}
+
+/// Convert a pair of (x, y) coordinates to a `u64` distance, with the same results on every
+/// target.
+pub fn convert_2d_to_1d_u64(x: u64, y: u64, n: u64) -> u64 {
+    convert_2d_to_1d(x, y, n)
+}
+
+/// Convert a `u128` distance `d` to a pair of (x, y) coordinates, supporting curves with up to
+/// 64 bits per axis (n up to `2^64`).
+pub fn convert_1d_to_2d_u128(d: u128, n: u128) -> (u128, u128) {
+    convert_1d_to_2d(d, n)
+}
+
+/// Convert a pair of (x, y) coordinates to a `u128` distance, supporting curves with up to 64
+/// bits per axis (n up to `2^64`).
+pub fn convert_2d_to_1d_u128(x: u128, y: u128, n: u128) -> u128 {
+    convert_2d_to_1d(x, y, n)
+}
+
+// Rotate a quadrant
+fn rotate<T: PrimInt + WrappingSub>(n: T, x: &mut T, y: &mut T, rx: T, ry: T) {
+    if ry.is_zero() {
+        if rx == T::one() {
+            *x = n.wrapping_sub(&T::one()).wrapping_sub(x);
+            *y = n.wrapping_sub(&T::one()).wrapping_sub(y);
+        }
+
+        core::mem::swap(x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_usize() {
+        for &n in &[1, 2, 4, 8, 16, 32, 64] {
+            for d in 0..(n * n) {
+                let (x, y) = crate::convert_1d_to_2d(d, n);
+                assert_eq!(convert_1d_to_2d(d as u16, n as u16), (x as u16, y as u16));
+                assert_eq!(
+                    convert_1d_to_2d(d as u128, n as u128),
+                    (x as u128, y as u128)
+                );
+                assert_eq!(convert_2d_to_1d(x as u32, y as u32, n as u32), d as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn full_width() {
+        let n = 1u32 << 16;
+        let (x, y) = convert_1d_to_2d_u32(u32::MAX, n);
+        assert_eq!(convert_2d_to_1d_u32(x, y, n), u32::MAX);
+
+        let n = 1u64 << 32;
+        let (x, y) = convert_1d_to_2d_u64(u64::MAX, n);
+        assert_eq!(convert_2d_to_1d_u64(x, y, n), u64::MAX);
+
+        let n = 1u128 << 64;
+        let (x, y) = convert_1d_to_2d_u128(u128::MAX, n);
+        assert_eq!((x, y), (n - 1, 0));
+        assert_eq!(convert_2d_to_1d_u128(x, y, n), u128::MAX);
+    }
+}
@@ -0,0 +1,287 @@
+//! Mapping of WGS84 latitudes and longitudes onto the grid of a Hilbert curve.
+//!
+//! Longitudes map to x and latitudes to y through an equirectangular projection of a bounding box,
+//! which may cross the antimeridian; longitudes are normalized to `-180.0..180.0` first, so that
+//! e.g. 190° and −170° are the same meridian.
+
+use core::{error, fmt};
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d, HilbertCurve};
+
+/// A geographic bounding box in degrees; `west` is greater than `east` if the box crosses the
+/// antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GeoBounds {
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub north: f64,
+}
+
+impl GeoBounds {
+    /// The whole globe.
+    pub const WORLD: GeoBounds = GeoBounds {
+        west: -180.0,
+        south: -90.0,
+        east: 180.0,
+        north: 90.0,
+    };
+
+    /// Create a bounding box with the given edges; the longitudes are normalized and the box
+    /// spans eastward from `west` to `east`, crossing the antimeridian if `east` is lower than
+    /// `west`, or covering all the longitudes if `east` is a whole turn east of `west`, as in
+    /// [`GeoBounds::WORLD`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the latitudes are not within `-90.0..=90.0` or `south` is not lower than `north`,
+    /// or if a longitude is not finite or `east` is the meridian of `west` but not east of it.
+    pub fn new(west: f64, south: f64, east: f64, north: f64) -> Self {
+        match Self::try_new(west, south, east, north) {
+            Ok(bounds) => bounds,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create a bounding box with the given edges like [`GeoBounds::new`], returning an error if
+    /// they are invalid.
+    pub fn try_new(west: f64, south: f64, east: f64, north: f64) -> Result<Self, InvalidGeoBounds> {
+        let latitudes = -90.0 <= south && south < north && north <= 90.0;
+        if !latitudes || !west.is_finite() || !east.is_finite() {
+            return Err(InvalidGeoBounds);
+        }
+        let (normal_west, normal_east) = (normalize(west), normalize(east));
+        let east = if normal_east != normal_west {
+            normal_east
+        } else if east > west {
+            normal_west + 360.0
+        } else {
+            return Err(InvalidGeoBounds);
+        };
+
+        Ok(GeoBounds {
+            west: normal_west,
+            south,
+            east,
+            north,
+        })
+    }
+
+    /// Whether the box crosses the antimeridian.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west > self.east
+    }
+
+    // The width of the box in degrees of longitude
+    fn width(&self) -> f64 {
+        if self.crosses_antimeridian() {
+            self.east + 360.0 - self.west
+        } else {
+            self.east - self.west
+        }
+    }
+
+    // The eastward offset of the given longitude from the western edge, in 0.0..360.0
+    fn offset(&self, lon: f64) -> f64 {
+        let offset = normalize(lon) - self.west;
+        if offset < 0.0 {
+            offset + 360.0
+        } else {
+            offset
+        }
+    }
+}
+
+// the edges are validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GeoBounds {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "GeoBounds")]
+        struct Raw {
+            west: f64,
+            south: f64,
+            east: f64,
+            north: f64,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        GeoBounds::try_new(raw.west, raw.south, raw.east, raw.north)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The error returned for bounding boxes with invalid edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidGeoBounds;
+
+impl fmt::Display for InvalidGeoBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "the latitudes must be increasing and within -90 to 90 degrees, and the longitudes \
+             finite and spanning a positive width",
+        )
+    }
+}
+
+impl error::Error for InvalidGeoBounds {}
+
+/// Convert a latitude and longitude in degrees to the distance of the cell containing them on the
+/// curve of the given order covering the bounding box; returns `None` if the position is outside
+/// the box or not finite. The northern and eastern edges belong to the last cells.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn lat_lon_to_d(lat: f64, lon: f64, bounds: &GeoBounds, order: u32) -> Option<usize> {
+    let n = side(order);
+    if !lat.is_finite() || !lon.is_finite() || lat < bounds.south || lat > bounds.north {
+        return None;
+    }
+
+    let mut offset = bounds.offset(lon);
+    // the eastern edge of the whole globe is its western one
+    if bounds.width() == 360.0 && offset == 0.0 && lon > bounds.west {
+        offset = 360.0;
+    } else if offset > bounds.width() {
+        return None;
+    }
+
+    let x = cell(offset / bounds.width(), n);
+    let y = cell((lat - bounds.south) / (bounds.north - bounds.south), n);
+
+    Some(convert_2d_to_1d(x, y, n))
+}
+
+/// Convert a distance `d` along the curve of the given order covering the bounding box to the
+/// latitude and longitude in degrees of the center of its cell.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than
+/// `4^order`.
+pub fn d_to_lat_lon(d: usize, bounds: &GeoBounds, order: u32) -> (f64, f64) {
+    let n = side(order);
+    assert!(d >> (2 * order) == 0, "d must be lower than 4^order");
+    let (x, y) = convert_1d_to_2d(d, n);
+    let center = |i: usize| (i as f64 + 0.5) / n as f64;
+
+    let lat = bounds.south + center(y) * (bounds.north - bounds.south);
+    let lon = normalize(bounds.west + center(x) * bounds.width());
+
+    (lat, lon)
+}
+
+// Normalize a finite longitude to -180.0..180.0
+fn normalize(lon: f64) -> f64 {
+    if (-180.0..180.0).contains(&lon) {
+        return lon;
+    }
+    // the truncating cast rounds the number of turns toward 0, which is corrected below
+    let lon = lon - 360.0 * ((lon + 180.0) / 360.0) as i64 as f64;
+    if lon < -180.0 {
+        lon + 360.0
+    } else if lon >= 180.0 {
+        lon - 360.0
+    } else {
+        lon
+    }
+}
+
+// The cell containing the given fraction of the side, with 1.0 belonging to the last cell
+fn cell(unit: f64, n: usize) -> usize {
+    ((unit * n as f64) as usize).min(n - 1)
+}
+
+fn side(order: u32) -> usize {
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+    1 << order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world() {
+        let world = GeoBounds::WORLD;
+        let order = 3;
+        assert_eq!(lat_lon_to_d(-90.0, -180.0, &world, order), Some(0));
+        assert_eq!(
+            lat_lon_to_d(-90.0, 180.0, &world, order),
+            Some(convert_2d_to_1d(7, 0, 8))
+        );
+        assert_eq!(
+            lat_lon_to_d(90.0, 540.0, &world, order),
+            lat_lon_to_d(90.0, 180.0, &world, order)
+        );
+        assert_eq!(
+            lat_lon_to_d(10.0, 200.0, &world, order),
+            lat_lon_to_d(10.0, -160.0, &world, order)
+        );
+        assert_eq!(lat_lon_to_d(91.0, 0.0, &world, order), None);
+        assert_eq!(lat_lon_to_d(0.0, f64::NAN, &world, order), None);
+
+        for d in 0..64 {
+            let (lat, lon) = d_to_lat_lon(d, &world, order);
+            assert_eq!(lat_lon_to_d(lat, lon, &world, order), Some(d));
+        }
+    }
+
+    #[test]
+    fn antimeridian() {
+        let pacific = GeoBounds::new(170.0, -10.0, -170.0, 10.0);
+        assert!(pacific.crosses_antimeridian());
+        let order = 2;
+        let x = |lon| lat_lon_to_d(-10.0, lon, &pacific, order).map(|d| convert_1d_to_2d(d, 4).0);
+        assert_eq!(x(170.0), Some(0));
+        assert_eq!(x(179.0), Some(1));
+        assert_eq!(x(-180.0), Some(2));
+        assert_eq!(x(-170.0), Some(3));
+        assert_eq!(x(550.0), Some(3));
+        assert_eq!(x(-169.0), None);
+        assert_eq!(x(169.0), None);
+
+        let (_, lon) = d_to_lat_lon(convert_2d_to_1d(3, 0, 4), &pacific, order);
+        assert!((lon + 172.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn edges() {
+        assert_eq!(GeoBounds::new(-180.0, -90.0, 180.0, 90.0), GeoBounds::WORLD);
+        let shifted = GeoBounds::new(10.0, -90.0, 370.0, 90.0);
+        assert_eq!(
+            lat_lon_to_d(0.0, 9.0, &shifted, 3).map(|d| convert_1d_to_2d(d, 8).0),
+            Some(7)
+        );
+        assert_eq!(
+            GeoBounds::try_new(190.0, -10.0, -170.0, 10.0),
+            Err(InvalidGeoBounds)
+        );
+        assert_eq!(
+            GeoBounds::try_new(0.0, 10.0, 20.0, -10.0),
+            Err(InvalidGeoBounds)
+        );
+        assert_eq!(
+            GeoBounds::try_new(f64::NAN, -10.0, 20.0, 10.0),
+            Err(InvalidGeoBounds)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        for bounds in [GeoBounds::WORLD, GeoBounds::new(170.0, -10.0, -170.0, 10.0)] {
+            let json = serde_json::to_string(&bounds).unwrap();
+            assert_eq!(serde_json::from_str::<GeoBounds>(&json).unwrap(), bounds);
+        }
+        let json = r#"{"west":20.0,"south":-10.0,"east":20.0,"north":10.0}"#;
+        assert!(serde_json::from_str::<GeoBounds>(json).is_err());
+        let json = r#"{"west":0.0,"south":10.0,"east":20.0,"north":-10.0}"#;
+        assert!(serde_json::from_str::<GeoBounds>(json).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Conversions using the coordinate, point and rectangle types of the `geo-types` crate.
+//!
+//! Integral coordinates are cells of the grid and integral rectangles are inclusive of their
+//! maximum corner, like the rectangles of [`ranges_for_rect`](crate::ranges_for_rect);
+//! floating-point rectangles describe [`Bounds`] for quantization.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{convert::TryFrom, ops::Range};
+
+use ::geo_types::{Coord, Point, Rect};
+
+use crate::{
+    convert_1d_to_2d, convert_2d_to_1d,
+    quantize::{Bounds, InvalidBounds},
+    GridPoint,
+};
+
+/// Convert a one-dimensional distance `d` to the coordinates of its cell.
+pub fn d_to_coord(d: usize, n: usize) -> Coord<usize> {
+    let (x, y) = convert_1d_to_2d(d, n);
+    Coord { x, y }
+}
+
+/// Convert the coordinates of a cell to its one-dimensional distance.
+pub fn coord_to_d(coord: Coord<usize>, n: usize) -> usize {
+    convert_2d_to_1d(coord.x, coord.y, n)
+}
+
+/// Convert a one-dimensional distance `d` to the point at its cell.
+pub fn d_to_point(d: usize, n: usize) -> Point<usize> {
+    d_to_coord(d, n).into()
+}
+
+/// Convert a point at a cell to its one-dimensional distance.
+pub fn point_to_d(point: Point<usize>, n: usize) -> usize {
+    coord_to_d(point.0, n)
+}
+
+/// Decompose a rectangle of cells, including its maximum corner, into ranges of distances as
+/// described by [`ranges_for_rect`](crate::ranges_for_rect).
+#[cfg(feature = "alloc")]
+pub fn ranges_for_rect(rect: Rect<usize>, n: usize, max_ranges: usize) -> Vec<Range<usize>> {
+    let (min, max) = (rect.min(), rect.max());
+    crate::ranges_for_rect(min.x, min.y, max.x, max.y, n, max_ranges)
+}
+
+/// Get the bounding rectangle of the cells of a segment of the curve, as described by
+/// [`segment_bounding_box`](crate::segment_bounding_box).
+pub fn segment_bounding_rect(segment: Range<usize>, n: usize) -> Option<Rect<usize>> {
+    crate::segment_bounding_box(segment, n)
+        .map(|(x0, y0, x1, y1)| Rect::new(Coord { x: x0, y: y0 }, Coord { x: x1, y: y1 }))
+}
+
+impl From<GridPoint> for Coord<usize> {
+    fn from(point: GridPoint) -> Self {
+        Coord {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+impl From<Coord<usize>> for GridPoint {
+    fn from(coord: Coord<usize>) -> Self {
+        GridPoint::new(coord.x, coord.y)
+    }
+}
+
+impl From<GridPoint> for Point<usize> {
+    fn from(point: GridPoint) -> Self {
+        Coord::from(point).into()
+    }
+}
+
+impl From<Point<usize>> for GridPoint {
+    fn from(point: Point<usize>) -> Self {
+        point.0.into()
+    }
+}
+
+impl TryFrom<Rect<f64>> for Bounds {
+    type Error = InvalidBounds;
+
+    /// Fails if the rectangle is empty or not finite.
+    fn try_from(rect: Rect<f64>) -> Result<Self, Self::Error> {
+        Bounds::try_new(rect.min().x, rect.min().y, rect.max().x, rect.max().y)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let n = 8;
+        for d in 0..(n * n) {
+            let point = d_to_point(d, n);
+            assert_eq!(point_to_d(point, n), d);
+            assert_eq!(coord_to_d(d_to_coord(d, n), n), d);
+            let grid = GridPoint::from(point);
+            assert_eq!((grid.x, grid.y), convert_1d_to_2d(d, n));
+            assert_eq!(Point::from(grid), point);
+        }
+
+        let rect = Rect::new(Coord { x: 5, y: 1 }, Coord { x: 2, y: 6 });
+        assert_eq!(
+            ranges_for_rect(rect, n, 4),
+            crate::ranges_for_rect(2, 1, 5, 6, n, 4)
+        );
+        assert_eq!(
+            segment_bounding_rect(0..4, n),
+            Some(Rect::new(Coord { x: 0, y: 0 }, Coord { x: 1, y: 1 }))
+        );
+
+        let bounds = Bounds::try_from(Rect::new(
+            Coord { x: 1.0, y: 2.0 },
+            Coord { x: -1.0, y: 0.0 },
+        ));
+        assert_eq!(bounds, Ok(Bounds::new(-1.0, 0.0, 1.0, 2.0)));
+        let flat = Rect::new(Coord { x: 0.0, y: 1.0 }, Coord { x: 2.0, y: 1.0 });
+        assert_eq!(Bounds::try_from(flat), Err(InvalidBounds));
+        let infinite = Rect::new(
+            Coord { x: 0.0, y: 0.0 },
+            Coord {
+                x: f64::INFINITY,
+                y: 1.0,
+            },
+        );
+        assert_eq!(Bounds::try_from(infinite), Err(InvalidBounds));
+    }
+}
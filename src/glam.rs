@@ -0,0 +1,79 @@
+//! Conversions using the integer vector types of the `glam` crate.
+//!
+//! A `UVec2` is a cell of the grid, while an `IVec2` is a cell of the grid centered on the origin
+//! used by [`convert_2d_to_1d_signed`](crate::convert_2d_to_1d_signed).
+
+use core::{convert::TryFrom, num::TryFromIntError};
+
+use ::glam::{IVec2, UVec2};
+
+use crate::{
+    convert_1d_to_2d, convert_1d_to_2d_signed, convert_2d_to_1d, convert_2d_to_1d_signed, GridPoint,
+};
+
+/// Convert a one-dimensional distance `d` to the coordinates of its cell.
+pub fn d_to_uvec2(d: usize, n: usize) -> UVec2 {
+    let (x, y) = convert_1d_to_2d(d, n);
+    UVec2::new(x as u32, y as u32)
+}
+
+/// Convert the coordinates of a cell to its one-dimensional distance.
+pub fn uvec2_to_d(v: UVec2, n: usize) -> usize {
+    convert_2d_to_1d(v.x as usize, v.y as usize, n)
+}
+
+/// Convert a one-dimensional distance `d` to the signed coordinates of its cell in a grid centered
+/// on the origin.
+pub fn d_to_ivec2(d: usize, n: usize) -> IVec2 {
+    let (x, y) = convert_1d_to_2d_signed(d, n);
+    IVec2::new(x as i32, y as i32)
+}
+
+/// Convert the signed coordinates of a cell in a grid centered on the origin to its
+/// one-dimensional distance.
+///
+/// # Panics
+///
+/// Panics if the coordinates are not within `[-n/2, n/2)`.
+pub fn ivec2_to_d(v: IVec2, n: usize) -> usize {
+    convert_2d_to_1d_signed(v.x as isize, v.y as isize, n)
+}
+
+impl From<UVec2> for GridPoint {
+    fn from(v: UVec2) -> Self {
+        GridPoint::new(v.x as usize, v.y as usize)
+    }
+}
+
+impl TryFrom<GridPoint> for UVec2 {
+    type Error = TryFromIntError;
+
+    /// Fails if either coordinate doesn't fit in a `u32`.
+    fn try_from(point: GridPoint) -> Result<Self, Self::Error> {
+        Ok(UVec2::new(u32::try_from(point.x)?, u32::try_from(point.y)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let n = 16;
+        for d in 0..(n * n) {
+            let v = d_to_uvec2(d, n);
+            assert_eq!(uvec2_to_d(v, n), d);
+            assert_eq!(UVec2::try_from(GridPoint::from(v)), Ok(v));
+            let (x, y) = convert_1d_to_2d(d, n);
+            assert_eq!(GridPoint::from(v), GridPoint::new(x, y));
+
+            let s = d_to_ivec2(d, n);
+            assert_eq!(s, v.as_ivec2() - IVec2::splat(8));
+            assert_eq!(ivec2_to_d(s, n), d);
+        }
+
+        let far = GridPoint::new(1, u32::MAX as usize + 1);
+        assert!(UVec2::try_from(far).is_err());
+    }
+}
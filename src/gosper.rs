@@ -0,0 +1,174 @@
+//! The Gosper curve (flowsnake), which covers regions of hexagonal grids.
+//!
+//! Cells are identified by their axial coordinates (q, r), where the six neighbors of a cell are at
+//! the offsets (1, 0), (0, 1), (−1, 1), (−1, 0), (0, −1) and (1, −1), in counterclockwise order. A
+//! curve of order `k` visits 7^k cells, starting at (0, 0); every level replaces each segment of
+//! the curve with seven shorter ones, so the region covered by the curve is a Gosper island rather
+//! than a regular shape.
+
+// the neighbor offsets, in counterclockwise order; they also represent the rotations by multiples
+// of 60 degrees
+const DIRECTIONS: [(isize, isize); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+// the two kinds of segments, A -> A-B--B+A++AA+B- and B -> +A-BB--B-A++A+B
+const KINDS: [[usize; 7]; 2] = [[0, 1, 1, 0, 0, 0, 1], [0, 1, 1, 1, 0, 0, 1]];
+
+// the directions of the seven parts of a segment, relative to its own direction
+const TURNS: [[usize; 7]; 2] = [[0, 5, 3, 4, 0, 0, 1], [1, 0, 0, 4, 3, 5, 0]];
+
+// the offsets of the seven parts of a segment from its start, relative to its own direction
+const OFFSETS: [[(isize, isize); 7]; 2] = [
+    [(0, 0), (1, 0), (2, -1), (1, -1), (1, -2), (2, -2), (3, -2)],
+    [(0, 0), (0, 1), (1, 1), (2, 1), (2, 0), (1, 0), (2, -1)],
+];
+
+/// The highest supported order.
+pub const MAX_ORDER: u32 = if usize::BITS >= 64 { 22 } else { 11 };
+
+/// Convert a one-dimensional distance `d` to the axial coordinates of a cell on a curve of the
+/// given order.
+pub fn convert_1d_to_2d(d: usize, order: u32) -> (isize, isize) {
+    assert!(order <= MAX_ORDER, "the order must not exceed MAX_ORDER");
+    assert!(d < 7usize.pow(order), "d must be lower than 7^order");
+    let scales = scales(order);
+    let (mut q, mut r) = (0, 0);
+    let (mut heading, mut kind) = (0, 0);
+
+    for level in (0..order as usize).rev() {
+        let part = (d / 7usize.pow(level as u32)) % 7;
+        let (dq, dr) = rotate(OFFSETS[kind][part], heading, scales[level]);
+        q += dq;
+        r += dr;
+        heading = (heading + TURNS[kind][part]) % 6;
+        kind = KINDS[kind][part];
+    }
+
+    (q, r)
+}
+
+/// Convert the axial coordinates of a cell to a one-dimensional distance on a curve of the given
+/// order; returns `None` if the curve doesn't visit the cell.
+pub fn convert_2d_to_1d(q: isize, r: isize, order: u32) -> Option<usize> {
+    assert!(order <= MAX_ORDER, "the order must not exceed MAX_ORDER");
+    let segment = Segment {
+        start: (0, 0),
+        heading: 0,
+        kind: 0,
+        level: order as usize,
+    };
+
+    segment.find((q, r), &scales(order), 0)
+}
+
+// A segment of the curve, covering 7^level cells
+struct Segment {
+    start: (isize, isize),
+    heading: usize,
+    kind: usize,
+    level: usize,
+}
+
+impl Segment {
+    // Find the distance of the cell `p` within this segment, which starts at distance `d`; the
+    // parts whose bounding circles don't contain the cell are skipped
+    fn find(&self, p: (isize, isize), scales: &[(isize, isize)], d: usize) -> Option<usize> {
+        if self.level == 0 {
+            return (p == self.start).then_some(d);
+        }
+
+        let level = self.level - 1;
+        let scale = scales[level];
+        for part in 0..7 {
+            let (dq, dr) = rotate(OFFSETS[self.kind][part], self.heading, scale);
+            let start = (self.start.0 + dq, self.start.1 + dr);
+            let heading = (self.heading + TURNS[self.kind][part]) % 6;
+            let (sq, sr) = rotate((1, 0), heading, scale);
+
+            // all the cells of a segment are within a distance of √(2 * 7^level) from its middle
+            let (mq, mr) = (2 * (p.0 - start.0) - sq, 2 * (p.1 - start.1) - sr);
+            if norm(mq, mr) > 8 * 7i128.pow(level as u32) {
+                continue;
+            }
+
+            let segment = Segment {
+                start,
+                heading,
+                kind: KINDS[self.kind][part],
+                level,
+            };
+            if let Some(d) = segment.find(p, scales, d + part * 7usize.pow(level as u32)) {
+                return Some(d);
+            }
+        }
+
+        None
+    }
+}
+
+// The vectors spanned by segments of every level below the given order, in the direction (1, 0)
+fn scales(order: u32) -> [(isize, isize); MAX_ORDER as usize] {
+    let mut scales = [(0, 0); MAX_ORDER as usize];
+    let mut scale = (1, 0);
+    for s in scales.iter_mut().take(order as usize) {
+        *s = scale;
+        // multiply by 3 - ω, the vector spanned by the seven parts of a segment
+        scale = (3 * scale.0 + scale.1, 2 * scale.1 - scale.0);
+    }
+    scales
+}
+
+// Rotate `v` by `heading` times 60 degrees and scale it by `scale`, treating both as Eisenstein
+// integers
+fn rotate(v: (isize, isize), heading: usize, scale: (isize, isize)) -> (isize, isize) {
+    let v = multiply(v, DIRECTIONS[heading]);
+    multiply(v, scale)
+}
+
+fn multiply((a, b): (isize, isize), (c, d): (isize, isize)) -> (isize, isize) {
+    (a * c - b * d, a * d + b * c + b * d)
+}
+
+// The squared length of a vector, in units of the distance between neighboring cells
+fn norm(q: isize, r: isize) -> i128 {
+    let (q, r) = (q as i128, r as i128);
+    q * q + q * r + r * r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        for order in 0..=5 {
+            for d in 0..7usize.pow(order) {
+                let (q, r) = convert_1d_to_2d(d, order);
+                assert_eq!(convert_2d_to_1d(q, r, order), Some(d));
+            }
+        }
+    }
+
+    #[test]
+    fn adjacency() {
+        for order in 1..=5 {
+            let mut prev = convert_1d_to_2d(0, order);
+            for d in 1..7usize.pow(order) {
+                let curr = convert_1d_to_2d(d, order);
+                let (dq, dr) = (curr.0 - prev.0, curr.1 - prev.1);
+                assert!(DIRECTIONS.contains(&(dq, dr)));
+                prev = curr;
+            }
+        }
+    }
+
+    #[test]
+    fn unvisited_cells() {
+        let order = 3;
+        let visited = (-30..30)
+            .flat_map(|q| (-30..30).map(move |r| (q, r)))
+            .filter(|&(q, r)| convert_2d_to_1d(q, r, order).is_some())
+            .count();
+        assert_eq!(visited, 7usize.pow(order));
+        assert_eq!(convert_2d_to_1d(1, 1, 1), None);
+    }
+}
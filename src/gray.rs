@@ -0,0 +1,119 @@
+//! The Gray-code curve, which visits the cells in the order of the Gray-code ranks of their
+//! Morton codes.
+//!
+//! Consecutive cells differ in a single bit of their Morton codes, so the conversions are almost
+//! as cheap as the Morton ones while keeping better locality.
+
+use crate::{morton, GridPoint, HilbertCurve, SpaceFillingCurve};
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+pub const fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
+    morton::convert_1d_to_2d(gray_encode(d), n)
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+pub const fn convert_2d_to_1d(x: usize, y: usize, n: usize) -> usize {
+    gray_decode(morton::convert_2d_to_1d(x, y, n))
+}
+
+/// Get the binary reflected Gray code of `i`; consecutive values differ in exactly one bit.
+pub const fn gray_encode(i: usize) -> usize {
+    i ^ (i >> 1)
+}
+
+/// Get the value whose binary reflected Gray code is `g`; the inverse of [`gray_encode`].
+pub const fn gray_decode(g: usize) -> usize {
+    let mut g = g;
+    let mut shift = 1;
+    while shift < usize::BITS {
+        g ^= g >> shift;
+        shift <<= 1;
+    }
+    g
+}
+
+/// A Gray-code curve filling a square grid of `2^order` by `2^order` cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GrayCurve {
+    order: u32,
+}
+
+impl GrayCurve {
+    /// Create a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        assert!(order <= HilbertCurve::MAX_ORDER, "the order is too large");
+        GrayCurve { order }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        1 << self.order
+    }
+}
+
+impl SpaceFillingCurve for GrayCurve {
+    fn len(&self) -> usize {
+        self.side() * self.side()
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        assert!(
+            index < self.len(),
+            "index must be lower than the length of the curve"
+        );
+        convert_1d_to_2d(index, self.side()).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        assert!(
+            point.x < self.side() && point.y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        convert_2d_to_1d(point.x, point.y, self.side())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        for &n in &[1, 2, 4, 8, 16, 32, 64, 128] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                assert!(x < n && y < n);
+                assert_eq!(convert_2d_to_1d(x, y, n), d);
+            }
+        }
+    }
+
+    #[test]
+    fn single_bit_steps() {
+        let n = 32;
+        for d in 1..(n * n) {
+            let ((px, py), (x, y)) = (convert_1d_to_2d(d - 1, n), convert_1d_to_2d(d, n));
+            let diff = morton::convert_2d_to_1d(px, py, n) ^ morton::convert_2d_to_1d(x, y, n);
+            assert_eq!(diff.count_ones(), 1);
+        }
+    }
+
+    #[test]
+    fn gray_codes() {
+        assert_eq!(gray_encode(0b1011), 0b1110);
+        for i in 0..1024 {
+            assert_eq!(gray_decode(gray_encode(i)), i);
+            assert_eq!((gray_encode(i) ^ gray_encode(i + 1)).count_ones(), 1);
+        }
+        assert_eq!(gray_decode(gray_encode(usize::MAX)), usize::MAX);
+    }
+}
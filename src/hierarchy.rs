@@ -0,0 +1,99 @@
+//! Navigation between the cells of Hilbert curves of different orders.
+//!
+//! The curves of consecutive orders are nested: the cell at distance `d` on the curve of order k
+//! contains the 4 cells at distances `4d..4d + 4` on the curve of order k + 1.
+
+use core::ops::Range;
+
+use crate::HilbertCurve;
+
+/// Get the distance of the cell containing the cell at distance `d` on the curve of order
+/// `order - 1`; returns `None` if `order` is 0.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than
+/// `4^order`.
+pub fn parent(d: usize, order: u32) -> Option<usize> {
+    check(d, order);
+    order
+        .checked_sub(1)
+        .map(|coarser| promote(d, order, coarser))
+}
+
+/// Get the distances of the 4 cells contained in the cell at distance `d` on the curve of order
+/// `order + 1`.
+///
+/// # Panics
+///
+/// Panics if `order` is not lower than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than
+/// `4^order`.
+pub fn children(d: usize, order: u32) -> Range<usize> {
+    demote(d, order, order + 1)
+}
+
+/// Get the distance of the cell containing the cell at distance `d` of the curve of order `order`
+/// on the curve of order `coarser`.
+///
+/// # Panics
+///
+/// Panics if `coarser` is greater than `order`, `order` is greater than
+/// [`HilbertCurve::MAX_ORDER`] or `d` is not lower than `4^order`.
+pub fn promote(d: usize, order: u32, coarser: u32) -> usize {
+    check(d, order);
+    assert!(coarser <= order, "coarser must not exceed order");
+    d >> (2 * (order - coarser))
+}
+
+/// Get the distances of the cells contained in the cell at distance `d` of the curve of order
+/// `order` on the curve of order `finer`; they form a contiguous segment of that curve.
+///
+/// # Panics
+///
+/// Panics if `finer` is lower than `order` or greater than [`HilbertCurve::MAX_ORDER`], or `d`
+/// is not lower than `4^order`.
+pub fn demote(d: usize, order: u32, finer: u32) -> Range<usize> {
+    assert!(finer >= order, "finer must not be lower than order");
+    check(d, order);
+    check(0, finer);
+    let shift = 2 * (finer - order);
+    (d << shift)..((d + 1) << shift)
+}
+
+fn check(d: usize, order: u32) {
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+    assert!(d >> (2 * order) == 0, "d must be lower than 4^order");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+    #[test]
+    fn nesting() {
+        for order in 0..5 {
+            let n = 1 << order;
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                for child in children(d, order) {
+                    assert_eq!(parent(child, order + 1), Some(d));
+                    let (cx, cy) = convert_1d_to_2d(child, 2 * n);
+                    assert_eq!((cx / 2, cy / 2), (x, y));
+                }
+                let cells = demote(d, order, order + 2);
+                assert_eq!(cells.len(), 16);
+                for child in cells {
+                    let (cx, cy) = convert_1d_to_2d(child, 4 * n);
+                    assert_eq!(convert_2d_to_1d(cx / 4, cy / 4, n), d);
+                    assert_eq!(promote(child, order + 2, order), d);
+                }
+            }
+        }
+        assert_eq!(parent(0, 0), None);
+        assert_eq!(demote(5, 3, 3), 5..6);
+    }
+}
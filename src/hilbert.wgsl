@@ -0,0 +1,73 @@
+// The bitwise conversions of the crate, one element per invocation; distances are 64-bit values
+// held as (low, high) pairs of 32-bit words, since WGSL has no 64-bit integers.
+
+struct Params {
+    order: u32,
+    count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<u32>;
+
+fn rotate(s: u32, p: vec2<u32>, rx: u32, ry: u32) -> vec2<u32> {
+    if ry == 0u {
+        var q = p;
+        if rx == 1u {
+            q = vec2<u32>(s - 1u - q.x, s - 1u - q.y);
+        }
+        return q.yx;
+    }
+    return p;
+}
+
+@compute @workgroup_size(64)
+fn d_to_xy(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= params.count {
+        return;
+    }
+
+    var lo = input[2u * i];
+    var hi = input[2u * i + 1u];
+    var p = vec2<u32>(0u, 0u);
+    for (var level = 0u; level < params.order; level++) {
+        let s = 1u << level;
+        let rx = 1u & (lo >> 1u);
+        let ry = 1u & (lo ^ rx);
+        p = rotate(s, p, rx, ry) + vec2<u32>(s * rx, s * ry);
+        lo = (lo >> 2u) | (hi << 30u);
+        hi = hi >> 2u;
+    }
+
+    output[2u * i] = p.x;
+    output[2u * i + 1u] = p.y;
+}
+
+@compute @workgroup_size(64)
+fn xy_to_d(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= params.count {
+        return;
+    }
+
+    var p = vec2<u32>(input[2u * i], input[2u * i + 1u]);
+    var lo = 0u;
+    var hi = 0u;
+    for (var level = params.order; level > 0u; level--) {
+        let bit = level - 1u;
+        let s = 1u << bit;
+        let rx = select(0u, 1u, (p.x & s) != 0u);
+        let ry = select(0u, 1u, (p.y & s) != 0u);
+        let digit = (3u * rx) ^ ry;
+        if bit < 16u {
+            lo |= digit << (2u * bit);
+        } else {
+            hi |= digit << (2u * bit - 32u);
+        }
+        p = rotate(s, p, rx, ry);
+    }
+
+    output[2u * i] = lo;
+    output[2u * i + 1u] = hi;
+}
@@ -0,0 +1,188 @@
+//! Raster images of the curve using the `image` crate.
+//!
+//! Every cell is a pixel, with the row of the highest y at the top of the image.
+
+use alloc::vec::Vec;
+use core::{convert::TryFrom, ops::Deref};
+
+use ::image::{ImageBuffer, Pixel, Rgb, RgbImage};
+
+use crate::{convert_1d_to_2d_rect, HilbertCurve};
+
+/// Render the values of the cells of the curve of the given order, given in curve order, converting
+/// each of them to a pixel with `f`; the cells past the end of `values` keep the default pixel.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or the side of the grid doesn't
+/// fit in a `u32`.
+pub fn render_values<T, P: Pixel>(
+    values: &[T],
+    order: u32,
+    f: impl Fn(&T) -> P,
+) -> ImageBuffer<P, Vec<P::Subpixel>> {
+    let curve = HilbertCurve::new(order);
+    let n = curve.side();
+    let side = u32::try_from(n).expect("the side of the grid must fit in a u32");
+    let mut image = ImageBuffer::new(side, side);
+
+    for (value, (_, x, y)) in values.iter().zip(curve.iter()) {
+        image.put_pixel(x as u32, (n - 1 - y) as u32, f(value));
+    }
+
+    image
+}
+
+/// Render the curve of the given order with every cell colored by its distance, going through
+/// the hues from red at the start of the curve to magenta at its end.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or the side of the grid doesn't
+/// fit in a `u32`.
+pub fn render_distances(order: u32) -> RgbImage {
+    let len = HilbertCurve::new(order).len();
+    let distances: Vec<usize> = (0..len).collect();
+    render_values(&distances, order, |&d| hue(d as f64 / len as f64))
+}
+
+/// Render a byte stream laid along the curve of the given order, coloring every byte by its class
+/// as is usual in binary analysis: black for 0x00, white for 0xff, blue for printable ASCII, green
+/// for ASCII whitespace and control characters, and red for the other bytes. The bytes past the
+/// end of the curve are ignored.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or the side of the grid doesn't
+/// fit in a `u32`.
+pub fn render_bytes(bytes: &[u8], order: u32) -> RgbImage {
+    render_values(bytes, order, |&byte| match byte {
+        0x00 => Rgb([0, 0, 0]),
+        0xff => Rgb([255, 255, 255]),
+        0x20..=0x7e => Rgb([55, 126, 184]),
+        0x01..=0x1f | 0x7f => Rgb([77, 175, 74]),
+        _ => Rgb([228, 26, 28]),
+    })
+}
+
+/// Get the pixels of an image of any size in the order of a
+/// [rectangular Hilbert curve](crate::convert_1d_to_2d_rect) filling the smallest grid of 2^a by
+/// 2^b cells containing it, skipping the cells outside of the image.
+pub fn reorder_pixels_hilbert<P, C>(image: &ImageBuffer<P, C>) -> Vec<P>
+where
+    P: Pixel,
+    C: Deref<Target = [P::Subpixel]>,
+{
+    let (width, height) = image.dimensions();
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for_each_pixel(width, height, |x, y| pixels.push(*image.get_pixel(x, y)));
+
+    pixels
+}
+
+/// Rebuild an image of the given size from its pixels in the order of
+/// [`reorder_pixels_hilbert`].
+///
+/// # Panics
+///
+/// Panics if the number of pixels is not `width * height`.
+pub fn restore_from_hilbert<P: Pixel>(
+    pixels: &[P],
+    width: u32,
+    height: u32,
+) -> ImageBuffer<P, Vec<P::Subpixel>> {
+    assert_eq!(
+        pixels.len(),
+        width as usize * height as usize,
+        "there must be one pixel per cell of the image"
+    );
+    let mut image = ImageBuffer::new(width, height);
+    let mut pixels = pixels.iter();
+    for_each_pixel(width, height, |x, y| {
+        image.put_pixel(
+            x,
+            y,
+            *pixels.next().expect("the number of pixels was checked"),
+        )
+    });
+
+    image
+}
+
+// Visit the pixels of an image in the order of the rectangular curve covering it
+fn for_each_pixel(width: u32, height: u32, mut f: impl FnMut(u32, u32)) {
+    let (w, h) = (width as usize, height as usize);
+    let (cw, ch) = (w.next_power_of_two(), h.next_power_of_two());
+    if w == 0 || h == 0 {
+        return;
+    }
+
+    for d in 0..cw * ch {
+        let (x, y) = convert_1d_to_2d_rect(d, cw, ch);
+        if x < w && y < h {
+            f(x as u32, (h - 1 - y) as u32);
+        }
+    }
+}
+
+// A fully saturated color with the given hue in [0, 1)
+fn hue(h: f64) -> Rgb<u8> {
+    let sector = h * 6.0;
+    let i = (sector as usize).min(5);
+    let rise = ((sector - i as f64) * 255.0) as u8;
+    let fall = 255 - rise;
+
+    Rgb(match i {
+        0 => [255, rise, 0],
+        1 => [fall, 255, 0],
+        2 => [0, 255, rise],
+        3 => [0, fall, 255],
+        4 => [rise, 0, 255],
+        _ => [255, 0, fall],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::image::Luma;
+
+    #[test]
+    fn rendering() {
+        let image = render_distances(3);
+        assert_eq!(image.dimensions(), (8, 8));
+        // the curve starts in the lower left corner and ends in the lower right one
+        assert_eq!(*image.get_pixel(0, 7), Rgb([255, 0, 0]));
+        assert_eq!(image.get_pixel(7, 7)[0], 255);
+
+        let bytes = render_bytes(b"\0\xffA\n\x80", 1);
+        assert_eq!(*bytes.get_pixel(0, 1), Rgb([0, 0, 0]));
+        assert_eq!(*bytes.get_pixel(0, 0), Rgb([255, 255, 255]));
+        assert_eq!(*bytes.get_pixel(1, 0), Rgb([55, 126, 184]));
+        assert_eq!(*bytes.get_pixel(1, 1), Rgb([77, 175, 74]));
+
+        let values = render_values(&[10u8, 20], 2, |&v| Luma([v]));
+        assert_eq!(values.get_pixel(0, 3)[0], 10);
+        assert_eq!(values.get_pixel(1, 3)[0], 20);
+        assert_eq!(values.get_pixel(3, 3)[0], 0);
+    }
+
+    #[test]
+    fn reordering() {
+        let image = ImageBuffer::from_fn(5, 3, |x, y| Luma([(10 * y + x) as u8]));
+        let pixels = reorder_pixels_hilbert(&image);
+        assert_eq!(pixels.len(), 15);
+        // the curve starts in the lower left corner and first goes right on an 8 by 4 grid
+        assert_eq!(pixels[0], Luma([20]));
+        assert_eq!(pixels[1], Luma([21]));
+        assert_eq!(restore_from_hilbert(&pixels, 5, 3), image);
+
+        let square = render_distances(2);
+        let pixels = reorder_pixels_hilbert(&square);
+        assert_eq!(
+            pixels,
+            (0..16).map(|d| hue(d as f64 / 16.0)).collect::<Vec<_>>()
+        );
+        assert!(reorder_pixels_hilbert(&RgbImage::new(0, 4)).is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+//! A common interface of the values that can be placed on the grid.
+
+use crate::GridPoint;
+
+/// A value occupying a cell of the grid of a Hilbert curve, which lets it be sorted, partitioned
+/// and packed along the curve by the helpers of the crate.
+pub trait HilbertIndexable {
+    /// Get the coordinates of the cell of an n by n grid occupied by the value; they must be
+    /// lower than `n`.
+    fn cell(&self, n: usize) -> (usize, usize);
+}
+
+impl HilbertIndexable for (usize, usize) {
+    fn cell(&self, _n: usize) -> (usize, usize) {
+        *self
+    }
+}
+
+impl HilbertIndexable for [usize; 2] {
+    fn cell(&self, _n: usize) -> (usize, usize) {
+        (self[0], self[1])
+    }
+}
+
+impl HilbertIndexable for GridPoint {
+    fn cell(&self, _n: usize) -> (usize, usize) {
+        (self.x, self.y)
+    }
+}
+
+impl<T: HilbertIndexable + ?Sized> HilbertIndexable for &T {
+    fn cell(&self, n: usize) -> (usize, usize) {
+        (**self).cell(n)
+    }
+}
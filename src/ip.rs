@@ -0,0 +1,135 @@
+//! Maps of the IPv4 and IPv6 address spaces, in the layout of the xkcd "Map of the Internet".
+//!
+//! The first `prefix_len` bits of an address are a distance along the curve filling a grid of
+//! `2^(prefix_len / 2)` cells per side, so each cell is a block of addresses sharing a prefix. The
+//! rows are numbered from the top, so `0.0.0.0` is in the upper left corner and the curve first
+//! heads right, as in the comic.
+
+use core::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{
+    convert_1d_to_2d_u128, convert_1d_to_2d_u32, convert_2d_to_1d_u128, convert_2d_to_1d_u32,
+};
+
+/// Get the (x, y) cell of an IPv4 address on the map of all the 2^32 addresses.
+pub fn ipv4_to_xy(addr: Ipv4Addr) -> (u16, u16) {
+    ipv4_prefix_to_xy(addr, 32)
+}
+
+/// Get the IPv4 address at the (x, y) cell of the map of all the 2^32 addresses.
+pub fn xy_to_ipv4(x: u16, y: u16) -> Ipv4Addr {
+    xy_to_ipv4_prefix(x, y, 32)
+}
+
+/// Get the (x, y) cell of the block of IPv4 addresses sharing the first `prefix_len` bits of the
+/// given one, on the map of all such blocks; e.g. the map of the /8 blocks is 16 by 16 cells.
+///
+/// # Panics
+///
+/// Panics if `prefix_len` is odd or greater than 32.
+pub fn ipv4_prefix_to_xy(addr: Ipv4Addr, prefix_len: u32) -> (u16, u16) {
+    let side = check_prefix(prefix_len, 32);
+    let prefix = u32::from(addr).checked_shr(32 - prefix_len).unwrap_or(0);
+    let (x, y) = convert_1d_to_2d_u32(prefix, side as u32);
+    (x as u16, y as u16)
+}
+
+/// Get the first address of the block of IPv4 addresses at the (x, y) cell of the map of the
+/// blocks sharing their first `prefix_len` bits.
+///
+/// # Panics
+///
+/// Panics if `prefix_len` is odd or greater than 32, or if either coordinate is not lower than
+/// `2^(prefix_len / 2)`.
+pub fn xy_to_ipv4_prefix(x: u16, y: u16, prefix_len: u32) -> Ipv4Addr {
+    let side = check_prefix(prefix_len, 32);
+    assert!(
+        u128::from(x) < side && u128::from(y) < side,
+        "coordinates must be lower than 2^(prefix_len / 2)"
+    );
+    let prefix = convert_2d_to_1d_u32(x.into(), y.into(), side as u32);
+    Ipv4Addr::from(prefix.checked_shl(32 - prefix_len).unwrap_or(0))
+}
+
+/// Get the (x, y) cell of the block of IPv6 addresses sharing the first `prefix_len` bits of the
+/// given one, on the map of all such blocks; e.g. the map of the /32 blocks is 65536 by 65536
+/// cells.
+///
+/// # Panics
+///
+/// Panics if `prefix_len` is odd or greater than 128.
+pub fn ipv6_prefix_to_xy(addr: Ipv6Addr, prefix_len: u32) -> (u64, u64) {
+    let side = check_prefix(prefix_len, 128);
+    let prefix = u128::from(addr).checked_shr(128 - prefix_len).unwrap_or(0);
+    let (x, y) = convert_1d_to_2d_u128(prefix, side);
+    (x as u64, y as u64)
+}
+
+/// Get the first address of the block of IPv6 addresses at the (x, y) cell of the map of the
+/// blocks sharing their first `prefix_len` bits.
+///
+/// # Panics
+///
+/// Panics if `prefix_len` is odd or greater than 128, or if either coordinate is not lower than
+/// `2^(prefix_len / 2)`.
+pub fn xy_to_ipv6_prefix(x: u64, y: u64, prefix_len: u32) -> Ipv6Addr {
+    let side = check_prefix(prefix_len, 128);
+    assert!(
+        u128::from(x) < side && u128::from(y) < side,
+        "coordinates must be lower than 2^(prefix_len / 2)"
+    );
+    let prefix = convert_2d_to_1d_u128(x.into(), y.into(), side);
+    Ipv6Addr::from(prefix.checked_shl(128 - prefix_len).unwrap_or(0))
+}
+
+// Check the length of a prefix and return the side of its map
+fn check_prefix(prefix_len: u32, bits: u32) -> u128 {
+    assert!(
+        prefix_len % 2 == 0 && prefix_len <= bits,
+        "the prefix length must be even and not exceed the address width"
+    );
+    1 << (prefix_len / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv4() {
+        // the top row of /8 blocks in the comic
+        let top = [
+            0, 1, 14, 15, 16, 19, 20, 21, 234, 235, 236, 239, 240, 241, 254, 255,
+        ];
+        for (x, &block) in top.iter().enumerate() {
+            assert_eq!(xy_to_ipv4_prefix(x as u16, 0, 8).octets()[0], block);
+        }
+
+        for &addr in &[
+            Ipv4Addr::new(0, 0, 0, 0),
+            Ipv4Addr::new(10, 1, 2, 3),
+            Ipv4Addr::new(255, 255, 255, 255),
+        ] {
+            let (x, y) = ipv4_to_xy(addr);
+            assert_eq!(xy_to_ipv4(x, y), addr);
+            assert_eq!(ipv4_prefix_to_xy(addr, 8), (x >> 12, y >> 12));
+        }
+        assert_eq!(ipv4_prefix_to_xy(Ipv4Addr::new(1, 2, 3, 4), 0), (0, 0));
+        assert_eq!(xy_to_ipv4_prefix(0, 0, 0), Ipv4Addr::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn ipv6() {
+        let addr: Ipv6Addr = "2001:db8:85a3::8a2e:370:7334".parse().unwrap();
+        let (x, y) = ipv6_prefix_to_xy(addr, 128);
+        assert_eq!(xy_to_ipv6_prefix(x, y, 128), addr);
+
+        let (x, y) = ipv6_prefix_to_xy(addr, 32);
+        assert!(x < 1 << 16 && y < 1 << 16);
+        assert_eq!(
+            xy_to_ipv6_prefix(x, y, 32),
+            "2001:db8::".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(ipv6_prefix_to_xy(addr, 128).0 >> 48, x);
+    }
+}
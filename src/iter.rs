@@ -0,0 +1,590 @@
+//! Iteration over the cells of a Hilbert curve in curve order.
+
+use core::{
+    iter::FusedIterator,
+    ops::{ControlFlow, Range},
+};
+
+use crate::{
+    convert_1d_to_2d, convert_2d_to_1d,
+    lut::{descend, quadrant, REVERSE, SWAP},
+    Direction, HilbertCurve,
+};
+
+// The (x, y) coordinates of a cell
+type Cell = (usize, usize);
+
+/// An iterator over the cells of a [`HilbertCurve`], yielding `(d, x, y)` in curve order.
+///
+/// Every cell after the first one (or before the last one, when iterating backwards) is derived
+/// from its neighbor in constant time.
+#[derive(Debug, Clone)]
+pub struct Iter {
+    curve: HilbertCurve,
+    front: (usize, usize, usize),
+    // one past the last distance, with the coordinates of the last cell
+    back: (usize, usize, usize),
+}
+
+impl Iter {
+    pub(crate) fn new(curve: &HilbertCurve, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= curve.len(),
+            "the range must be a valid segment of the curve"
+        );
+        let (start, end) = (range.start, range.end);
+        let (x, y, bx, by) = if start < end {
+            let (x, y) = curve.d_to_xy(start);
+            let (bx, by) = curve.d_to_xy(end - 1);
+            (x, y, bx, by)
+        } else {
+            (0, 0, 0, 0)
+        };
+
+        Iter {
+            curve: *curve,
+            front: (start, x, y),
+            back: (end, bx, by),
+        }
+    }
+}
+
+impl Iterator for Iter {
+    type Item = (usize, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (d, x, y) = self.front;
+        if d >= self.back.0 {
+            return None;
+        }
+
+        if d + 1 < self.back.0 {
+            let (dx, dy) = self.curve.step(d);
+            self.front = (
+                d + 1,
+                x.wrapping_add(dx as usize),
+                y.wrapping_add(dy as usize),
+            );
+        } else {
+            self.front.0 += 1;
+        }
+
+        Some((d, x, y))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (end, x, y) = self.back;
+        if end <= self.front.0 {
+            return None;
+        }
+
+        if end - 1 > self.front.0 {
+            let (dx, dy) = self.curve.step(end - 2);
+            self.back = (
+                end - 1,
+                x.wrapping_sub(dx as usize),
+                y.wrapping_sub(dy as usize),
+            );
+        } else {
+            self.back.0 -= 1;
+        }
+
+        Some((end - 1, x, y))
+    }
+}
+
+impl ExactSizeIterator for Iter {
+    fn len(&self) -> usize {
+        self.back.0.saturating_sub(self.front.0)
+    }
+}
+
+impl FusedIterator for Iter {}
+
+/// A square block of cells, which is a contiguous segment of the curve.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Block {
+    /// The distances of the cells in the block.
+    pub range: Range<usize>,
+    /// The cells of the block, as inclusive (x0, y0, x1, y1) bounds.
+    pub bounds: (usize, usize, usize, usize),
+}
+
+/// An iterator over the b by b blocks of an n by n grid in the order of the curve over the
+/// blocks, created by [`blocks`].
+#[derive(Debug, Clone)]
+pub struct Blocks {
+    inner: Iter,
+    size: usize,
+}
+
+impl Blocks {
+    fn block(&self, (k, bx, by): (usize, usize, usize)) -> Block {
+        let (size, cells) = (self.size, self.size * self.size);
+        let (x0, y0) = (bx * size, by * size);
+        Block {
+            range: k * cells..(k + 1) * cells,
+            bounds: (x0, y0, x0 + size - 1, y0 + size - 1),
+        }
+    }
+}
+
+impl Iterator for Blocks {
+    type Item = Block;
+
+    fn next(&mut self) -> Option<Block> {
+        let block = self.inner.next()?;
+        Some(self.block(block))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Blocks {
+    fn next_back(&mut self) -> Option<Block> {
+        let block = self.inner.next_back()?;
+        Some(self.block(block))
+    }
+}
+
+impl ExactSizeIterator for Blocks {}
+
+impl FusedIterator for Blocks {}
+
+/// Iterate over the b by b blocks of an n by n grid in curve order; since the curve over the
+/// blocks is the curve over the cells at a coarser order, the cells of every block are a
+/// contiguous range of distances and consecutive blocks are adjacent.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or `b` is not a power of 2 lower than
+/// or equal to `n`.
+pub fn blocks(n: usize, b: usize) -> Blocks {
+    assert!(
+        HilbertCurve::try_from_side(n).is_ok(),
+        "n must be a valid side"
+    );
+    assert!(
+        b.is_power_of_two() && b <= n,
+        "b must be a power of 2 not greater than n"
+    );
+    let curve = HilbertCurve::try_from_side(n / b).expect("the curve over the blocks is smaller");
+
+    Blocks {
+        inner: curve.iter(),
+        size: b,
+    }
+}
+
+/// Get the coordinates of the cell at distance `d + 1`, given the coordinates (x, y) of the cell
+/// at distance `d`, in constant time; returns `None` if `d` is the end of the curve.
+pub fn next_point(x: usize, y: usize, d: usize, n: usize) -> Option<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let order = n.trailing_zeros();
+    if order == 0 || d >= low_mask(2 * order) {
+        return None;
+    }
+
+    let (dx, dy) = step(d, order);
+    Some((x.wrapping_add(dx as usize), y.wrapping_add(dy as usize)))
+}
+
+/// Get the coordinates of the cell at distance `d - 1`, given the coordinates (x, y) of the cell
+/// at distance `d`, in constant time; returns `None` if `d` is the start of the curve.
+pub fn prev_point(x: usize, y: usize, d: usize, n: usize) -> Option<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    if d == 0 {
+        return None;
+    }
+
+    let (dx, dy) = step(d - 1, n.trailing_zeros());
+    Some((x.wrapping_sub(dx as usize), y.wrapping_sub(dy as usize)))
+}
+
+/// Get the direction in which the curve moves from the cell at distance `d` to the one at
+/// distance `d + 1`; returns `None` if `d` is the end of the curve.
+pub fn heading_at(d: usize, n: usize) -> Option<Direction> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let order = n.trailing_zeros();
+    if order == 0 || d >= low_mask(2 * order) {
+        return None;
+    }
+
+    Some(Direction::from_step(step(d, order)))
+}
+
+/// Get the distance of the cell adjacent to the cell at distance `d` in the given direction,
+/// without converting the distances from scratch; returns `None` if the move leaves the grid.
+///
+/// A move to the preceding or following cell on the curve takes constant time, and any other move
+/// only re-encodes the levels of the curve within the smallest block containing both cells.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `d` is not lower than n^2.
+pub fn index_after_move(d: usize, direction: Direction, n: usize) -> Option<usize> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let order = n.trailing_zeros();
+    let last = low_mask(2 * order);
+    assert!(d <= last, "d must be lower than n^2");
+
+    let (dx, dy) = direction.offset();
+    if d < last && step(d, order) == (dx, dy) {
+        return Some(d + 1);
+    }
+    if d > 0 && step(d - 1, order) == (-dx, -dy) {
+        return Some(d - 1);
+    }
+
+    let (x, y) = convert_1d_to_2d(d, n);
+    let nx = x.checked_add_signed(dx).filter(|&nx| nx < n)?;
+    let ny = y.checked_add_signed(dy).filter(|&ny| ny < n)?;
+
+    // the digits above the level of the block containing both cells are shared, and so is the
+    // state of the curve within that block
+    let level = usize::BITS - ((x ^ nx) | (y ^ ny)).leading_zeros();
+    let above = last & !low_mask(2 * level) & (usize::MAX / 3);
+    let mut state = 0;
+    if (!(d ^ (d >> 1)) & above).count_ones() % 2 == 1 {
+        state ^= SWAP;
+    }
+    if (d & (d >> 1) & above).count_ones() % 2 == 1 {
+        state ^= REVERSE;
+    }
+
+    let mut moved = d & !low_mask(2 * level);
+    for i in (0..level).rev() {
+        let (mut qx, mut qy) = ((nx >> i) & 1, (ny >> i) & 1);
+        if state & REVERSE != 0 {
+            qx ^= 1;
+            qy ^= 1;
+        }
+        if state & SWAP != 0 {
+            core::mem::swap(&mut qx, &mut qy);
+        }
+        let digit = (3 * qx) ^ qy;
+        moved |= digit << (2 * i);
+        state ^= descend(digit);
+    }
+
+    Some(moved)
+}
+
+/// Get the cells preceding and following the cell (x, y) on the curve, if any.
+pub fn curve_neighbors(x: usize, y: usize, n: usize) -> (Option<Cell>, Option<Cell>) {
+    let d = convert_2d_to_1d(x, y, n);
+    (prev_point(x, y, d, n), next_point(x, y, d, n))
+}
+
+/// Iterate over the cells adjacent to the cell (x, y) within the grid, on the left, right,
+/// bottom and top, yielding their `(d, x, y)`.
+pub fn grid_neighbors_with_d(
+    x: usize,
+    y: usize,
+    n: usize,
+) -> impl Iterator<Item = (usize, usize, usize)> {
+    assert!(x < n && y < n, "coordinates must be lower than n");
+    let neighbors = [
+        x.checked_sub(1).map(|x| (x, y)),
+        Some(x + 1).filter(|&x| x < n).map(|x| (x, y)),
+        y.checked_sub(1).map(|y| (x, y)),
+        Some(y + 1).filter(|&y| y < n).map(|y| (x, y)),
+    ];
+
+    IntoIterator::into_iter(neighbors)
+        .flatten()
+        .map(move |(x, y)| (convert_2d_to_1d(x, y, n), x, y))
+}
+
+/// Traverse the curve of the given order recursively, from the whole grid down to its cells.
+///
+/// The callback is called with `(d, x, y, side)` for every square block of `side` by `side`
+/// cells whose lower left cell is (x, y), which holds the distances `d..d + side * side`; the
+/// blocks are visited depth-first in curve order, down to the single cells, unless the callback
+/// returns [`ControlFlow::Break`] to skip the sub-blocks of a block.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn walk<F>(order: u32, mut f: F)
+where
+    F: FnMut(usize, usize, usize, usize) -> ControlFlow<()>,
+{
+    let curve = HilbertCurve::new(order);
+    walk_block(0, 0, 0, curve.side(), 0, &mut f);
+}
+
+fn walk_block<F>(d: usize, x: usize, y: usize, side: usize, state: usize, f: &mut F)
+where
+    F: FnMut(usize, usize, usize, usize) -> ControlFlow<()>,
+{
+    if f(d, x, y, side).is_break() || side == 1 {
+        return;
+    }
+
+    let half = side / 2;
+    for digit in 0..4 {
+        let (qx, qy) = quadrant(state, digit);
+        let start = d + digit * half * half;
+        walk_block(
+            start,
+            x + qx * half,
+            y + qy * half,
+            half,
+            state ^ descend(digit),
+            f,
+        );
+    }
+}
+
+// The offset from the cell at distance `d` to the cell at distance `d + 1` on a curve of the
+// given order; `d` must not be the last distance of the curve
+pub(crate) fn step(d: usize, order: u32) -> (isize, isize) {
+    // the step crosses between two adjacent sub-curves at the level of the lowest base-4 digit
+    // of `d` that is not a 3
+    let level = d.trailing_ones() / 2;
+    debug_assert!(level < order, "the last cell has no successor");
+    let (dx, dy) = match (d >> (2 * level)) & 3 {
+        0 => (0, 1),
+        1 => (1, 0),
+        _ => (0, -1),
+    };
+
+    // each 0 digit above that level transposes the sub-curve and each 3 digit transposes it
+    // and reverses its direction
+    let above = low_mask(2 * order) & !low_mask(2 * (level + 1)) & (usize::MAX / 3);
+    let transpositions = !(d ^ (d >> 1)) & above;
+    let reversals = d & (d >> 1) & above;
+
+    let (dx, dy) = if reversals.count_ones() % 2 == 1 {
+        (-dx, -dy)
+    } else {
+        (dx, dy)
+    };
+    if transpositions.count_ones() % 2 == 1 {
+        (dy, dx)
+    } else {
+        (dx, dy)
+    }
+}
+
+// A mask with the lowest `bits` bits set
+fn low_mask(bits: u32) -> usize {
+    if bits >= usize::BITS {
+        usize::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn matches_conversion() {
+        for order in 0..=6 {
+            let curve = HilbertCurve::new(order);
+            let mut count = 0;
+            for (d, x, y) in curve.iter() {
+                assert_eq!((x, y), curve.d_to_xy(d));
+                count += 1;
+            }
+            assert_eq!(count, curve.len());
+        }
+    }
+
+    #[test]
+    fn block_iteration() {
+        let (n, b) = (16, 4);
+        let all: Vec<_> = blocks(n, b).collect();
+        assert_eq!(all.len(), 16);
+        assert_eq!(
+            all[0],
+            Block {
+                range: 0..16,
+                bounds: (0, 0, 3, 3)
+            }
+        );
+        for block in &all {
+            let (x0, y0, x1, y1) = block.bounds;
+            for d in block.range.clone() {
+                let (x, y) = convert_1d_to_2d(d, n);
+                assert!((x0..=x1).contains(&x) && (y0..=y1).contains(&y));
+            }
+        }
+        assert_eq!(blocks(n, b).next_back(), all.last().cloned());
+        assert_eq!(blocks(n, n).count(), 1);
+        assert_eq!(blocks(n, 1).len(), n * n);
+    }
+
+    #[test]
+    fn walking() {
+        let curve = HilbertCurve::new(4);
+        let mut cells = [None; 256];
+        walk(4, |d, x, y, side| {
+            let (cx, cy) = curve.d_to_xy(d);
+            assert!((x..x + side).contains(&cx) && (y..y + side).contains(&cy));
+            if side == 1 {
+                cells[d] = Some((x, y));
+            }
+            ControlFlow::Continue(())
+        });
+        assert!(cells
+            .iter()
+            .enumerate()
+            .all(|(d, &c)| c == Some(curve.d_to_xy(d))));
+
+        // prune the blocks outside of the rectangle spanning 2..=5 and 9..=10
+        let (mut visited, mut inside) = (0, 0);
+        walk(4, |_, x, y, side| {
+            visited += 1;
+            if x + side <= 2 || x > 5 || y + side <= 9 || y > 10 {
+                return ControlFlow::Break(());
+            }
+            inside += (side == 1) as usize;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(inside, 8);
+        assert!(visited < 64);
+    }
+
+    #[test]
+    fn stepping() {
+        for &n in &[1, 2, 4, 8, 16, 32] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                let next = if d + 1 < n * n {
+                    Some(convert_1d_to_2d(d + 1, n))
+                } else {
+                    None
+                };
+                let prev = if d > 0 {
+                    Some(convert_1d_to_2d(d - 1, n))
+                } else {
+                    None
+                };
+                assert_eq!(next_point(x, y, d, n), next);
+                assert_eq!(prev_point(x, y, d, n), prev);
+            }
+        }
+    }
+
+    #[test]
+    fn range() {
+        let curve = HilbertCurve::new(4);
+        let all: Vec<_> = curve.iter().collect();
+        for start in 0..curve.len() {
+            for end in start..=curve.len() {
+                assert!(curve.range(start..end).eq(all[start..end].iter().copied()));
+            }
+        }
+        assert!(curve.range(7..7).rev().eq(None));
+    }
+
+    #[test]
+    fn double_ended() {
+        let curve = HilbertCurve::new(3);
+        let forward: Vec<_> = curve.iter().collect();
+        let mut backward: Vec<_> = curve.iter().rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        let mut iter = curve.iter();
+        assert_eq!(iter.len(), 64);
+        let mut meet = Vec::new();
+        while let (Some(a), Some(b)) = (iter.next(), iter.next_back()) {
+            meet.push(a);
+            meet.push(b);
+        }
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        meet.sort_unstable();
+        assert_eq!(meet, forward);
+    }
+
+    #[test]
+    fn neighbors() {
+        let n = 8;
+        for d in 0..(n * n) {
+            let (x, y) = convert_1d_to_2d(d, n);
+            let (prev, next) = curve_neighbors(x, y, n);
+            assert_eq!(prev, d.checked_sub(1).map(|d| convert_1d_to_2d(d, n)));
+            assert_eq!(
+                next,
+                Some(d + 1)
+                    .filter(|&d| d < n * n)
+                    .map(|d| convert_1d_to_2d(d, n))
+            );
+
+            let neighbors: Vec<_> = grid_neighbors_with_d(x, y, n).collect();
+            let expected = [x == 0, x == n - 1, y == 0, y == n - 1];
+            assert_eq!(
+                neighbors.len(),
+                4 - expected.iter().filter(|&&edge| edge).count()
+            );
+            for &(nd, nx, ny) in &neighbors {
+                assert_eq!(convert_1d_to_2d(nd, n), (nx, ny));
+                assert_eq!(nx.abs_diff(x) + ny.abs_diff(y), 1);
+            }
+            assert!(neighbors
+                .iter()
+                .any(|&(nd, _, _)| nd + 1 == d || nd == d + 1));
+        }
+    }
+
+    #[test]
+    fn headings() {
+        let n = 16;
+        for d in 0..(n * n) {
+            let (x, y) = convert_1d_to_2d(d, n);
+            let expected = next_point(x, y, d, n).map(|(nx, ny)| {
+                match (nx as isize - x as isize, ny as isize - y as isize) {
+                    (0, 1) => Direction::Up,
+                    (0, -1) => Direction::Down,
+                    (-1, 0) => Direction::Left,
+                    _ => Direction::Right,
+                }
+            });
+            assert_eq!(heading_at(d, n), expected);
+        }
+        assert_eq!(heading_at(0, 2), Some(Direction::Up));
+        assert_eq!(heading_at(0, 4), Some(Direction::Right));
+    }
+
+    #[test]
+    fn moves() {
+        let directions = [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ];
+        for order in 0..=5 {
+            let n = 1 << order;
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                for &direction in &directions {
+                    let (dx, dy) = direction.offset();
+                    let expected = x
+                        .checked_add_signed(dx)
+                        .zip(y.checked_add_signed(dy))
+                        .filter(|&(nx, ny)| nx < n && ny < n)
+                        .map(|(nx, ny)| convert_2d_to_1d(nx, ny, n));
+                    assert_eq!(index_after_move(d, direction, n), expected);
+                }
+            }
+        }
+    }
+}
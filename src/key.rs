@@ -0,0 +1,200 @@
+//! A spatial key for ordered containers.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+
+#[cfg(feature = "serde")]
+use crate::HilbertError;
+use crate::{convert_1d_to_2d, convert_2d_to_1d, GridPoint, HilbertCurve, HilbertIndex};
+
+/// A cell of a Hilbert curve of a given order, ordered by its order and then by its distance
+/// along the curve.
+///
+/// Used as the key of a `BTreeMap`, the entries of every order are stored in curve order, so a
+/// range scan over the map visits a segment of the curve; [`HilbertKey::ranges_for_rect`] builds
+/// the ranges of keys covering a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HilbertKey {
+    order: u32,
+    d: usize,
+}
+
+impl HilbertKey {
+    /// Create the key of the cell (x, y) on a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or either coordinate is not
+    /// lower than `2^order`.
+    pub fn new(x: usize, y: usize, order: u32) -> Self {
+        let n = side(order);
+        assert!(x < n && y < n, "coordinates must be lower than 2^order");
+        HilbertKey {
+            order,
+            d: convert_2d_to_1d(x, y, n),
+        }
+    }
+
+    /// Create the key of the cell at distance `d` on a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than
+    /// `4^order`.
+    pub fn from_distance(d: usize, order: u32) -> Self {
+        let n = side(order);
+        assert!(d < n * n, "d must be lower than 4^order");
+        HilbertKey { order, d }
+    }
+
+    /// Create the key of the given point on a curve of the given order.
+    pub fn from_point(point: GridPoint, order: u32) -> Self {
+        Self::new(point.x, point.y, order)
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The distance of the cell along the curve.
+    pub fn distance(&self) -> usize {
+        self.d
+    }
+
+    /// The cell the key corresponds to.
+    pub fn point(&self) -> GridPoint {
+        convert_1d_to_2d(self.d, side(self.order)).into()
+    }
+
+    /// The key of the cell containing this one on the curve of the previous order; returns `None`
+    /// if the order is 0.
+    pub fn parent(&self) -> Option<HilbertKey> {
+        crate::parent(self.d, self.order).map(|d| HilbertKey {
+            order: self.order - 1,
+            d,
+        })
+    }
+
+    /// The keys of the 4 cells contained in this one on the curve of the next order, in curve
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the order is [`HilbertCurve::MAX_ORDER`].
+    pub fn children(&self) -> impl Iterator<Item = HilbertKey> {
+        let order = self.order + 1;
+        crate::children(self.d, self.order).map(move |d| HilbertKey { order, d })
+    }
+
+    /// Get the ranges of keys covering the rectangle spanning `x0..=x1` and `y0..=y1` on a curve of
+    /// the given order, as decomposed by [`ranges_for_rect`](crate::ranges_for_rect).
+    #[cfg(feature = "alloc")]
+    pub fn ranges_for_rect(
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+        order: u32,
+        max_ranges: usize,
+    ) -> Vec<Range<HilbertKey>> {
+        // the end of a range may be one past the end of the curve, which is still a valid bound
+        let key = |d| HilbertKey { order, d };
+        crate::ranges_for_rect(x0, y0, x1, y1, side(order), max_ranges)
+            .into_iter()
+            .map(|range| key(range.start)..key(range.end))
+            .collect()
+    }
+}
+
+impl From<HilbertKey> for HilbertIndex {
+    fn from(key: HilbertKey) -> Self {
+        HilbertIndex(key.d as u64)
+    }
+}
+
+// the distance is validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HilbertKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "HilbertKey")]
+        struct Raw {
+            order: u32,
+            d: usize,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        if raw.order > HilbertCurve::MAX_ORDER {
+            return Err(serde::de::Error::custom(HilbertError::OrderTooLarge {
+                order: raw.order,
+            }));
+        }
+        let n = side(raw.order);
+        if raw.d >= n * n {
+            return Err(serde::de::Error::custom(HilbertError::DistanceOutOfRange {
+                d: raw.d,
+                n,
+            }));
+        }
+
+        Ok(HilbertKey {
+            order: raw.order,
+            d: raw.d,
+        })
+    }
+}
+
+fn side(order: u32) -> usize {
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+    1 << order
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn conversions() {
+        let key = HilbertKey::new(3, 1, 2);
+        assert_eq!(key, HilbertKey::from_distance(convert_2d_to_1d(3, 1, 4), 2));
+        assert_eq!(key.point(), GridPoint::new(3, 1));
+        assert_eq!(HilbertKey::from_point(key.point(), 2), key);
+        assert_eq!(HilbertIndex::from(key), HilbertIndex(key.distance() as u64));
+        assert!(HilbertKey::new(0, 0, 3) > HilbertKey::new(3, 0, 2));
+
+        let children: Vec<_> = key.children().collect();
+        assert_eq!(children.len(), 4);
+        for child in children {
+            assert_eq!(child.parent(), Some(key));
+            assert_eq!(child.point().x / 2, 3);
+        }
+    }
+
+    #[test]
+    fn range_scans() {
+        let order = 4;
+        let map: BTreeMap<_, _> = (0..16)
+            .flat_map(|x| (0..16).map(move |y| (HilbertKey::new(x, y, order), (x, y))))
+            .collect();
+
+        let ranges = HilbertKey::ranges_for_rect(2, 3, 9, 11, order, usize::MAX);
+        let mut found: Vec<_> = ranges
+            .into_iter()
+            .flat_map(|range| map.range(range).map(|(_, &point)| point))
+            .collect();
+        found.sort_unstable();
+        let expected: Vec<_> = (2..=9)
+            .flat_map(|x| (3..=11).map(move |y| (x, y)))
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(map.range(..HilbertKey::new(1, 0, order)).count(), 1);
+    }
+}
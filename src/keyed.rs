@@ -0,0 +1,228 @@
+//! Keyed variants of the Hilbert curve, scrambled by a secret key.
+//!
+//! Every node of the quadtree of the curve is transformed by one of the 8 symmetries of the
+//! square, chosen by hashing the key with the position of the node; the sub-squares of every node
+//! are still visited in the U-shaped order of the Hilbert curve, so every aligned block of the
+//! grid remains a contiguous range of distances and consecutive blocks of the same parent remain
+//! adjacent. The price is the continuity of the curve: a sub-curve no longer enters its square at
+//! the corner where its predecessor left, so consecutive cells of different blocks are in general
+//! not adjacent.
+//!
+//! Without the key the distance of a cell doesn't reveal its position, but the hash is SplitMix64,
+//! which is fast rather than cryptographic: the scrambling hides positions from casual observers,
+//! not from an attacker who knows the cells of many distances.
+
+use crate::{analysis::splitmix64, GridPoint, HilbertCurve, SpaceFillingCurve};
+
+// A symmetry of the square: swap the axes (bit 0), then flip x (bit 1) and y (bit 2)
+type Symmetry = u8;
+
+// The quadrants of the canonical node in curve order and the symmetries of their sub-curves
+const QUADRANTS: [(usize, usize); 4] = [(0, 0), (0, 1), (1, 1), (1, 0)];
+const SUB_CURVES: [Symmetry; 4] = [0b001, 0, 0, 0b111];
+
+/// A Hilbert curve filling a square grid of `2^order` by `2^order` cells, scrambled by a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyedCurve {
+    order: u32,
+    key: u64,
+}
+
+impl KeyedCurve {
+    /// Create a curve of the given order scrambled by `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32, key: u64) -> Self {
+        assert!(order <= HilbertCurve::MAX_ORDER, "the order is too large");
+        KeyedCurve { order, key }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        1 << self.order
+    }
+
+    /// Convert a distance `d` to the coordinates of its cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is not lower than the length of the curve.
+    pub fn d_to_xy(&self, d: usize) -> (usize, usize) {
+        assert!(
+            d < self.len(),
+            "d must be lower than the length of the curve"
+        );
+        let (mut x, mut y) = (0, 0);
+        let (mut node, mut symmetry) = (1u64, 0);
+
+        for level in (0..self.order).rev() {
+            symmetry = compose(symmetry, self.scramble(node));
+            let digit = (d >> (2 * level)) & 3;
+            let (qx, qy) = apply(symmetry, QUADRANTS[digit]);
+            x = x << 1 | qx;
+            y = y << 1 | qy;
+            symmetry = compose(symmetry, SUB_CURVES[digit]);
+            node = node << 2 | digit as u64;
+        }
+
+        (x, y)
+    }
+
+    /// Convert the coordinates of a cell to its distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate is not lower than the side of the grid.
+    pub fn xy_to_d(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.side() && y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        let mut d = 0;
+        let (mut node, mut symmetry) = (1u64, 0);
+
+        for level in (0..self.order).rev() {
+            symmetry = compose(symmetry, self.scramble(node));
+            let (qx, qy) = apply(inverse(symmetry), ((x >> level) & 1, (y >> level) & 1));
+            let digit = (3 * qx) ^ qy;
+            d = d << 2 | digit;
+            symmetry = compose(symmetry, SUB_CURVES[digit]);
+            node = node << 2 | digit as u64;
+        }
+
+        d
+    }
+
+    // The symmetry of the node with the given id: its digits from the top, after a marker bit
+    fn scramble(&self, node: u64) -> Symmetry {
+        let mut state = node;
+        let mut state = self.key ^ splitmix64(&mut state);
+        (splitmix64(&mut state) & 7) as Symmetry
+    }
+}
+
+impl SpaceFillingCurve for KeyedCurve {
+    fn len(&self) -> usize {
+        self.side() * self.side()
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        self.d_to_xy(index).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        self.xy_to_d(point.x, point.y)
+    }
+}
+
+fn apply(symmetry: Symmetry, (x, y): (usize, usize)) -> (usize, usize) {
+    let (x, y) = if symmetry & 1 != 0 { (y, x) } else { (x, y) };
+    (
+        x ^ (symmetry >> 1 & 1) as usize,
+        y ^ (symmetry >> 2 & 1) as usize,
+    )
+}
+
+// The symmetry applying `b` and then `a`
+fn compose(a: Symmetry, b: Symmetry) -> Symmetry {
+    let (bx, by) = (b >> 1 & 1, b >> 2 & 1);
+    let (bx, by) = if a & 1 != 0 { (by, bx) } else { (bx, by) };
+    ((a ^ b) & 1) | ((a ^ (bx << 1) ^ (by << 2)) & 0b110)
+}
+
+fn inverse(symmetry: Symmetry) -> Symmetry {
+    let (fx, fy) = (symmetry >> 1 & 1, symmetry >> 2 & 1);
+    let (fx, fy) = if symmetry & 1 != 0 {
+        (fy, fx)
+    } else {
+        (fx, fy)
+    };
+    (symmetry & 1) | fx << 1 | fy << 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetries() {
+        for a in 0..8 {
+            assert_eq!(compose(a, inverse(a)), 0);
+            for b in 0..8 {
+                for p in QUADRANTS {
+                    assert_eq!(apply(compose(a, b), p), apply(a, apply(b, p)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bijection() {
+        let curve = KeyedCurve::new(5, 0xdead_beef);
+        let mut seen = [false; 1024];
+        for d in 0..curve.len() {
+            let (x, y) = curve.d_to_xy(d);
+            assert_eq!(curve.xy_to_d(x, y), d);
+            seen[y * 32 + x] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn scrambling() {
+        let order = 3;
+        let mut curves = Vec::new();
+        let mut jumps = 0;
+        for key in 0..64 {
+            let curve = KeyedCurve::new(order, key);
+            let cells: Vec<_> = (0..curve.len()).map(|d| curve.d_to_xy(d)).collect();
+            jumps += cells
+                .windows(2)
+                .filter(|pair| {
+                    let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                    x0.abs_diff(x1) + y0.abs_diff(y1) != 1
+                })
+                .count();
+            curves.push(cells);
+        }
+        // every key gives a different curve, which is not continuous across blocks
+        curves.sort_unstable();
+        curves.dedup();
+        assert_eq!(curves.len(), 64);
+        assert!(jumps > 0);
+    }
+
+    #[test]
+    fn locality() {
+        let order = 4;
+        let curve = KeyedCurve::new(order, 42);
+        let other = KeyedCurve::new(order, 43);
+        assert!((0..curve.len()).any(|d| curve.d_to_xy(d) != other.d_to_xy(d)));
+        assert_eq!(KeyedCurve::new(order, 42).d_to_xy(100), curve.d_to_xy(100));
+
+        // the blocks of every level are contiguous and their consecutive children adjacent
+        for level in 0..order {
+            let size = 1 << level;
+            for d in (0..curve.len()).step_by(size * size) {
+                let (x, y) = curve.d_to_xy(d);
+                let block = (x / size, y / size);
+                for i in d..d + size * size {
+                    let (cx, cy) = curve.d_to_xy(i);
+                    assert_eq!((cx / size, cy / size), block);
+                }
+                if (d / (size * size)) % 4 != 3 {
+                    let (nx, ny) = curve.d_to_xy(d + size * size);
+                    let next = (nx / size, ny / size);
+                    assert_eq!(block.0.abs_diff(next.0) + block.1.abs_diff(next.1), 1);
+                }
+            }
+        }
+    }
+}
@@ -5,11 +5,232 @@
 //! coordinates: (0, 0) in the lower left corner, (n − 1, n − 1) in the upper right corner, and a
 //! distance d that starts at 0 in the lower left corner and goes to n^2 − 1 in the lower-right
 //! corner.
+//!
+//! Curves with more than two dimensions are supported by the [`nd`] module, conversions with an
+//! integer type other than `usize` by the [`generic`] module, and vectorized batch conversions by
+//! the [`simd`] module.
+//!
+//! Other space-filling curves are available in their own modules:
+//!
+//! - [`gosper`]: the Gosper curve (flowsnake), for hexagonal grids
+//! - [`gray`]: the Gray-code curve
+//! - [`keyed`]: variants of the Hilbert curve scrambled by a secret key
+//! - [`moore`]: the closed Moore variant of the Hilbert curve
+//! - [`morton`]: the Morton (Z-order) curve
+//! - [`peano`]: the Peano curve, for grids with a side that is a power of 3
+//! - [`sierpinski`]: the Sierpiński curve, for triangulated grids
+//!
+//! # Features
+//!
+//! The crate is `no_std`-compatible when its default features are disabled.
+//!
+//! - `std` (default): implies `alloc` and enables runtime CPU feature detection, the bit streams
+//!   of `bitstream` and the exports of `export`
+//! - `alloc`: functions returning vectors
+//! - `arbitrary`: `arbitrary` implementations for structured fuzzing
+//! - `arrow`: conversions of Apache Arrow columns
+//! - `bigint`: arbitrary-precision conversions using `num-bigint`
+//! - `cli`: the `hilbert` command-line tool
+//! - `derive`: the `HilbertSortable` derive macro
+//! - `ffi`: a C interface
+//! - `futures`: asynchronous streams of curve points and range decompositions
+//! - `geo`: mapping of latitudes and longitudes onto the grid, and with `std` onto the faces of a
+//!   cube covering the sphere
+//! - `geo-types`: conversions using the types of the `geo-types` crate
+//! - `glam`: conversions using the vector types of `glam`
+//! - `image`: raster images of the curve using the `image` crate
+//! - `nalgebra`: conversions using the point type of `nalgebra`
+//! - `ndarray`: reordering of `ndarray` arrays along the curve
+//! - `python`: a PyO3 module for Python
+//! - `rayon`: parallel batch conversions and iteration over the curve
+//! - `rstar`: Hilbert ordering of the objects of `rstar` R-trees
+//! - `serde`: serialization of the public types
+//! - `sqlite`: the registration of SQL functions on SQLite connections
+//! - `sqlite-extension`: implies `sqlite` and adds the entry point of a SQLite loadable extension
+//! - `svg`: SVG drawings of the curve
+//! - `wasm`: `wasm-bindgen` exports for JavaScript
+//! - `wgpu`: batch conversions on the GPU using `wgpu`
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use std::mem;
+use core::mem;
+
+mod adaptive;
+pub mod analysis;
+#[cfg(feature = "alloc")]
+mod ann;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "alloc")]
+pub mod base32;
+mod batch;
+#[cfg(feature = "bigint")]
+pub mod bigint;
+pub mod bits;
+#[cfg(feature = "std")]
+pub mod bitstream;
+mod bmi2;
+mod cell_id;
+#[cfg(feature = "alloc")]
+mod cluster;
+pub mod compact;
+pub mod continuous;
+mod curve;
+#[cfg(feature = "alloc")]
+pub mod delta;
+mod error;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixed;
+#[cfg(feature = "futures")]
+pub mod futures;
+pub mod generic;
+#[cfg(feature = "geo")]
+pub mod geo;
+#[cfg(feature = "geo-types")]
+pub mod geo_types;
+#[cfg(feature = "glam")]
+pub mod glam;
+pub mod gosper;
+pub mod gray;
+mod hierarchy;
+#[cfg(feature = "image")]
+pub mod image;
+mod indexable;
+pub mod ip;
+mod iter;
+mod key;
+pub mod keyed;
+mod lut;
+pub mod moore;
+pub mod morton;
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra;
+pub mod nd;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+mod orientation;
+#[cfg(feature = "alloc")]
+mod packing;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "alloc")]
+mod partition;
+#[cfg(feature = "alloc")]
+mod path;
+pub mod peano;
+pub mod pseudo;
+#[cfg(feature = "python")]
+mod python;
+pub mod quadkey;
+pub mod quantize;
+mod query;
+mod rect;
+#[cfg(feature = "alloc")]
+pub mod render;
+#[cfg(feature = "rstar")]
+pub mod rstar;
+#[cfg(feature = "alloc")]
+mod search;
+mod shard;
+pub mod sierpinski;
+#[cfg(feature = "alloc")]
+pub mod simd;
+#[cfg(feature = "alloc")]
+mod sort;
+mod space_filling;
+pub mod spacetime;
+#[cfg(all(feature = "geo", feature = "std"))]
+pub mod sphere;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod table;
+pub mod torus;
+mod traverse;
+pub mod turtle;
+mod types;
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "wgpu")]
+pub mod wgpu;
+
+pub use adaptive::{AdaptiveHilbert, Backend};
+#[cfg(feature = "alloc")]
+pub use ann::AnnIndex;
+#[cfg(feature = "alloc")]
+pub use batch::{convert_1d_to_2d_batch, convert_2d_to_1d_batch, layout_heatmap, sample_points};
+pub use batch::{
+    convert_1d_to_2d_into, convert_1d_to_2d_split_into, convert_2d_to_1d_into,
+    convert_2d_to_1d_split_into,
+};
+pub use cell_id::CellId;
+#[cfg(feature = "alloc")]
+pub use cluster::{cluster_by_gap, cluster_by_size};
+pub use curve::HilbertCurve;
+pub use error::HilbertError;
+pub use generic::{
+    convert_1d_to_2d_u128, convert_1d_to_2d_u32, convert_1d_to_2d_u64, convert_2d_to_1d_u128,
+    convert_2d_to_1d_u32, convert_2d_to_1d_u64,
+};
+pub use hierarchy::{children, demote, parent, promote};
+#[cfg(feature = "derive")]
+pub use hilbert_curve_derive::HilbertSortable;
+pub use indexable::HilbertIndexable;
+pub use iter::{
+    blocks, curve_neighbors, grid_neighbors_with_d, heading_at, index_after_move, next_point,
+    prev_point, walk, Block, Blocks, Iter,
+};
+pub use key::HilbertKey;
+pub use lut::LutHilbert;
+pub use nd::{convert_1d_to_3d, convert_1d_to_point, convert_3d_to_1d, convert_point_to_1d};
+#[cfg(feature = "alloc")]
+pub use nd::{convert_1d_to_nd, convert_nd_to_1d};
+pub use orientation::{
+    all_variants, transform, Axis, CoordinateSystem, Corner, Direction, Orientation,
+};
+#[cfg(feature = "alloc")]
+pub use packing::{pack_rtree, pack_rtree_items};
+#[cfg(feature = "rayon")]
+pub use par::{par_convert_1d_to_2d_batch, par_convert_2d_to_1d_batch};
+#[cfg(feature = "alloc")]
+pub use partition::{partition, partition_items, partition_weighted, Partition};
+#[cfg(feature = "alloc")]
+pub use path::{path_points, path_points_scaled, refinement_frames};
+#[cfg(feature = "alloc")]
+pub use query::{
+    byte_ranges_for_rect, cells_on_segment, ranges_for_polygon, ranges_for_rect,
+    try_ranges_for_rect,
+};
+pub use query::{count_in_rect, segment_bounding_box};
+#[cfg(feature = "alloc")]
+pub use query::{merge_ranges, rect_ranges, simplify_ranges, RectRanges};
+pub use rect::{convert_1d_to_2d_rect, convert_2d_to_1d_rect};
+#[cfg(feature = "alloc")]
+pub use search::{curve_window, nearest_neighbors};
+#[cfg(feature = "alloc")]
+pub use shard::shard_ranges;
+pub use shard::{shard_for_d, shard_for_point, shard_range};
+#[cfg(feature = "alloc")]
+pub use sort::{hilbert_argsort, hilbert_sort, sort_by_hilbert_key};
+pub use space_filling::SpaceFillingCurve;
+pub use traverse::{
+    for_each_hilbert, for_each_hilbert_mut, for_each_hilbert_strided, for_each_hilbert_strided_mut,
+};
+pub use types::{GridPoint, HilbertIndex};
+pub use validate::{are_curve_adjacent, is_valid_index, verify_curve, CurveDefect};
 
 /// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
-pub fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
+pub const fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
     assert!((n & (n - 1)) == 0, "n must be a power of 2");
     let mut s = 1;
     let mut t = d;
@@ -30,7 +251,7 @@ pub fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
 }
 
 /// Convert a pair of (x, y) coordinates to a one-dimensional distance.
-pub fn convert_2d_to_1d (x: usize, y: usize, n: usize) -> usize {
+pub const fn convert_2d_to_1d(x: usize, y: usize, n: usize) -> usize {
     assert!((n & (n - 1)) == 0, "n must be a power of 2");
     let mut d = 0;
     let mut s = n / 2;
@@ -48,8 +269,82 @@ pub fn convert_2d_to_1d (x: usize, y: usize, n: usize) -> usize {
     d
 }
 
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates on a curve of the given
+/// order, i.e. in a grid with a side of `2^order`.
+pub const fn convert_1d_to_2d_order(d: usize, order: u32) -> (usize, usize) {
+    convert_1d_to_2d(d, 1 << order)
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance on a curve of the given
+/// order, i.e. in a grid with a side of `2^order`.
+pub const fn convert_2d_to_1d_order(x: usize, y: usize, order: u32) -> usize {
+    convert_2d_to_1d(x, y, 1 << order)
+}
+
+/// Convert a one-dimensional distance `d` to a pair of signed (x, y) coordinates in a grid
+/// centered on the origin, i.e. with coordinates in [−n/2, n/2).
+pub fn convert_1d_to_2d_signed(d: usize, n: usize) -> (isize, isize) {
+    let (x, y) = convert_1d_to_2d(d, n);
+    let offset = (n / 2) as isize;
+    (x as isize - offset, y as isize - offset)
+}
+
+/// Convert a pair of signed (x, y) coordinates in a grid centered on the origin, i.e. with
+/// coordinates in [−n/2, n/2), to a one-dimensional distance.
+pub fn convert_2d_to_1d_signed(x: isize, y: isize, n: usize) -> usize {
+    let offset = (n / 2) as isize;
+    let range = -offset..(n as isize - offset);
+    assert!(
+        range.contains(&x) && range.contains(&y),
+        "coordinates must be within [-n/2, n/2)"
+    );
+    convert_2d_to_1d((x + offset) as usize, (y + offset) as usize, n)
+}
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates, returning an error
+/// instead of panicking if the inputs are invalid.
+pub fn try_convert_1d_to_2d(d: usize, n: usize) -> Result<(usize, usize), HilbertError> {
+    if d >= HilbertCurve::try_from_side(n)?.len() {
+        return Err(HilbertError::DistanceOutOfRange { d, n });
+    }
+
+    Ok(convert_1d_to_2d(d, n))
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance, returning an error instead
+/// of panicking if the inputs are invalid.
+pub fn try_convert_2d_to_1d(x: usize, y: usize, n: usize) -> Result<usize, HilbertError> {
+    HilbertCurve::try_from_side(n)?;
+    if x >= n || y >= n {
+        return Err(HilbertError::CoordinateOutOfRange { x, y, n });
+    }
+
+    Ok(convert_2d_to_1d(x, y, n))
+}
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates, returning `None` if the
+/// inputs are invalid.
+pub fn checked_convert_1d_to_2d(d: usize, n: usize) -> Option<(usize, usize)> {
+    try_convert_1d_to_2d(d, n).ok()
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance, returning `None` if the
+/// inputs are invalid.
+pub fn checked_convert_2d_to_1d(x: usize, y: usize, n: usize) -> Option<usize> {
+    try_convert_2d_to_1d(x, y, n).ok()
+}
+
+/// Rotate the coordinates (x, y) within a quadrant of side `n`, given the bits `rx` and `ry` of the
+/// quadrant at this level; this is the per-level transformation applied by the conversions, and it
+/// is its own inverse.
+pub const fn rotate_quadrant(n: usize, x: usize, y: usize, rx: usize, ry: usize) -> (usize, usize) {
+    let (mut x, mut y) = (x, y);
+    rotate(n, &mut x, &mut y, rx, ry);
+    (x, y)
+}
+
 // Rotate a quadrant
-fn rotate(n: usize, x: &mut usize, y: &mut usize, rx: usize, ry: usize) {
+const fn rotate(n: usize, x: &mut usize, y: &mut usize, rx: usize, ry: usize) {
     if ry == 0 {
         if rx == 1 {
             *x = n.wrapping_sub(1).wrapping_sub(*x);
@@ -73,4 +368,93 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn const_evaluation() {
+        const POINT: (usize, usize) = convert_1d_to_2d(37, 8);
+        const DISTANCE: usize = convert_2d_to_1d(POINT.0, POINT.1, 8);
+        assert_eq!(POINT, convert_1d_to_2d(37, 8));
+        assert_eq!(DISTANCE, 37);
+    }
+
+    #[test]
+    fn order_based() {
+        for order in 0..8 {
+            let n = 1 << order;
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d_order(d, order);
+                assert_eq!((x, y), convert_1d_to_2d(d, n));
+                assert_eq!(convert_2d_to_1d_order(x, y, order), d);
+            }
+        }
+    }
+
+    #[test]
+    fn signed() {
+        for &n in &[1, 2, 4, 8, 16] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d_signed(d, n);
+                let (ux, uy) = convert_1d_to_2d(d, n);
+                let offset = (n / 2) as isize;
+                assert_eq!((x + offset, y + offset), (ux as isize, uy as isize));
+                assert_eq!(convert_2d_to_1d_signed(x, y, n), d);
+            }
+        }
+        assert_eq!(convert_1d_to_2d_signed(0, 8), (-4, -4));
+    }
+
+    #[test]
+    fn fallible() {
+        assert_eq!(try_convert_1d_to_2d(5, 4), Ok(convert_1d_to_2d(5, 4)));
+        assert_eq!(try_convert_2d_to_1d(3, 1, 4), Ok(convert_2d_to_1d(3, 1, 4)));
+        assert_eq!(
+            try_convert_1d_to_2d(0, 0),
+            Err(HilbertError::NotPowerOfTwo { n: 0 })
+        );
+        assert_eq!(
+            try_convert_2d_to_1d(0, 0, 6),
+            Err(HilbertError::NotPowerOfTwo { n: 6 })
+        );
+        assert_eq!(
+            try_convert_1d_to_2d(16, 4),
+            Err(HilbertError::DistanceOutOfRange { d: 16, n: 4 })
+        );
+        assert_eq!(
+            try_convert_2d_to_1d(1, 4, 4),
+            Err(HilbertError::CoordinateOutOfRange { x: 1, y: 4, n: 4 })
+        );
+
+        // the sides of curves longer than a usize are rejected instead of overflowing
+        let order = HilbertCurve::MAX_ORDER + 1;
+        let error = HilbertError::OrderTooLarge { order };
+        assert_eq!(try_convert_2d_to_1d(0, 0, 1 << order), Err(error));
+        assert_eq!(try_convert_1d_to_2d(usize::MAX, 1 << order), Err(error));
+        assert_eq!(checked_convert_2d_to_1d(0, 0, 1 << order), None);
+    }
+
+    #[test]
+    fn checked() {
+        assert_eq!(checked_convert_1d_to_2d(5, 4), Some(convert_1d_to_2d(5, 4)));
+        assert_eq!(
+            checked_convert_2d_to_1d(3, 1, 4),
+            Some(convert_2d_to_1d(3, 1, 4))
+        );
+        assert_eq!(checked_convert_1d_to_2d(16, 4), None);
+        assert_eq!(checked_convert_2d_to_1d(0, 0, 3), None);
+    }
+
+    #[test]
+    fn quadrant_rotation() {
+        for (rx, ry) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            for x in 0..4 {
+                for y in 0..4 {
+                    let (rx2, ry2) = rotate_quadrant(4, x, y, rx, ry);
+                    assert_eq!(rotate_quadrant(4, rx2, ry2, rx, ry), (x, y));
+                }
+            }
+        }
+        assert_eq!(rotate_quadrant(4, 1, 0, 0, 0), (0, 1));
+        assert_eq!(rotate_quadrant(4, 1, 0, 1, 0), (3, 2));
+        assert_eq!(rotate_quadrant(4, 1, 0, 0, 1), (1, 0));
+    }
 }
@@ -0,0 +1,239 @@
+//! A lookup-table accelerated converter.
+//!
+//! The curve is traversed from its top level down, tracking the orientation of the current
+//! sub-curve as one of 4 states; precomputed tables map a state and 4 levels' worth of bits (8
+//! bits of the distance or 4 bits of each coordinate) to the converted bits and the next state.
+
+use crate::{HilbertCurve, HilbertError};
+
+// The number of curve levels handled by a single table lookup
+pub(crate) const LEVELS: u32 = 4;
+// The number of entries per state
+pub(crate) const ENTRIES: usize = 1 << (2 * LEVELS);
+// State flags: the sub-curve has its axes swapped and/or both of them reversed
+pub(crate) const SWAP: usize = 1;
+pub(crate) const REVERSE: usize = 2;
+
+/// A converter using precomputed state tables, which processes 8 bits of the distance per
+/// iteration instead of 2.
+///
+/// The tables don't depend on the order and are computed at compile time, so converters share
+/// them and creating one costs nothing; there is no binary form of them to save and load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LutHilbert {
+    curve: HilbertCurve,
+}
+
+impl LutHilbert {
+    /// Create a converter for a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        match Self::try_new(order) {
+            Ok(lut) => lut,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create a converter for a curve of the given order, returning an error if it is too large.
+    pub fn try_new(order: u32) -> Result<Self, HilbertError> {
+        Ok(LutHilbert {
+            curve: HilbertCurve::try_new(order)?,
+        })
+    }
+
+    /// The order of the curve, i.e. the number of bits per axis.
+    pub fn order(&self) -> u32 {
+        self.curve.order()
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        self.curve.side()
+    }
+
+    /// The number of cells visited by the curve.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.curve.len()
+    }
+
+    /// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is not lower than [`LutHilbert::len`].
+    pub fn d_to_xy(&self, d: usize) -> (usize, usize) {
+        assert!(
+            d < self.len(),
+            "d must be lower than the length of the curve"
+        );
+        let (chunks, mut state) = start(self.order());
+        let (mut x, mut y) = (0, 0);
+
+        for chunk in (0..chunks).rev() {
+            let bits = (d >> (2 * LEVELS * chunk)) & (ENTRIES - 1);
+            let entry = D_TO_XY[state * ENTRIES + bits] as usize;
+            x = (x << LEVELS) | (entry >> LEVELS) & 0xf;
+            y = (y << LEVELS) | entry & 0xf;
+            state = entry >> 8;
+        }
+
+        (x, y)
+    }
+
+    /// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is not lower than [`LutHilbert::side`].
+    pub fn xy_to_d(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.side() && y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        let (chunks, mut state) = start(self.order());
+        let mut d = 0;
+
+        for chunk in (0..chunks).rev() {
+            let xb = (x >> (LEVELS * chunk)) & 0xf;
+            let yb = (y >> (LEVELS * chunk)) & 0xf;
+            let entry = XY_TO_D[state * ENTRIES + (xb << LEVELS | yb)] as usize;
+            d = (d << (2 * LEVELS)) | entry & 0xff;
+            state = entry >> 8;
+        }
+
+        d
+    }
+}
+
+// The number of table lookups and the initial state for a curve of the given order; the order is
+// padded to a multiple of the levels per lookup, and every padding level transposes the curve
+pub(crate) fn start(order: u32) -> (u32, usize) {
+    let chunks = order.div_ceil(LEVELS);
+    let padding = chunks * LEVELS - order;
+    (chunks, if padding % 2 == 1 { SWAP } else { 0 })
+}
+
+// The tables of every converter; entry = converted bits | next state << 8
+static D_TO_XY: [u16; 4 * ENTRIES] = build_d_to_xy();
+static XY_TO_D: [u16; 4 * ENTRIES] = build_xy_to_d();
+
+const fn build_d_to_xy() -> [u16; 4 * ENTRIES] {
+    let mut table = [0; 4 * ENTRIES];
+    let mut i = 0;
+    while i < table.len() {
+        let (xy, next) = walk_d_to_xy(i / ENTRIES, i % ENTRIES);
+        table[i] = (xy | next << 8) as u16;
+        i += 1;
+    }
+
+    table
+}
+
+const fn build_xy_to_d() -> [u16; 4 * ENTRIES] {
+    let mut table = [0; 4 * ENTRIES];
+    let mut i = 0;
+    while i < table.len() {
+        let (d, next) = walk_xy_to_d(i / ENTRIES, i % ENTRIES);
+        table[i] = (d | next << 8) as u16;
+        i += 1;
+    }
+
+    table
+}
+
+// The state change caused by descending into the sub-curve with the given digit
+pub(crate) const fn descend(digit: usize) -> usize {
+    match digit {
+        0 => SWAP,
+        3 => SWAP | REVERSE,
+        _ => 0,
+    }
+}
+
+// The quadrant (x and y bits) of the sub-curve with the given digit, in the given state
+pub(crate) const fn quadrant(state: usize, digit: usize) -> (usize, usize) {
+    let (mut qx, mut qy) = match digit {
+        0 => (0, 0),
+        1 => (0, 1),
+        2 => (1, 1),
+        _ => (1, 0),
+    };
+    if state & SWAP != 0 {
+        core::mem::swap(&mut qx, &mut qy);
+    }
+    if state & REVERSE != 0 {
+        qx ^= 1;
+        qy ^= 1;
+    }
+    (qx, qy)
+}
+
+// Convert `LEVELS` base-4 digits of the distance to `LEVELS` bits of each coordinate, packed as
+// x << LEVELS | y
+pub(crate) const fn walk_d_to_xy(mut state: usize, bits: usize) -> (usize, usize) {
+    let (mut x, mut y) = (0, 0);
+
+    let mut level = LEVELS;
+    while level > 0 {
+        level -= 1;
+        let digit = (bits >> (2 * level)) & 3;
+        let (qx, qy) = quadrant(state, digit);
+        x = x << 1 | qx;
+        y = y << 1 | qy;
+        state ^= descend(digit);
+    }
+
+    (x << LEVELS | y, state)
+}
+
+// Convert `LEVELS` bits of each coordinate, packed as x << LEVELS | y, to `LEVELS` base-4 digits
+// of the distance
+pub(crate) const fn walk_xy_to_d(mut state: usize, bits: usize) -> (usize, usize) {
+    let mut d = 0;
+
+    let mut level = LEVELS;
+    while level > 0 {
+        level -= 1;
+        let mut qx = (bits >> (LEVELS + level)) & 1;
+        let mut qy = (bits >> level) & 1;
+        if state & REVERSE != 0 {
+            qx ^= 1;
+            qy ^= 1;
+        }
+        if state & SWAP != 0 {
+            core::mem::swap(&mut qx, &mut qy);
+        }
+        let digit = (3 * qx) ^ qy;
+        d = d << 2 | digit;
+        state ^= descend(digit);
+    }
+
+    (d, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+    #[test]
+    fn matches_conversion() {
+        for order in 0..=9 {
+            let lut = LutHilbert::new(order);
+            let n = lut.side();
+            for d in 0..lut.len() {
+                let (x, y) = lut.d_to_xy(d);
+                assert_eq!((x, y), convert_1d_to_2d(d, n));
+                assert_eq!(lut.xy_to_d(x, y), d);
+            }
+        }
+
+        let lut = LutHilbert::new(HilbertCurve::MAX_ORDER);
+        let (x, y) = (lut.side() - 1, lut.side() / 3);
+        assert_eq!(lut.xy_to_d(x, y), convert_2d_to_1d(x, y, lut.side()));
+    }
+}
@@ -0,0 +1,124 @@
+//! The Moore curve, a closed variant of the Hilbert curve whose last cell is adjacent to its
+//! first one.
+//!
+//! The grid is split into four quadrants, each traversed by a Hilbert curve of the next lower
+//! order: upward through the left half, starting at (n/2 − 1, 0), and downward through the right
+//! half, ending at (n/2, 0).
+
+use crate::{self as hilbert, GridPoint, HilbertCurve, SpaceFillingCurve};
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+pub const fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    if n == 1 {
+        return (0, 0);
+    }
+
+    let h = n / 2;
+    let quadrant = d / (h * h);
+    let (x, y) = hilbert::convert_1d_to_2d(d % (h * h), h);
+
+    match quadrant {
+        0 => (h - 1 - y, x),
+        1 => (h - 1 - y, x + h),
+        2 => (y + h, 2 * h - 1 - x),
+        _ => (y + h, h - 1 - x),
+    }
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+pub const fn convert_2d_to_1d(x: usize, y: usize, n: usize) -> usize {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    if n == 1 {
+        return 0;
+    }
+
+    let h = n / 2;
+    let (quadrant, lx, ly) = match (x < h, y < h) {
+        (true, true) => (0, y, h - 1 - x),
+        (true, false) => (1, y - h, h - 1 - x),
+        (false, false) => (2, 2 * h - 1 - y, x - h),
+        (false, true) => (3, h - 1 - y, x - h),
+    };
+
+    quadrant * h * h + hilbert::convert_2d_to_1d(lx, ly, h)
+}
+
+/// A Moore curve filling a square grid of `2^order` by `2^order` cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MooreCurve {
+    order: u32,
+}
+
+impl MooreCurve {
+    /// Create a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        assert!(order <= HilbertCurve::MAX_ORDER, "the order is too large");
+        MooreCurve { order }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        1 << self.order
+    }
+}
+
+impl SpaceFillingCurve for MooreCurve {
+    fn len(&self) -> usize {
+        self.side() * self.side()
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        assert!(
+            index < self.len(),
+            "index must be lower than the length of the curve"
+        );
+        convert_1d_to_2d(index, self.side()).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        assert!(
+            point.x < self.side() && point.y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        convert_2d_to_1d(point.x, point.y, self.side())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        for &n in &[1, 2, 4, 8, 16, 32, 64] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                assert!(x < n && y < n);
+                assert_eq!(convert_2d_to_1d(x, y, n), d);
+            }
+        }
+    }
+
+    #[test]
+    fn closed_loop() {
+        for &n in &[2, 4, 8, 16, 32] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                let (nx, ny) = convert_1d_to_2d((d + 1) % (n * n), n);
+                assert_eq!(x.abs_diff(nx) + y.abs_diff(ny), 1);
+            }
+        }
+        assert_eq!(convert_1d_to_2d(0, 8), (3, 0));
+        assert_eq!(convert_1d_to_2d(63, 8), (4, 0));
+    }
+}
@@ -0,0 +1,218 @@
+//! The Morton (Z-order) curve, with the same conventions as the Hilbert curve functions.
+//!
+//! The bits of x occupy the even bits of the distance and the bits of y the odd ones, so the curve
+//! starts at (0, 0) and ends at (n − 1, n − 1). The const conversions interleave the bits with
+//! shifts and masks; [`MortonCurve`] and the conversions of slices use the BMI2 instructions PDEP
+//! and PEXT when the CPU supports them.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{
+    bits, bmi2,
+    lut::{descend, quadrant, REVERSE, SWAP},
+    GridPoint, HilbertCurve, SpaceFillingCurve,
+};
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+pub const fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let (x, y) = bits::deinterleave_bits(d as u64);
+    (x as usize, y as usize)
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+pub const fn convert_2d_to_1d(x: usize, y: usize, n: usize) -> usize {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    bits::interleave_bits(x as u32, y as u32) as usize
+}
+
+/// Convert a slice of one-dimensional distances to their (x, y) coordinates.
+#[cfg(feature = "alloc")]
+pub fn convert_1d_to_2d_batch(ds: &[usize], n: usize) -> Vec<(usize, usize)> {
+    let mut points = alloc::vec![(0, 0); ds.len()];
+    convert_1d_to_2d_into(ds, &mut points, n);
+    points
+}
+
+/// Convert a slice of (x, y) coordinates to their one-dimensional distances.
+#[cfg(feature = "alloc")]
+pub fn convert_2d_to_1d_batch(points: &[(usize, usize)], n: usize) -> Vec<usize> {
+    let mut ds = alloc::vec![0; points.len()];
+    convert_2d_to_1d_into(points, &mut ds, n);
+    ds
+}
+
+/// Convert a slice of one-dimensional distances to their (x, y) coordinates, written to `out`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `out` is not as long as `ds`.
+pub fn convert_1d_to_2d_into(ds: &[usize], out: &mut [(usize, usize)], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert_eq!(
+        ds.len(),
+        out.len(),
+        "the output must be as long as the input"
+    );
+    bmi2::morton_1d_to_2d_into(ds, out);
+}
+
+/// Convert a slice of (x, y) coordinates to their one-dimensional distances, written to `out`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or `out` is not as long as `points`.
+pub fn convert_2d_to_1d_into(points: &[(usize, usize)], out: &mut [usize], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert_eq!(
+        points.len(),
+        out.len(),
+        "the output must be as long as the input"
+    );
+    bmi2::morton_2d_to_1d_into(points, out);
+}
+
+/// Convert a distance `d` along the Hilbert curve to the Morton code of the same cell.
+pub fn hilbert_to_morton(d: usize, n: usize) -> usize {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let mut state = 0;
+    let mut z = 0;
+
+    for level in (0..n.trailing_zeros()).rev() {
+        let digit = (d >> (2 * level)) & 3;
+        let (qx, qy) = quadrant(state, digit);
+        z = z << 2 | qy << 1 | qx;
+        state ^= descend(digit);
+    }
+
+    z
+}
+
+/// Convert the Morton code `z` of a cell to its distance along the Hilbert curve.
+pub fn morton_to_hilbert(z: usize, n: usize) -> usize {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let mut state = 0;
+    let mut d = 0;
+
+    for level in (0..n.trailing_zeros()).rev() {
+        let mut qx = (z >> (2 * level)) & 1;
+        let mut qy = (z >> (2 * level + 1)) & 1;
+        if state & REVERSE != 0 {
+            qx ^= 1;
+            qy ^= 1;
+        }
+        if state & SWAP != 0 {
+            core::mem::swap(&mut qx, &mut qy);
+        }
+        let digit = (3 * qx) ^ qy;
+        d = d << 2 | digit;
+        state ^= descend(digit);
+    }
+
+    d
+}
+
+/// A Morton curve filling a square grid of `2^order` by `2^order` cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MortonCurve {
+    order: u32,
+}
+
+impl MortonCurve {
+    /// Create a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        assert!(order <= HilbertCurve::MAX_ORDER, "the order is too large");
+        MortonCurve { order }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        1 << self.order
+    }
+}
+
+impl SpaceFillingCurve for MortonCurve {
+    fn len(&self) -> usize {
+        self.side() * self.side()
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        assert!(
+            index < self.len(),
+            "index must be lower than the length of the curve"
+        );
+        bmi2::morton_1d_to_2d(index).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        assert!(
+            point.x < self.side() && point.y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        bmi2::morton_2d_to_1d(point.x, point.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        for &n in &[1, 2, 4, 8, 16, 32, 64, 128] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                assert!(x < n && y < n);
+                assert_eq!(convert_2d_to_1d(x, y, n), d);
+            }
+        }
+    }
+
+    #[test]
+    fn z_order() {
+        let order: Vec<_> = (0..16).map(|d| convert_1d_to_2d(d, 4)).collect();
+        assert_eq!(&order[..4], &[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(order[4], (2, 0));
+        assert_eq!(order[15], (3, 3));
+        assert_eq!(convert_2d_to_1d(0, 0xffff, 1 << 16), 0xaaaa_aaaa);
+    }
+
+    #[test]
+    fn slices() {
+        let n = 64;
+        let ds: Vec<usize> = (0..n * n).rev().collect();
+        let points = convert_1d_to_2d_batch(&ds, n);
+        for (&d, &point) in ds.iter().zip(&points) {
+            assert_eq!(point, convert_1d_to_2d(d, n));
+        }
+        assert_eq!(convert_2d_to_1d_batch(&points, n), ds);
+
+        let curve = MortonCurve::new(HilbertCurve::MAX_ORDER);
+        let z = curve.len() / 3;
+        let point = curve.index_to_point(z);
+        assert_eq!((point.x, point.y), convert_1d_to_2d(z, curve.side()));
+        assert_eq!(curve.point_to_index(point), z);
+    }
+
+    #[test]
+    fn hilbert_conversion() {
+        for &n in &[1, 2, 4, 8, 16, 32, 64] {
+            for d in 0..(n * n) {
+                let (x, y) = crate::convert_1d_to_2d(d, n);
+                let z = hilbert_to_morton(d, n);
+                assert_eq!(z, convert_2d_to_1d(x, y, n));
+                assert_eq!(morton_to_hilbert(z, n), d);
+            }
+        }
+    }
+}
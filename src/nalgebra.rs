@@ -0,0 +1,66 @@
+//! Conversions using the point type of the `nalgebra` crate.
+
+use ::nalgebra::Point2;
+
+use crate::{
+    convert_1d_to_2d, convert_1d_to_2d_signed, convert_2d_to_1d, convert_2d_to_1d_signed, GridPoint,
+};
+
+/// Convert a one-dimensional distance `d` to the coordinates of its cell.
+pub fn d_to_point2(d: usize, n: usize) -> Point2<usize> {
+    let (x, y) = convert_1d_to_2d(d, n);
+    Point2::new(x, y)
+}
+
+/// Convert the coordinates of a cell to its one-dimensional distance.
+pub fn point2_to_d(point: Point2<usize>, n: usize) -> usize {
+    convert_2d_to_1d(point.x, point.y, n)
+}
+
+/// Convert a one-dimensional distance `d` to the signed coordinates of its cell in a grid centered
+/// on the origin, as described by [`convert_1d_to_2d_signed`].
+pub fn d_to_signed_point2(d: usize, n: usize) -> Point2<isize> {
+    let (x, y) = convert_1d_to_2d_signed(d, n);
+    Point2::new(x, y)
+}
+
+/// Convert the signed coordinates of a cell in a grid centered on the origin to its
+/// one-dimensional distance, as described by [`convert_2d_to_1d_signed`].
+///
+/// # Panics
+///
+/// Panics if the coordinates are not within `[-n/2, n/2)`.
+pub fn signed_point2_to_d(point: Point2<isize>, n: usize) -> usize {
+    convert_2d_to_1d_signed(point.x, point.y, n)
+}
+
+impl From<Point2<usize>> for GridPoint {
+    fn from(point: Point2<usize>) -> Self {
+        GridPoint::new(point.x, point.y)
+    }
+}
+
+impl From<GridPoint> for Point2<usize> {
+    fn from(point: GridPoint) -> Self {
+        Point2::new(point.x, point.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let n = 8;
+        for d in 0..(n * n) {
+            let point = d_to_point2(d, n);
+            assert_eq!(point2_to_d(point, n), d);
+            assert_eq!(Point2::from(GridPoint::from(point)), point);
+
+            let signed = d_to_signed_point2(d, n);
+            assert_eq!(signed.x, point.x as isize - 4);
+            assert_eq!(signed_point2_to_d(signed, n), d);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! N-dimensional Hilbert curve mapping, based on John Skilling's transpose algorithm
+//! ("Programming the Hilbert curve", AIP Conference Proceedings 707, 2004).
+//!
+//! The space is a hypercube with `2^bits` cells along each of the `dims` axes; the distance
+//! `d` goes from 0 to `2^(bits * dims) − 1`.
+//!
+//! The algorithm works on the "transposed" form of the distance, in which its bits are spread
+//! across one word per axis; [`axes_to_transpose`] and [`transpose_to_axes`] convert between it
+//! and the coordinates in place, while [`distance_to_transpose`] and [`transpose_to_distance`]
+//! convert between it and the distance. In two dimensions the curve is identical to the one used
+//! by the crate's top-level functions, so `convert_1d_to_point::<2>` can be used as an alternative
+//! 2D backend.
+
+/// Convert a one-dimensional distance `d` to a vector of `dims` coordinates.
+///
+/// # Panics
+///
+/// Panics if there are no dimensions or `bits * dims` exceeds the width of a `usize`.
+#[cfg(feature = "alloc")]
+pub fn convert_1d_to_nd(d: usize, bits: usize, dims: usize) -> alloc::vec::Vec<usize> {
+    assert_dimensions(bits, dims);
+    let mut coords = alloc::vec![0; dims];
+    distance_to_transpose(d, bits, &mut coords);
+    transpose_to_axes(&mut coords, bits);
+    coords
+}
+
+/// Convert a slice of coordinates to a one-dimensional distance.
+///
+/// # Panics
+///
+/// Panics if there are no dimensions or `bits * dims` exceeds the width of a `usize`, or a
+/// coordinate is not lower than `2^bits`.
+#[cfg(feature = "alloc")]
+pub fn convert_nd_to_1d(coords: &[usize], bits: usize) -> usize {
+    assert_dimensions(bits, coords.len());
+    assert!(
+        coords
+            .iter()
+            .all(|&c| bits == usize::BITS as usize || c >> bits == 0),
+        "coordinates must be lower than 2^bits"
+    );
+    let mut transposed = alloc::vec::Vec::from(coords);
+    axes_to_transpose(&mut transposed, bits);
+    transpose_to_distance(&transposed, bits)
+}
+
+/// Convert a one-dimensional distance `d` to an array of `D` coordinates.
+///
+/// # Panics
+///
+/// Panics if there are no dimensions or `bits * D` exceeds the width of a `usize`.
+pub fn convert_1d_to_point<const D: usize>(d: usize, bits: usize) -> [usize; D] {
+    assert_dimensions(bits, D);
+    let mut coords = [0; D];
+    distance_to_transpose(d, bits, &mut coords);
+    transpose_to_axes(&mut coords, bits);
+    coords
+}
+
+/// Convert an array of `D` coordinates to a one-dimensional distance.
+///
+/// # Panics
+///
+/// Panics if there are no dimensions or `bits * D` exceeds the width of a `usize`, or a
+/// coordinate is not lower than `2^bits`.
+pub fn convert_point_to_1d<const D: usize>(point: [usize; D], bits: usize) -> usize {
+    assert_dimensions(bits, D);
+    assert!(
+        point
+            .iter()
+            .all(|&c| bits == usize::BITS as usize || c >> bits == 0),
+        "coordinates must be lower than 2^bits"
+    );
+    let mut transposed = point;
+    axes_to_transpose(&mut transposed, bits);
+    transpose_to_distance(&transposed, bits)
+}
+
+/// Convert a one-dimensional distance `d` to a triple of (x, y, z) coordinates in a cube divided
+/// into n by n by n cells.
+pub fn convert_1d_to_3d(d: usize, n: usize) -> (usize, usize, usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let bits = n.trailing_zeros() as usize;
+    assert_dimensions(bits, 3);
+    let mut coords = [0; 3];
+    distance_to_transpose(d, bits, &mut coords);
+    transpose_to_axes(&mut coords, bits);
+    (coords[0], coords[1], coords[2])
+}
+
+/// Convert a triple of (x, y, z) coordinates to a one-dimensional distance.
+pub fn convert_3d_to_1d(x: usize, y: usize, z: usize, n: usize) -> usize {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let bits = n.trailing_zeros() as usize;
+    assert_dimensions(bits, 3);
+    let mut transposed = [x, y, z];
+    axes_to_transpose(&mut transposed, bits);
+    transpose_to_distance(&transposed, bits)
+}
+
+fn assert_dimensions(bits: usize, dims: usize) {
+    assert!(dims > 0, "dims must be greater than 0");
+    assert!(
+        bits.checked_mul(dims)
+            .is_some_and(|total| total <= usize::BITS as usize),
+        "bits * dims must not exceed the width of usize"
+    );
+}
+
+/// Spread the bits of the distance `d` over the transposed representation of a curve with `bits`
+/// bits per axis; the most significant bit of `d` becomes the top bit of `x[0]`, the next one the
+/// top bit of `x[1]` and so on. `x` must be zeroed beforehand.
+///
+/// # Panics
+///
+/// Panics if `x` is empty or `bits * x.len()` exceeds the width of a `usize`.
+pub fn distance_to_transpose(d: usize, bits: usize, x: &mut [usize]) {
+    let dims = x.len();
+    assert_dimensions(bits, dims);
+
+    for level in 0..bits {
+        for (i, xi) in x.iter_mut().enumerate() {
+            let pos = level * dims + (dims - 1 - i);
+            *xi |= ((d >> pos) & 1) << level;
+        }
+    }
+}
+
+/// Gather the bits of the transposed representation back into a single distance; the inverse of
+/// [`distance_to_transpose`].
+///
+/// # Panics
+///
+/// Panics if `x` is empty or `bits * x.len()` exceeds the width of a `usize`.
+pub fn transpose_to_distance(x: &[usize], bits: usize) -> usize {
+    let dims = x.len();
+    assert_dimensions(bits, dims);
+    let mut d = 0;
+
+    for level in 0..bits {
+        for (i, xi) in x.iter().enumerate() {
+            let pos = level * dims + (dims - 1 - i);
+            d |= ((xi >> level) & 1) << pos;
+        }
+    }
+
+    d
+}
+
+/// Convert a transposed Hilbert index in place to the coordinates it represents.
+///
+/// # Panics
+///
+/// Panics if `x` is empty or `bits * x.len()` exceeds the width of a `usize`.
+pub fn transpose_to_axes(x: &mut [usize], bits: usize) {
+    assert_dimensions(bits, x.len());
+    if bits == 0 {
+        return;
+    }
+    let n = x.len();
+    let top = 1 << (bits - 1);
+
+    // Gray decode
+    let mut t = x[n - 1] >> 1;
+    for i in (1..n).rev() {
+        x[i] ^= x[i - 1];
+    }
+    x[0] ^= t;
+
+    // undo excess work
+    let mut q = 2;
+    while q != top << 1 {
+        let p = q - 1;
+        for i in (0..n).rev() {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q <<= 1;
+    }
+}
+
+/// Convert coordinates in place to their transposed Hilbert index.
+///
+/// # Panics
+///
+/// Panics if `x` is empty or `bits * x.len()` exceeds the width of a `usize`.
+pub fn axes_to_transpose(x: &mut [usize], bits: usize) {
+    assert_dimensions(bits, x.len());
+    if bits == 0 {
+        return;
+    }
+    let n = x.len();
+    let top = 1 << (bits - 1);
+
+    // inverse undo
+    let mut q = top;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    for i in 1..n {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q = top;
+    while q > 1 {
+        if x[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for xi in x.iter_mut() {
+        *xi ^= t;
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        for dims in 1..=4 {
+            for bits in 0..=4 {
+                for d in 0..(1 << (bits * dims)) {
+                    let coords = convert_1d_to_nd(d, bits, dims);
+                    assert_eq!(convert_nd_to_1d(&coords, bits), d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_2d() {
+        for bits in 0..=6 {
+            let n = 1 << bits;
+            for d in 0..(n * n) {
+                let (x, y) = crate::convert_1d_to_2d(d, n);
+                assert_eq!(convert_1d_to_nd(d, bits, 2), vec![x, y]);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_3d() {
+        for bits in 0..=4 {
+            let n = 1 << bits;
+            for d in 0..(n * n * n) {
+                let (x, y, z) = convert_1d_to_3d(d, n);
+                assert_eq!(convert_1d_to_nd(d, bits, 3), vec![x, y, z]);
+                assert_eq!(convert_3d_to_1d(x, y, z, n), d);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_point() {
+        let bits = 3;
+        for d in 0..(1 << (bits * 4)) {
+            let point = convert_1d_to_point::<4>(d, bits);
+            assert_eq!(convert_1d_to_nd(d, bits, 4), point.to_vec());
+            assert_eq!(convert_point_to_1d(point, bits), d);
+        }
+    }
+
+    #[test]
+    fn transpose() {
+        let mut x = [0; 3];
+        distance_to_transpose(0b101_110, 2, &mut x);
+        assert_eq!(x, [0b11, 0b01, 0b10]);
+        assert_eq!(transpose_to_distance(&x, 2), 0b101_110);
+
+        let mut point = x;
+        transpose_to_axes(&mut point, 2);
+        assert_eq!(point, convert_1d_to_point::<3>(0b101_110, 2));
+        axes_to_transpose(&mut point, 2);
+        assert_eq!(point, x);
+
+        // the distance may take all the bits of a usize
+        let bits = usize::BITS as usize / 2;
+        let mut x = [0; 2];
+        distance_to_transpose(usize::MAX, bits, &mut x);
+        assert_eq!(x, [usize::MAX >> bits; 2]);
+        let mut point = x;
+        transpose_to_axes(&mut point, bits);
+        axes_to_transpose(&mut point, bits);
+        assert_eq!(transpose_to_distance(&point, bits), usize::MAX);
+    }
+
+    #[test]
+    fn adjacency() {
+        for dims in 1..=4 {
+            let bits = 3;
+            let mut prev = convert_1d_to_nd(0, bits, dims);
+            for d in 1..(1 << (bits * dims)) {
+                let curr = convert_1d_to_nd(d, bits, dims);
+                let dist: usize = prev.iter().zip(&curr).map(|(&a, &b)| a.abs_diff(b)).sum();
+                assert_eq!(dist, 1);
+                prev = curr;
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Reordering of the arrays of the `ndarray` crate along the curve.
+//!
+//! The element `[row, column]` of a two-dimensional array is the cell (column, row), as in the
+//! row-major [traversals](crate::for_each_hilbert), and arrays of any shape are traversed along
+//! the pseudo-Hilbert path of [`pseudo`](crate::pseudo). Point arrays hold one point per row, with
+//! its x and y coordinates in the first two columns.
+
+use ::ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix1, Ix2};
+
+use crate::{hilbert_argsort, pseudo};
+
+/// Iterate over the indices and the elements of the array in curve order.
+pub fn hilbert_iter<'a, S, T: 'a>(
+    array: &'a ArrayBase<S, Ix2>,
+) -> impl Iterator<Item = ([usize; 2], &'a T)> + 'a
+where
+    S: Data<Elem = T>,
+{
+    let (rows, cols) = array.dim();
+    (0..rows * cols).map(move |d| {
+        let (x, y) = pseudo::convert_1d_to_2d(d, cols, rows);
+        ([y, x], &array[[y, x]])
+    })
+}
+
+/// Get the elements of the array in curve order.
+pub fn reorder<S, T>(array: &ArrayBase<S, Ix2>) -> Array1<T>
+where
+    S: Data<Elem = T>,
+    T: Clone,
+{
+    let mut values = alloc::vec::Vec::with_capacity(array.len());
+    let (rows, cols) = array.dim();
+    pseudo::for_each_point(cols, rows, |x, y| values.push(array[[y, x]].clone()));
+
+    Array1::from(values)
+}
+
+/// Rebuild an array of the given shape from its elements in the order of [`reorder`].
+///
+/// # Panics
+///
+/// Panics if the number of values is not `rows * cols`.
+pub fn restore<S, T>(values: &ArrayBase<S, Ix1>, rows: usize, cols: usize) -> Array2<T>
+where
+    S: Data<Elem = T>,
+    T: Clone,
+{
+    assert_eq!(
+        values.len(),
+        rows * cols,
+        "there must be one value per element of the array"
+    );
+    let mut cells = alloc::vec![0; rows * cols];
+    let mut d = 0;
+    pseudo::for_each_point(cols, rows, |x, y| {
+        cells[y * cols + x] = d;
+        d += 1;
+    });
+
+    Array2::from_shape_fn((rows, cols), |(y, x)| values[cells[y * cols + x]].clone())
+}
+
+/// Get the permutation ordering the points of an n by n grid along the curve: the point in the
+/// row `order[i]` is the `i`-th one in curve order.
+///
+/// # Panics
+///
+/// Panics if the array has fewer than 2 columns, `n` is not a power of 2, or any point is outside
+/// the grid.
+pub fn argsort_points<S>(points: &ArrayBase<S, Ix2>, n: usize) -> Array1<usize>
+where
+    S: Data<Elem = usize>,
+{
+    assert!(points.ncols() >= 2, "the points must have 2 coordinates");
+    let cells: alloc::vec::Vec<_> = points.rows().into_iter().map(|p| (p[0], p[1])).collect();
+
+    Array1::from(hilbert_argsort(&cells, n))
+}
+
+/// Get the points of an n by n grid sorted along the curve, as given by [`argsort_points`].
+///
+/// # Panics
+///
+/// Panics if the array has fewer than 2 columns, `n` is not a power of 2, or any point is outside
+/// the grid.
+pub fn sort_points<S>(points: &ArrayBase<S, Ix2>, n: usize) -> Array2<usize>
+where
+    S: Data<Elem = usize>,
+{
+    let order = argsort_points(points, n);
+    points.select(Axis(0), order.as_slice().expect("the order is contiguous"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::ndarray::{array, Array2};
+
+    use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+    #[test]
+    fn reordering() {
+        let n = 8;
+        let matrix = Array2::from_shape_fn((n, n), |(row, col)| row * n + col);
+        let values = reorder(&matrix);
+        for (d, &value) in values.iter().enumerate() {
+            let (x, y) = convert_1d_to_2d(d, n);
+            assert_eq!(value, y * n + x);
+        }
+        assert_eq!(restore(&values, n, n), matrix);
+
+        let view = matrix.slice(::ndarray::s![1..4, ..5]);
+        let values = reorder(&view);
+        assert_eq!(restore(&values, 3, 5), view);
+        let visited: Vec<_> = hilbert_iter(&view).map(|(_, &v)| v).collect();
+        assert_eq!(visited, values.to_vec());
+    }
+
+    #[test]
+    fn points() {
+        let points = array![[3, 0, 7], [0, 0, 8], [1, 1, 9], [0, 3, 10]];
+        assert_eq!(argsort_points(&points, 4), array![1, 2, 3, 0]);
+        let sorted = sort_points(&points, 4);
+        assert_eq!(sorted.column(2), array![8, 9, 10, 7]);
+        for (d, point) in sorted.rows().into_iter().enumerate().skip(1) {
+            let prev = sorted.row(d - 1);
+            assert!(
+                convert_2d_to_1d(prev[0], prev[1], 4) <= convert_2d_to_1d(point[0], point[1], 4)
+            );
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! The placement of a Hilbert curve within its grid and the coordinates used to describe it.
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+/// A corner of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Corner {
+    /// The corner at (0, 0).
+    #[default]
+    LowerLeft,
+    /// The corner at (n − 1, 0).
+    LowerRight,
+    /// The corner at (0, n − 1).
+    UpperLeft,
+    /// The corner at (n − 1, n − 1).
+    UpperRight,
+}
+
+/// A coordinate axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    #[default]
+    X,
+    Y,
+}
+
+/// The direction of a step between two adjacent cells, in the coordinates of the cells; with
+/// [`CoordinateSystem::YDown`], `Up` points down on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// Towards higher y coordinates.
+    Up,
+    /// Towards lower y coordinates.
+    Down,
+    /// Towards lower x coordinates.
+    Left,
+    /// Towards higher x coordinates.
+    Right,
+}
+
+impl Direction {
+    pub(crate) fn from_step(step: (isize, isize)) -> Self {
+        match step {
+            (0, 1) => Direction::Up,
+            (0, -1) => Direction::Down,
+            (-1, 0) => Direction::Left,
+            (1, 0) => Direction::Right,
+            _ => unreachable!("consecutive cells are adjacent"),
+        }
+    }
+
+    pub(crate) fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+}
+
+/// The convention for the coordinates of the cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoordinateSystem {
+    /// The origin is in the lower left corner and y grows upward.
+    #[default]
+    YUp,
+    /// The origin is in the upper left corner and y grows downward, as in images.
+    YDown,
+}
+
+impl CoordinateSystem {
+    // Convert between the y-up coordinates and this system; the conversion is its own inverse
+    pub(crate) fn convert(&self, x: usize, y: usize, n: usize) -> (usize, usize) {
+        match self {
+            CoordinateSystem::YUp => (x, y),
+            CoordinateSystem::YDown => (x, n - 1 - y),
+        }
+    }
+
+    pub(crate) fn convert_step(&self, (dx, dy): (isize, isize)) -> (isize, isize) {
+        match self {
+            CoordinateSystem::YUp => (dx, dy),
+            CoordinateSystem::YDown => (dx, -dy),
+        }
+    }
+}
+
+/// The orientation of a curve, given by the corner it starts in and the axis along which its end
+/// corner is adjacent to it, regardless of the [`CoordinateSystem`]; it defaults to the curve used
+/// by the conversion functions, which starts in the lower left corner and ends in the lower right
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Orientation {
+    pub start: Corner,
+    pub towards: Axis,
+}
+
+impl Orientation {
+    /// The 8 orientations, which are the images of the default curve by the symmetries of the
+    /// square, starting with the default one.
+    pub const ALL: [Orientation; 8] = [
+        Orientation::new(Corner::LowerLeft, Axis::X),
+        Orientation::new(Corner::LowerLeft, Axis::Y),
+        Orientation::new(Corner::LowerRight, Axis::X),
+        Orientation::new(Corner::LowerRight, Axis::Y),
+        Orientation::new(Corner::UpperLeft, Axis::X),
+        Orientation::new(Corner::UpperLeft, Axis::Y),
+        Orientation::new(Corner::UpperRight, Axis::X),
+        Orientation::new(Corner::UpperRight, Axis::Y),
+    ];
+
+    /// Create an orientation with the given starting corner and axis.
+    pub const fn new(start: Corner, towards: Axis) -> Self {
+        Orientation { start, towards }
+    }
+
+    // Map a cell of the default curve to this orientation
+    pub(crate) fn apply(&self, x: usize, y: usize, n: usize) -> (usize, usize) {
+        let (x, y) = self.transpose(x, y);
+        self.reflect(x, y, n)
+    }
+
+    // Map a cell of this orientation to the default curve
+    pub(crate) fn revert(&self, x: usize, y: usize, n: usize) -> (usize, usize) {
+        let (x, y) = self.reflect(x, y, n);
+        self.transpose(x, y)
+    }
+
+    // Map a step between two cells of the default curve to this orientation
+    pub(crate) fn apply_step(&self, (dx, dy): (isize, isize)) -> (isize, isize) {
+        let (dx, dy) = match self.towards {
+            Axis::X => (dx, dy),
+            Axis::Y => (dy, dx),
+        };
+        match self.start {
+            Corner::LowerLeft => (dx, dy),
+            Corner::LowerRight => (-dx, dy),
+            Corner::UpperLeft => (dx, -dy),
+            Corner::UpperRight => (-dx, -dy),
+        }
+    }
+
+    fn transpose(&self, x: usize, y: usize) -> (usize, usize) {
+        match self.towards {
+            Axis::X => (x, y),
+            Axis::Y => (y, x),
+        }
+    }
+
+    fn reflect(&self, x: usize, y: usize, n: usize) -> (usize, usize) {
+        match self.start {
+            Corner::LowerLeft => (x, y),
+            Corner::LowerRight => (n - 1 - x, y),
+            Corner::UpperLeft => (x, n - 1 - y),
+            Corner::UpperRight => (n - 1 - x, n - 1 - y),
+        }
+    }
+}
+
+/// Get the distance along the curve with the given orientation of the cell at distance `d` along
+/// the default curve, e.g. to translate distances from a library using another convention.
+pub fn transform(d: usize, orientation: Orientation, n: usize) -> usize {
+    let (x, y) = convert_1d_to_2d(d, n);
+    let (x, y) = orientation.revert(x, y, n);
+    convert_2d_to_1d(x, y, n)
+}
+
+/// Get the distances of the cell (x, y) along the curves with each of the orientations of
+/// [`Orientation::ALL`], in the same order; their minimum is a canonical distance that doesn't
+/// depend on the orientation of the curve.
+pub fn all_variants(x: usize, y: usize, n: usize) -> [usize; 8] {
+    assert!(x < n && y < n, "coordinates must be lower than n");
+    Orientation::ALL.map(|orientation| {
+        let (x, y) = orientation.revert(x, y, n);
+        convert_2d_to_1d(x, y, n)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HilbertCurve;
+
+    #[test]
+    fn symmetries() {
+        let n = 8;
+        for (i, &orientation) in Orientation::ALL.iter().enumerate() {
+            assert!(!Orientation::ALL[..i].contains(&orientation));
+            let curve = HilbertCurve::new(3).with_orientation(orientation);
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                assert_eq!(transform(d, orientation, n), curve.xy_to_d(x, y));
+                assert_eq!(all_variants(x, y, n)[i], curve.xy_to_d(x, y));
+            }
+        }
+        assert_eq!(Orientation::ALL[0], Orientation::default());
+        assert_eq!(all_variants(0, 0, n), [0, 0, 63, 21, 21, 63, 42, 42]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Hilbert packing of bounding boxes into the nodes of an R-tree.
+
+use alloc::vec::Vec;
+
+use crate::{
+    hilbert_argsort,
+    quantize::{quantize, Bounds, OutOfRange, Rounding},
+    HilbertCurve, HilbertIndexable,
+};
+
+/// Group bounding boxes `(min_x, min_y, max_x, max_y)` into the nodes of an R-tree with at most
+/// `node_capacity` children per node, ordering them by the Hilbert distances of their centers
+/// on the curve of the given order covering all of them.
+///
+/// The result lists the levels of the tree from the leaves up: every node of the first level
+/// holds the indices of its boxes and every node of a higher level the indices of its children in
+/// the level below; the last level consists of the root alone, unless there are no boxes.
+///
+/// # Panics
+///
+/// Panics if `node_capacity` is lower than 2, `order` is greater than
+/// [`HilbertCurve::MAX_ORDER`], or a box is not finite.
+pub fn pack_rtree(
+    boxes: &[(f64, f64, f64, f64)],
+    node_capacity: usize,
+    order: u32,
+) -> Vec<Vec<Vec<usize>>> {
+    assert!(
+        node_capacity >= 2,
+        "nodes must have a capacity of at least 2"
+    );
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+    assert!(
+        boxes
+            .iter()
+            .all(|&(x0, y0, x1, y1)| [x0, y0, x1, y1].iter().all(|c| c.is_finite())),
+        "the boxes must be finite"
+    );
+    if boxes.is_empty() {
+        return Vec::new();
+    }
+
+    let n = 1 << order;
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x0, y0, x1, y1) in boxes {
+        min_x = min_x.min(x0.min(x1));
+        min_y = min_y.min(y0.min(y1));
+        max_x = max_x.max(x0.max(x1));
+        max_y = max_y.max(y0.max(y1));
+    }
+    // a degenerate extent is widened, since all the centers fall in the same cell anyway
+    let bounds = Bounds::new(
+        min_x,
+        min_y,
+        if max_x > min_x { max_x } else { min_x + 1.0 },
+        if max_y > min_y { max_y } else { min_y + 1.0 },
+    );
+
+    let centers: Vec<_> = boxes
+        .iter()
+        .map(|&(x0, y0, x1, y1)| {
+            let (x, y) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+            quantize(x, y, &bounds, n, Rounding::Floor, OutOfRange::Clamp).unwrap_or((0, 0))
+        })
+        .collect();
+
+    levels(&hilbert_argsort(&centers, n), node_capacity)
+}
+
+/// Group items into the nodes of an R-tree with at most `node_capacity` children per node,
+/// ordering them by the Hilbert distances of their cells in an n by n grid; the levels of the
+/// tree are listed as by [`pack_rtree`].
+///
+/// # Panics
+///
+/// Panics if `node_capacity` is lower than 2, `n` is not a power of 2, or any item is outside the
+/// grid.
+pub fn pack_rtree_items<T: HilbertIndexable>(
+    items: &[T],
+    node_capacity: usize,
+    n: usize,
+) -> Vec<Vec<Vec<usize>>> {
+    assert!(
+        node_capacity >= 2,
+        "nodes must have a capacity of at least 2"
+    );
+    if items.is_empty() {
+        return Vec::new();
+    }
+
+    levels(&hilbert_argsort(items, n), node_capacity)
+}
+
+// Build the levels of the tree over the leaves in curve order
+fn levels(sorted: &[usize], node_capacity: usize) -> Vec<Vec<Vec<usize>>> {
+    // the nodes of every level are in curve order, so consecutive ones are grouped together
+    let mut levels = alloc::vec![group(sorted, node_capacity)];
+    while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+        let indices: Vec<usize> = (0..level.len()).collect();
+        levels.push(group(&indices, node_capacity));
+    }
+
+    levels
+}
+
+fn group(indices: &[usize], node_capacity: usize) -> Vec<Vec<usize>> {
+    indices
+        .chunks(node_capacity)
+        .map(|chunk| chunk.to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing() {
+        let boxes: Vec<_> = (0..100)
+            .map(|i| {
+                let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+                (x, y, x + 0.5, y + 0.5)
+            })
+            .collect();
+        let levels = pack_rtree(&boxes, 4, 8);
+        let sizes: Vec<_> = levels.iter().map(|level| level.len()).collect();
+        assert_eq!(sizes, [25, 7, 2, 1]);
+
+        let mut leaves: Vec<_> = levels[0].iter().flatten().copied().collect();
+        // the boxes of every leaf are within a small region
+        for leaf in &levels[0] {
+            let xs = leaf.iter().map(|&i| boxes[i].0 as i32);
+            let ys = leaf.iter().map(|&i| boxes[i].1 as i32);
+            assert!(xs.clone().max().unwrap() - xs.min().unwrap() <= 3);
+            assert!(ys.clone().max().unwrap() - ys.min().unwrap() <= 3);
+        }
+        leaves.sort_unstable();
+        assert_eq!(leaves, (0..100).collect::<Vec<_>>());
+        for pair in levels.windows(2) {
+            let children: Vec<_> = pair[1].iter().flatten().copied().collect();
+            assert_eq!(children, (0..pair[0].len()).collect::<Vec<_>>());
+        }
+
+        assert!(pack_rtree(&[], 4, 8).is_empty());
+        assert_eq!(pack_rtree(&[(1.0, 1.0, 1.0, 1.0)], 4, 8), [[[0]]]);
+    }
+
+    #[test]
+    fn packing_items() {
+        let points: Vec<_> = (0..64).map(|i| (i % 8, i / 8)).collect();
+        let levels = pack_rtree_items(&points, 4, 8);
+        let sizes: Vec<_> = levels.iter().map(|level| level.len()).collect();
+        assert_eq!(sizes, [16, 4, 1]);
+        // every leaf holds a 2 by 2 block of points
+        for leaf in &levels[0] {
+            let (x, y) = points[leaf[0]];
+            assert!(leaf
+                .iter()
+                .all(|&i| points[i].0 / 2 == x / 2 && points[i].1 / 2 == y / 2));
+        }
+        assert!(pack_rtree_items::<(usize, usize)>(&[], 4, 8).is_empty());
+    }
+}
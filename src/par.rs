@@ -0,0 +1,58 @@
+//! Parallel conversions and iteration, available with the `rayon` feature.
+
+use rayon::prelude::*;
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d, HilbertCurve};
+
+// The number of consecutive cells handled by a single parallel task
+const CHUNK_LEN: usize = 4096;
+
+/// Convert a slice of one-dimensional distances to their (x, y) coordinates in parallel.
+pub fn par_convert_1d_to_2d_batch(ds: &[usize], n: usize) -> Vec<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    ds.par_iter().map(|&d| convert_1d_to_2d(d, n)).collect()
+}
+
+/// Convert a slice of (x, y) coordinates to their one-dimensional distances in parallel.
+pub fn par_convert_2d_to_1d_batch(points: &[(usize, usize)], n: usize) -> Vec<usize> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    points
+        .par_iter()
+        .map(|&(x, y)| convert_2d_to_1d(x, y, n))
+        .collect()
+}
+
+impl HilbertCurve {
+    /// Iterate over all the cells of the grid in parallel, yielding `(d, x, y)`; the items are
+    /// produced in curve order if the iterator is collected into an ordered container.
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = (usize, usize, usize)> {
+        let curve = *self;
+        let chunks = curve.len().div_ceil(CHUNK_LEN);
+
+        (0..chunks).into_par_iter().flat_map_iter(move |i| {
+            let start = i * CHUNK_LEN;
+            curve.range(start..curve.len().min(start + CHUNK_LEN))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d_batch, convert_2d_to_1d_batch};
+
+    #[test]
+    fn matches_sequential() {
+        let curve = HilbertCurve::new(7);
+        let par: Vec<_> = curve.par_iter().collect();
+        assert!(par.into_iter().eq(curve.iter()));
+
+        let ds: Vec<usize> = (0..curve.len()).rev().collect();
+        let points = par_convert_1d_to_2d_batch(&ds, curve.side());
+        assert_eq!(points, convert_1d_to_2d_batch(&ds, curve.side()));
+        assert_eq!(
+            par_convert_2d_to_1d_batch(&points, curve.side()),
+            convert_2d_to_1d_batch(&points, curve.side())
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Splitting of the curve into contiguous parts.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{
+    hilbert_argsort, segment_bounding_box, shard::boundary, HilbertCurve, HilbertIndexable,
+};
+
+/// A contiguous segment of the curve.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Partition {
+    /// The distances of the cells in the partition.
+    pub range: Range<usize>,
+    /// The smallest rectangle containing the cells, as inclusive (x0, y0, x1, y1) bounds.
+    pub bounds: (usize, usize, usize, usize),
+}
+
+impl Partition {
+    fn new(range: Range<usize>, n: usize) -> Self {
+        let bounds = segment_bounding_box(range.clone(), n).expect("partitions are not empty");
+        Partition { range, bounds }
+    }
+
+    /// The number of cells in the partition.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+}
+
+/// Split the curve into `k` contiguous partitions whose numbers of cells differ by at most one.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or `k` is not within [1, n^2].
+pub fn partition(k: usize, n: usize) -> Vec<Partition> {
+    let curve = HilbertCurve::try_from_side(n).expect("n must be a valid side");
+    let len = curve.len() as u128;
+    assert!(k > 0 && k as u128 <= len, "k must be within [1, n^2]");
+
+    (0..k)
+        .map(|i| Partition::new(boundary(i, k, len)..boundary(i + 1, k, len), n))
+        .collect()
+}
+
+/// Split the items into `k` groups whose sizes differ by at most one, each group holding the
+/// indices of items consecutive in curve order; the groups are in curve order as well.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2, `k` is 0, or any item is outside the grid.
+pub fn partition_items<T: HilbertIndexable>(items: &[T], k: usize, n: usize) -> Vec<Vec<usize>> {
+    assert!(k > 0, "k must be positive");
+    let sorted = hilbert_argsort(items, n);
+    let len = sorted.len() as u128;
+
+    (0..k)
+        .map(|i| sorted[boundary(i, k, len)..boundary(i + 1, k, len)].to_vec())
+        .collect()
+}
+
+/// Split the curve into `p` contiguous partitions of approximately equal total weight, given the
+/// non-negative weights of the cells in row-major order (the cell (x, y) having the weight
+/// `weights[y * n + x]`); every partition contains at least one cell.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`], there are not n^2 weights, any weight
+/// is negative or not finite, or `p` is not within [1, n^2].
+pub fn partition_weighted(weights: &[f64], p: usize, n: usize) -> Vec<Partition> {
+    let curve = HilbertCurve::try_from_side(n).expect("n must be a valid side");
+    let len = curve.len();
+    assert_eq!(weights.len(), len, "there must be one weight per cell");
+    assert!(
+        weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+        "the weights must be finite and non-negative"
+    );
+    assert!(p > 0 && p <= len, "p must be within [1, n^2]");
+
+    // the total weight of the cells before every distance
+    let mut prefix = Vec::with_capacity(len + 1);
+    prefix.push(0.0);
+    for (_, x, y) in curve.iter() {
+        prefix.push(prefix[prefix.len() - 1] + weights[y * n + x]);
+    }
+    let total = prefix[len];
+
+    let mut partitions = Vec::with_capacity(p);
+    let mut start = 0;
+    for i in 1..=p {
+        let end = if i == p {
+            len
+        } else {
+            // the first boundary reaching the target weight, leaving a cell for every remaining
+            // partition
+            let target = total * i as f64 / p as f64;
+            let end = prefix.partition_point(|&w| w < target);
+            end.clamp(start + 1, len - (p - i))
+        };
+        partitions.push(Partition::new(start..end, n));
+        start = end;
+    }
+
+    partitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn items() {
+        let n = 8;
+        let points: Vec<_> = (0..10).map(|d| convert_1d_to_2d(63 - 7 * d, n)).collect();
+        let groups = partition_items(&points, 3, n);
+        assert_eq!(groups, [vec![9, 8, 7], vec![6, 5, 4], vec![3, 2, 1, 0]]);
+        assert_eq!(partition_items(&points[..1], 2, n), [vec![], vec![0]]);
+    }
+
+    #[test]
+    fn even_partitions() {
+        let n = 16;
+        for &k in &[1, 3, 7, 64, 256] {
+            let partitions = partition(k, n);
+            assert_eq!(partitions.len(), k);
+            assert_eq!(partitions[0].range.start, 0);
+            assert_eq!(partitions[k - 1].range.end, n * n);
+            for pair in partitions.windows(2) {
+                assert_eq!(pair[0].range.end, pair[1].range.start);
+                assert!(pair[0].len().abs_diff(pair[1].len()) <= 1);
+            }
+            for part in &partitions {
+                let (x0, y0, x1, y1) = part.bounds;
+                for d in part.range.clone() {
+                    let (x, y) = convert_1d_to_2d(d, n);
+                    assert!((x0..=x1).contains(&x) && (y0..=y1).contains(&y));
+                }
+            }
+        }
+        assert_eq!(partition(4, n)[1].bounds, (0, 8, 7, 15));
+
+        let curve = HilbertCurve::new(HilbertCurve::MAX_ORDER);
+        let halves = partition(2, curve.side());
+        assert_eq!(halves[1].range, curve.len() / 2..curve.len());
+        // the curve of an odd order is transposed
+        assert_eq!(
+            halves[1].bounds,
+            (curve.side() / 2, 0, curve.side() - 1, curve.side() - 1)
+        );
+    }
+
+    #[test]
+    fn weighted_partitions() {
+        let n = 16;
+        let uniform = vec![1.0; n * n];
+        assert_eq!(partition_weighted(&uniform, 4, n), partition(4, n));
+
+        // all the weight in the right half of the grid
+        let weights: Vec<f64> = (0..n * n)
+            .map(|i| if i % n >= n / 2 { 1.0 } else { 0.0 })
+            .collect();
+        let partitions = partition_weighted(&weights, 8, n);
+        assert_eq!(partitions.len(), 8);
+        for part in &partitions {
+            let weight: f64 = part
+                .range
+                .clone()
+                .map(|d| convert_1d_to_2d(d, n))
+                .map(|(x, y)| weights[y * n + x])
+                .sum();
+            assert!((weight - 16.0).abs() <= 1.0);
+        }
+
+        let zero = vec![0.0; n * n];
+        let partitions = partition_weighted(&zero, n * n, n);
+        assert!(partitions.iter().all(|part| part.len() == 1));
+    }
+}
@@ -0,0 +1,84 @@
+//! Paths through the centers of the cells, for plotting.
+
+use alloc::vec::Vec;
+
+use crate::HilbertCurve;
+
+/// Get the centers of the cells of the curve of the given order in curve order, with cells of
+/// unit size and the lower left corner of the grid at the origin.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn path_points(order: u32) -> Vec<(f64, f64)> {
+    path_points_scaled(order, 1.0, (0.0, 0.0))
+}
+
+/// Get the centers of the cells of the curve of the given order in curve order, with cells of
+/// `scale` by `scale` units and the lower left corner of the grid at `offset`.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn path_points_scaled(order: u32, scale: f64, offset: (f64, f64)) -> Vec<(f64, f64)> {
+    HilbertCurve::new(order)
+        .iter()
+        .map(|(_, x, y)| {
+            (
+                offset.0 + (x as f64 + 0.5) * scale,
+                offset.1 + (y as f64 + 0.5) * scale,
+            )
+        })
+        .collect()
+}
+
+/// Get the paths of the curves of orders 1 to `max_order`, as given by [`path_points_scaled`], all
+/// filling the same square of `size` by `size` units with its lower left corner at the origin.
+///
+/// The curves of consecutive orders are nested, so every path refines the previous one: each of
+/// its points is replaced by 4 points around it, which suits animations of the construction.
+///
+/// # Panics
+///
+/// Panics if `max_order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn refinement_frames(max_order: u32, size: f64) -> Vec<Vec<(f64, f64)>> {
+    (1..=max_order)
+        .map(|order| path_points_scaled(order, size / (1u64 << order) as f64, (0.0, 0.0)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points() {
+        assert_eq!(
+            path_points(1),
+            [(0.5, 0.5), (0.5, 1.5), (1.5, 1.5), (1.5, 0.5)]
+        );
+        assert_eq!(path_points(0), [(0.5, 0.5)]);
+        assert_eq!(
+            path_points_scaled(1, 2.0, (10.0, -4.0)),
+            [(11.0, -3.0), (11.0, -1.0), (13.0, -1.0), (13.0, -3.0)]
+        );
+        assert_eq!(path_points(5).len(), 1024);
+    }
+
+    #[test]
+    fn frames() {
+        let frames = refinement_frames(4, 16.0);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[3], path_points(4));
+        for pair in frames.windows(2) {
+            // the centroid of every 4 consecutive points is the point they refine
+            for (i, &(x, y)) in pair[0].iter().enumerate() {
+                let children = &pair[1][4 * i..4 * i + 4];
+                let cx = children.iter().map(|p| p.0).sum::<f64>() / 4.0;
+                let cy = children.iter().map(|p| p.1).sum::<f64>() / 4.0;
+                assert_eq!((cx, cy), (x, y));
+            }
+        }
+        assert!(refinement_frames(0, 1.0).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! The Peano curve, which covers grids of n by n cells with n being a power of 3.
+//!
+//! Every level divides a cell into 3 by 3 sub-cells traversed in columns of alternating direction,
+//! so the curve starts at (0, 0) and ends at (n − 1, n − 1).
+
+use crate::{GridPoint, SpaceFillingCurve};
+
+/// The highest supported order; the length of the curve must fit in a `usize`.
+pub const MAX_ORDER: u32 = if usize::BITS >= 64 { 20 } else { 10 };
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates.
+pub const fn convert_1d_to_2d(d: usize, n: usize) -> (usize, usize) {
+    assert!(is_power_of_three(n), "n must be a power of 3");
+    let (mut x, mut y) = (0, 0);
+    // the parities of the sums of the x and y digits of `d` seen so far
+    let (mut flip_x, mut flip_y) = (false, false);
+    let mut s = n / 3;
+
+    while s > 0 {
+        let digits = (d / (s * s)) % 9;
+        let (a, b) = (digits / 3, digits % 3);
+        x = 3 * x + if flip_y { 2 - a } else { a };
+        flip_x ^= a % 2 == 1;
+        y = 3 * y + if flip_x { 2 - b } else { b };
+        flip_y ^= b % 2 == 1;
+        s /= 3;
+    }
+
+    (x, y)
+}
+
+/// Convert a pair of (x, y) coordinates to a one-dimensional distance.
+pub const fn convert_2d_to_1d(x: usize, y: usize, n: usize) -> usize {
+    assert!(is_power_of_three(n), "n must be a power of 3");
+    let mut d = 0;
+    let (mut flip_x, mut flip_y) = (false, false);
+    let mut s = n / 3;
+
+    while s > 0 {
+        let (dx, dy) = ((x / s) % 3, (y / s) % 3);
+        let a = if flip_y { 2 - dx } else { dx };
+        flip_x ^= a % 2 == 1;
+        let b = if flip_x { 2 - dy } else { dy };
+        flip_y ^= b % 2 == 1;
+        d = 9 * d + 3 * a + b;
+        s /= 3;
+    }
+
+    d
+}
+
+/// A Peano curve filling a square grid of `3^order` by `3^order` cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeanoCurve {
+    order: u32,
+}
+
+impl PeanoCurve {
+    /// Create a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`MAX_ORDER`].
+    pub fn new(order: u32) -> Self {
+        assert!(order <= MAX_ORDER, "the order is too large");
+        PeanoCurve { order }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of cells along each side of the grid.
+    pub fn side(&self) -> usize {
+        3usize.pow(self.order)
+    }
+}
+
+impl SpaceFillingCurve for PeanoCurve {
+    fn len(&self) -> usize {
+        self.side() * self.side()
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        assert!(
+            index < self.len(),
+            "index must be lower than the length of the curve"
+        );
+        convert_1d_to_2d(index, self.side()).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        assert!(
+            point.x < self.side() && point.y < self.side(),
+            "coordinates must be lower than the side of the grid"
+        );
+        convert_2d_to_1d(point.x, point.y, self.side())
+    }
+}
+
+const fn is_power_of_three(n: usize) -> bool {
+    let mut n = n;
+    while n > 1 && n % 3 == 0 {
+        n /= 3;
+    }
+    n == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversibility() {
+        for &n in &[1, 3, 9, 27, 81] {
+            for d in 0..(n * n) {
+                let (x, y) = convert_1d_to_2d(d, n);
+                assert!(x < n && y < n);
+                assert_eq!(convert_2d_to_1d(x, y, n), d);
+            }
+        }
+    }
+
+    #[test]
+    fn adjacency() {
+        for &n in &[3, 9, 27, 81] {
+            let mut prev = convert_1d_to_2d(0, n);
+            assert_eq!(prev, (0, 0));
+            for d in 1..(n * n) {
+                let curr = convert_1d_to_2d(d, n);
+                assert_eq!(prev.0.abs_diff(curr.0) + prev.1.abs_diff(curr.1), 1);
+                prev = curr;
+            }
+            assert_eq!(prev, (n - 1, n - 1));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! A pseudo-Hilbert curve covering rectangles of arbitrary size, based on the generalized Hilbert
+//! curve by Jakub Červený.
+//!
+//! The rectangle is split recursively into two or three sub-rectangles traversed like the
+//! quadrants of a Hilbert curve, choosing the splits so that consecutive cells stay adjacent; if
+//! the longer side is odd and the shorter one is even, a single diagonal step cannot be avoided.
+
+// A sub-rectangle starting in the corner (x, y), with a major axis `a` along which the curve
+// advances and a minor axis `b`
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: isize,
+    y: isize,
+    ax: isize,
+    ay: isize,
+    bx: isize,
+    by: isize,
+}
+
+// The parts of a rectangle in curve order
+enum Split {
+    // a single row of cells, from the corner along the given unit step
+    Line(isize, isize, usize),
+    Two([Rect; 2]),
+    Three([Rect; 3]),
+}
+
+impl Rect {
+    fn new(w: usize, h: usize) -> Self {
+        let (w, h) = (w as isize, h as isize);
+        if w >= h {
+            Rect {
+                x: 0,
+                y: 0,
+                ax: w,
+                ay: 0,
+                bx: 0,
+                by: h,
+            }
+        } else {
+            Rect {
+                x: 0,
+                y: 0,
+                ax: 0,
+                ay: h,
+                bx: w,
+                by: 0,
+            }
+        }
+    }
+
+    fn width(&self) -> usize {
+        (self.ax + self.ay).unsigned_abs()
+    }
+
+    fn height(&self) -> usize {
+        (self.bx + self.by).unsigned_abs()
+    }
+
+    fn len(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    fn contains(&self, x: isize, y: isize) -> bool {
+        let (dax, day) = (self.ax.signum(), self.ay.signum());
+        let (dbx, dby) = (self.bx.signum(), self.by.signum());
+        let i = (x - self.x) * dax + (y - self.y) * day;
+        let j = (x - self.x) * dbx + (y - self.y) * dby;
+
+        (0..self.width() as isize).contains(&i) && (0..self.height() as isize).contains(&j)
+    }
+
+    fn split(&self) -> Split {
+        let Rect {
+            x,
+            y,
+            ax,
+            ay,
+            bx,
+            by,
+        } = *self;
+        let (w, h) = (self.width(), self.height());
+        let (dax, day) = (ax.signum(), ay.signum());
+        let (dbx, dby) = (bx.signum(), by.signum());
+
+        if h == 1 {
+            return Split::Line(dax, day, w);
+        }
+        if w == 1 {
+            return Split::Line(dbx, dby, h);
+        }
+
+        let (mut ax2, mut ay2) = (ax.div_euclid(2), ay.div_euclid(2));
+        let (mut bx2, mut by2) = (bx.div_euclid(2), by.div_euclid(2));
+        let w2 = (ax2 + ay2).unsigned_abs();
+        let h2 = (bx2 + by2).unsigned_abs();
+
+        if 2 * w > 3 * h {
+            // a long rectangle is split in two along its major axis
+            if w2 % 2 == 1 && w > 2 {
+                ax2 += dax;
+                ay2 += day;
+            }
+            Split::Two([
+                Rect {
+                    x,
+                    y,
+                    ax: ax2,
+                    ay: ay2,
+                    bx,
+                    by,
+                },
+                Rect {
+                    x: x + ax2,
+                    y: y + ay2,
+                    ax: ax - ax2,
+                    ay: ay - ay2,
+                    bx,
+                    by,
+                },
+            ])
+        } else {
+            if h2 % 2 == 1 && h > 2 {
+                bx2 += dbx;
+                by2 += dby;
+            }
+            Split::Three([
+                Rect {
+                    x,
+                    y,
+                    ax: bx2,
+                    ay: by2,
+                    bx: ax2,
+                    by: ay2,
+                },
+                Rect {
+                    x: x + bx2,
+                    y: y + by2,
+                    ax,
+                    ay,
+                    bx: bx - bx2,
+                    by: by - by2,
+                },
+                Rect {
+                    x: x + (ax - dax) + (bx2 - dbx),
+                    y: y + (ay - day) + (by2 - dby),
+                    ax: -bx2,
+                    ay: -by2,
+                    bx: -(ax - ax2),
+                    by: -(ay - ay2),
+                },
+            ])
+        }
+    }
+}
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates in a grid `w` cells
+/// wide and `h` cells high.
+pub fn convert_1d_to_2d(d: usize, w: usize, h: usize) -> (usize, usize) {
+    assert!(d < w * h, "d must be lower than w * h");
+    let mut rect = Rect::new(w, h);
+    let mut d = d;
+
+    loop {
+        match rect.split() {
+            Split::Line(dx, dy, len) => {
+                debug_assert!(d < len);
+                let d = d as isize;
+                return ((rect.x + dx * d) as usize, (rect.y + dy * d) as usize);
+            }
+            Split::Two(parts) => rect = descend_by_distance(&parts, &mut d),
+            Split::Three(parts) => rect = descend_by_distance(&parts, &mut d),
+        }
+    }
+}
+
+/// Convert a pair of (x, y) coordinates in a grid `w` cells wide and `h` cells high to a
+/// one-dimensional distance.
+pub fn convert_2d_to_1d(x: usize, y: usize, w: usize, h: usize) -> usize {
+    assert!(x < w && y < h, "coordinates must be within the grid");
+    let (x, y) = (x as isize, y as isize);
+    let mut rect = Rect::new(w, h);
+    let mut d = 0;
+
+    loop {
+        match rect.split() {
+            Split::Line(dx, dy, _) => {
+                return d + ((x - rect.x) * dx + (y - rect.y) * dy) as usize;
+            }
+            Split::Two(parts) => rect = descend_by_point(&parts, x, y, &mut d),
+            Split::Three(parts) => rect = descend_by_point(&parts, x, y, &mut d),
+        }
+    }
+}
+
+/// All the cells of a grid `w` cells wide and `h` cells high, in curve order.
+#[cfg(feature = "alloc")]
+pub fn points(w: usize, h: usize) -> alloc::vec::Vec<(usize, usize)> {
+    let mut points = alloc::vec::Vec::with_capacity(w * h);
+    for_each_point(w, h, |x, y| points.push((x, y)));
+    points
+}
+
+/// Call `f` with the coordinates of all the cells of a grid `w` cells wide and `h` cells high, in
+/// curve order.
+pub fn for_each_point(w: usize, h: usize, mut f: impl FnMut(usize, usize)) {
+    if w * h > 0 {
+        visit(Rect::new(w, h), &mut f);
+    }
+}
+
+fn visit(rect: Rect, f: &mut impl FnMut(usize, usize)) {
+    match rect.split() {
+        Split::Line(dx, dy, len) => {
+            for i in 0..len as isize {
+                f((rect.x + dx * i) as usize, (rect.y + dy * i) as usize);
+            }
+        }
+        Split::Two(parts) => parts.iter().for_each(|&part| visit(part, f)),
+        Split::Three(parts) => parts.iter().for_each(|&part| visit(part, f)),
+    }
+}
+
+// Pick the part containing the distance `d`, making `d` relative to it
+fn descend_by_distance(parts: &[Rect], d: &mut usize) -> Rect {
+    for part in parts {
+        if *d < part.len() {
+            return *part;
+        }
+        *d -= part.len();
+    }
+    unreachable!("the parts cover the whole rectangle")
+}
+
+// Pick the part containing the point, adding the lengths of the skipped parts to `d`
+fn descend_by_point(parts: &[Rect], x: isize, y: isize, d: &mut usize) -> Rect {
+    for part in parts {
+        if part.contains(x, y) {
+            return *part;
+        }
+        *d += part.len();
+    }
+    unreachable!("the parts cover the whole rectangle")
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage() {
+        for w in 1..=20 {
+            for h in 1..=20 {
+                let points = points(w, h);
+                assert_eq!(points.len(), w * h);
+                let mut diagonal = 0;
+                for (d, &(x, y)) in points.iter().enumerate() {
+                    assert_eq!(convert_1d_to_2d(d, w, h), (x, y));
+                    assert_eq!(convert_2d_to_1d(x, y, w, h), d);
+                    if d > 0 {
+                        let (px, py) = points[d - 1];
+                        match (px.abs_diff(x), py.abs_diff(y)) {
+                            (0, 1) | (1, 0) => {}
+                            (1, 1) => diagonal += 1,
+                            step => panic!("invalid step {:?} in {}x{}", step, w, h),
+                        }
+                    }
+                }
+                let (long, short) = (w.max(h), w.min(h));
+                let allowed = if long % 2 == 1 && short % 2 == 0 {
+                    1
+                } else {
+                    0
+                };
+                assert!(diagonal <= allowed, "{}x{}", w, h);
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! A PyO3 module exposing the conversions and range decompositions to Python.
+//!
+//! The batch functions take any C-contiguous buffer of unsigned 64-bit integers, such as a numpy
+//! `uint64` array, and return `bytes` of native-endian unsigned 64-bit integers, with the
+//! coordinates of the cells interleaved as `[x0, y0, x1, y1, ...]`; they can be turned back into
+//! an array with `numpy.frombuffer(result, dtype=numpy.uint64)`. The module can be built as a
+//! Python extension named `hilbert_curve` with `maturin build --features python`.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::convert::TryFrom;
+
+use pyo3::{buffer::PyBuffer, exceptions::PyValueError, prelude::*, types::PyBytes};
+
+use crate::{try_convert_1d_to_2d, try_convert_2d_to_1d, try_ranges_for_rect};
+
+/// Convert a distance `d` to the coordinates of its cell.
+#[pyfunction]
+fn d2xy(d: u64, n: u64) -> PyResult<(u64, u64)> {
+    let xy = to_points(&[d], n).map_err(PyValueError::new_err)?;
+    Ok((xy[0], xy[1]))
+}
+
+/// Convert the coordinates of a cell to its distance.
+#[pyfunction]
+fn xy2d(x: u64, y: u64, n: u64) -> PyResult<u64> {
+    Ok(to_distances(&[x, y], n).map_err(PyValueError::new_err)?[0])
+}
+
+/// Convert a buffer of distances to the interleaved coordinates of their cells.
+#[pyfunction]
+fn d2xy_batch<'py>(py: Python<'py>, ds: PyBuffer<u64>, n: u64) -> PyResult<Bound<'py, PyBytes>> {
+    let xy = to_points(&read(py, &ds)?, n).map_err(PyValueError::new_err)?;
+    Ok(to_bytes(py, &xy))
+}
+
+/// Convert a buffer of interleaved coordinates to the distances of their cells.
+#[pyfunction]
+fn xy2d_batch<'py>(py: Python<'py>, xy: PyBuffer<u64>, n: u64) -> PyResult<Bound<'py, PyBytes>> {
+    let ds = to_distances(&read(py, &xy)?, n).map_err(PyValueError::new_err)?;
+    Ok(to_bytes(py, &ds))
+}
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into at most `max_ranges` ranges of
+/// distances, returned as a list of `(start, end)` tuples of half-open bounds.
+#[pyfunction]
+fn ranges_for_rect(
+    x0: u64,
+    y0: u64,
+    x1: u64,
+    y1: u64,
+    n: u64,
+    max_ranges: usize,
+) -> PyResult<Vec<(u64, u64)>> {
+    ranges(x0, y0, x1, y1, n, max_ranges).map_err(PyValueError::new_err)
+}
+
+/// The `hilbert_curve` Python module.
+#[pymodule]
+fn hilbert_curve(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(d2xy, m)?)?;
+    m.add_function(wrap_pyfunction!(xy2d, m)?)?;
+    m.add_function(wrap_pyfunction!(d2xy_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(xy2d_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(ranges_for_rect, m)?)?;
+    Ok(())
+}
+
+fn read(py: Python<'_>, buffer: &PyBuffer<u64>) -> PyResult<Vec<u64>> {
+    let cells = buffer
+        .as_slice(py)
+        .ok_or_else(|| PyValueError::new_err("the buffer must be C-contiguous"))?;
+    Ok(cells.iter().map(|cell| cell.get()).collect())
+}
+
+fn to_bytes<'py>(py: Python<'py>, values: &[u64]) -> Bound<'py, PyBytes> {
+    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+    PyBytes::new(py, &bytes)
+}
+
+// numpy buffers hold 64-bit integers, which may not fit in the usize of the target
+fn to_usize(value: u64) -> Result<usize, String> {
+    usize::try_from(value).map_err(|_| format!("{} is too large", value))
+}
+
+fn to_points(ds: &[u64], n: u64) -> Result<Vec<u64>, String> {
+    let n = to_usize(n)?;
+    let mut xy = Vec::with_capacity(2 * ds.len());
+    for &d in ds {
+        let (x, y) = try_convert_1d_to_2d(to_usize(d)?, n).map_err(|e| e.to_string())?;
+        xy.extend_from_slice(&[x as u64, y as u64]);
+    }
+    Ok(xy)
+}
+
+fn to_distances(xy: &[u64], n: u64) -> Result<Vec<u64>, String> {
+    let n = to_usize(n)?;
+    if xy.len() % 2 != 0 {
+        return Err("the coordinates must come in pairs".into());
+    }
+    xy.chunks_exact(2)
+        .map(|pair| {
+            let (x, y) = (to_usize(pair[0])?, to_usize(pair[1])?);
+            let d = try_convert_2d_to_1d(x, y, n).map_err(|e| e.to_string())?;
+            Ok(d as u64)
+        })
+        .collect()
+}
+
+fn ranges(
+    x0: u64,
+    y0: u64,
+    x1: u64,
+    y1: u64,
+    n: u64,
+    max_ranges: usize,
+) -> Result<Vec<(u64, u64)>, String> {
+    let [x0, y0, x1, y1, n] = [
+        to_usize(x0)?,
+        to_usize(y0)?,
+        to_usize(x1)?,
+        to_usize(y1)?,
+        to_usize(n)?,
+    ];
+    let ranges = try_ranges_for_rect(x0, y0, x1, y1, n, max_ranges).map_err(|e| e.to_string())?;
+    Ok(ranges
+        .into_iter()
+        .map(|range| (range.start as u64, range.end as u64))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // calling the functions requires an interpreter, so the tests cover the functions they wrap
+    #[test]
+    fn conversions() {
+        assert_eq!(to_points(&[0, 5], 4), Ok(vec![0, 0, 0, 3]));
+        assert_eq!(to_distances(&[0, 0, 0, 3], 4), Ok(vec![0, 5]));
+        assert_eq!(
+            to_points(&[16], 4),
+            Err("distance 16 is out of range for a 4x4 grid".into())
+        );
+        assert!(to_points(&[1], 3).is_err());
+        assert!(to_distances(&[0, 0, 1], 4).is_err());
+        assert!(to_distances(&[4, 0], 4).is_err());
+
+        assert_eq!(ranges(0, 0, 1, 1, 4, 8), Ok(vec![(0, 4)]));
+        assert!(ranges(0, 0, 4, 1, 4, 8).is_err());
+        assert!(ranges(0, 0, 1, 1, 4, 0).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Interoperability with Bing-style quadkeys, Z-order tile ids and slippy-map tiles.
+//!
+//! A tile (x, y) at zoom level z, with y growing downward as in web maps, is treated as the cell
+//! (x, y) of the Hilbert curve of order z. The digits of its quadkey, from the coarsest level, are
+//! `x_bit + 2 * y_bit`, which makes its Z-order tile id the [Morton code](crate::morton) of the
+//! cell.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use core::ops::Range;
+
+use crate::{
+    demote,
+    morton::{hilbert_to_morton, morton_to_hilbert},
+    HilbertCurve, HilbertKey,
+};
+
+/// Convert a distance `d` along the Hilbert curve of order `zoom` to the Z-order id of the same
+/// tile.
+///
+/// # Panics
+///
+/// Panics if `zoom` is greater than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than `4^zoom`.
+pub fn hilbert_to_tile_id(d: usize, zoom: u32) -> usize {
+    hilbert_to_morton(d, side(d, zoom))
+}
+
+/// Convert the Z-order id of a tile at the given zoom level to its distance along the Hilbert
+/// curve of order `zoom`.
+///
+/// # Panics
+///
+/// Panics if `zoom` is greater than [`HilbertCurve::MAX_ORDER`] or `id` is not lower than
+/// `4^zoom`.
+pub fn tile_id_to_hilbert(id: usize, zoom: u32) -> usize {
+    morton_to_hilbert(id, side(id, zoom))
+}
+
+/// Convert a distance `d` along the Hilbert curve of order `zoom` to the quadkey of the same tile,
+/// which has `zoom` digits.
+///
+/// # Panics
+///
+/// Panics if `zoom` is greater than [`HilbertCurve::MAX_ORDER`] or `d` is not lower than `4^zoom`.
+#[cfg(feature = "alloc")]
+pub fn hilbert_to_quadkey(d: usize, zoom: u32) -> String {
+    let id = hilbert_to_tile_id(d, zoom);
+    (0..zoom)
+        .rev()
+        .map(|level| char::from(b'0' + ((id >> (2 * level)) & 3) as u8))
+        .collect()
+}
+
+/// Convert a quadkey to the distance of its tile along the Hilbert curve whose order is the zoom
+/// level of the quadkey, returning `(d, zoom)`; returns `None` if the quadkey contains a character
+/// other than the digits 0 to 3 or is longer than [`HilbertCurve::MAX_ORDER`].
+pub fn quadkey_to_hilbert(quadkey: &str) -> Option<(usize, u32)> {
+    if quadkey.len() > HilbertCurve::MAX_ORDER as usize {
+        return None;
+    }
+
+    let mut id = 0;
+    for digit in quadkey.bytes() {
+        match digit {
+            b'0'..=b'3' => id = id << 2 | (digit - b'0') as usize,
+            _ => return None,
+        }
+    }
+
+    let zoom = quadkey.len() as u32;
+    Some((tile_id_to_hilbert(id, zoom), zoom))
+}
+
+/// Get the Hilbert key of the slippy-map tile `zoom/x/y`.
+///
+/// # Panics
+///
+/// Panics if `zoom` is greater than [`HilbertCurve::MAX_ORDER`] or either coordinate is not lower
+/// than `2^zoom`.
+pub fn tile_to_key(zoom: u32, x: usize, y: usize) -> HilbertKey {
+    HilbertKey::new(x, y, zoom)
+}
+
+/// Get the slippy-map tile `(zoom, x, y)` with the given Hilbert key.
+pub fn key_to_tile(key: HilbertKey) -> (u32, usize, usize) {
+    let point = key.point();
+    (key.order(), point.x, point.y)
+}
+
+/// Get the distances along the curve of order `child_zoom` of the tiles covered by the tile
+/// `zoom/x/y`, which form a single contiguous range.
+///
+/// # Panics
+///
+/// Panics if `child_zoom` is lower than `zoom` or greater than [`HilbertCurve::MAX_ORDER`], or if
+/// either coordinate is not lower than `2^zoom`.
+pub fn tile_children_range(zoom: u32, x: usize, y: usize, child_zoom: u32) -> Range<usize> {
+    demote(tile_to_key(zoom, x, y).distance(), zoom, child_zoom)
+}
+
+fn side(d: usize, zoom: u32) -> usize {
+    assert!(
+        zoom <= HilbertCurve::MAX_ORDER,
+        "zoom must not exceed HilbertCurve::MAX_ORDER"
+    );
+    assert!(d >> (2 * zoom) == 0, "d must be lower than 4^zoom");
+    1 << zoom
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d, morton};
+
+    #[test]
+    fn quadkeys() {
+        // the examples of the Bing Maps tile system documentation
+        let d = crate::convert_2d_to_1d(3, 5, 8);
+        assert_eq!(hilbert_to_quadkey(d, 3), "213");
+        assert_eq!(quadkey_to_hilbert("213"), Some((d, 3)));
+        assert_eq!(quadkey_to_hilbert(""), Some((0, 0)));
+        assert_eq!(hilbert_to_quadkey(0, 0), "");
+        assert_eq!(quadkey_to_hilbert("0124"), None);
+
+        let zoom = 4;
+        for d in 0..256 {
+            let (x, y) = convert_1d_to_2d(d, 16);
+            let id = hilbert_to_tile_id(d, zoom);
+            assert_eq!(morton::convert_1d_to_2d(id, 16), (x, y));
+            assert_eq!(tile_id_to_hilbert(id, zoom), d);
+            assert_eq!(
+                quadkey_to_hilbert(&hilbert_to_quadkey(d, zoom)),
+                Some((d, zoom))
+            );
+        }
+    }
+
+    #[test]
+    fn tiles() {
+        let (zoom, x, y) = (3, 5, 2);
+        let key = tile_to_key(zoom, x, y);
+        assert_eq!(key_to_tile(key), (zoom, x, y));
+
+        let range = tile_children_range(zoom, x, y, zoom + 2);
+        assert_eq!(range.len(), 16);
+        for d in range {
+            let (_, cx, cy) = key_to_tile(HilbertKey::from_distance(d, zoom + 2));
+            assert_eq!((cx / 4, cy / 4), (x, y));
+        }
+        assert_eq!(
+            tile_children_range(zoom, x, y, zoom),
+            key.distance()..key.distance() + 1
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! Quantization of floating-point points onto the grid, and fitting of integer points into it.
+
+use core::{error, fmt};
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d, HilbertCurve, HilbertIndex};
+
+/// An axis-aligned rectangle in floating-point space mapped onto the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Bounds {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Bounds {
+    /// Create bounds with the given corners.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bounds are empty or not finite.
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        match Self::try_new(min_x, min_y, max_x, max_y) {
+            Ok(bounds) => bounds,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create bounds with the given corners, returning an error if they are empty or not finite.
+    pub fn try_new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Result<Self, InvalidBounds> {
+        let positive = min_x < max_x && min_y < max_y;
+        if !positive || !(max_x - min_x).is_finite() || !(max_y - min_y).is_finite() {
+            return Err(InvalidBounds);
+        }
+
+        Ok(Bounds {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        })
+    }
+}
+
+// the bounds are validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bounds {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Bounds")]
+        struct Raw {
+            min_x: f64,
+            min_y: f64,
+            max_x: f64,
+            max_y: f64,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        Bounds::try_new(raw.min_x, raw.min_y, raw.max_x, raw.max_y)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The error returned for bounds which are empty or not finite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidBounds;
+
+impl fmt::Display for InvalidBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the bounds must be finite and have a positive width and height")
+    }
+}
+
+impl error::Error for InvalidBounds {}
+
+/// The way a floating-point coordinate is rounded to a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rounding {
+    /// The bounds are split into n equal, half-open cells; the upper bound itself falls outside.
+    Floor,
+    /// The bounds are mapped onto the cell indices, so that the lower bound corresponds to cell 0
+    /// and the upper bound to cell n − 1; coordinates are rounded to the nearest index, with ties
+    /// going to the even one.
+    HalfEven,
+}
+
+/// The treatment of coordinates falling outside the bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutOfRange {
+    /// The point is rejected.
+    Reject,
+    /// The coordinate is clamped to the nearest cell.
+    Clamp,
+}
+
+/// Quantize a floating-point point to the (x, y) coordinates of a cell of an n by n grid covering
+/// the given bounds; returns `None` if a coordinate is NaN or is rejected as out of range.
+pub fn quantize(
+    x: f64,
+    y: f64,
+    bounds: &Bounds,
+    n: usize,
+    rounding: Rounding,
+    out_of_range: OutOfRange,
+) -> Option<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let cx = quantize_axis(x, bounds.min_x, bounds.max_x, n, rounding, out_of_range)?;
+    let cy = quantize_axis(y, bounds.min_y, bounds.max_y, n, rounding, out_of_range)?;
+
+    Some((cx, cy))
+}
+
+/// Quantize a floating-point point like [`quantize`] and convert the resulting cell to its
+/// one-dimensional distance.
+pub fn point_to_d(
+    x: f64,
+    y: f64,
+    bounds: &Bounds,
+    n: usize,
+    rounding: Rounding,
+    out_of_range: OutOfRange,
+) -> Option<usize> {
+    quantize(x, y, bounds, n, rounding, out_of_range).map(|(x, y)| convert_2d_to_1d(x, y, n))
+}
+
+/// A mapping of floating-point points onto the grid of a curve, which keeps the transform so that
+/// the cells can be mapped back to points.
+///
+/// It rounds with [`Rounding::Floor`] and clamps the points outside the bounds with
+/// [`OutOfRange::Clamp`] unless configured otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GridMapper {
+    bounds: Bounds,
+    order: u32,
+    rounding: Rounding,
+    out_of_range: OutOfRange,
+}
+
+impl GridMapper {
+    /// Create a mapper of the given bounds onto the grid of a curve of the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(bounds: Bounds, order: u32) -> Self {
+        assert!(
+            order <= HilbertCurve::MAX_ORDER,
+            "order must not exceed HilbertCurve::MAX_ORDER"
+        );
+        GridMapper {
+            bounds,
+            order,
+            rounding: Rounding::Floor,
+            out_of_range: OutOfRange::Clamp,
+        }
+    }
+
+    /// Create a mapper of the bounding box of the given points onto the grid of a curve of the
+    /// given order; the points with a non-finite coordinate are ignored, and a degenerate extent
+    /// is widened to a unit one.
+    ///
+    /// With [`Rounding::Floor`], the points on the upper bounds fall outside them, so they are
+    /// only mapped to the last cells when clamping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`], there are no points with
+    /// finite coordinates, or the extent of the points overflows an `f64`.
+    pub fn fit(points: &[(f64, f64)], order: u32) -> Self {
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        let finite = points
+            .iter()
+            .filter(|(x, y)| x.is_finite() && y.is_finite());
+        for &(x, y) in finite {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        assert!(
+            [min_x, min_y, max_x, max_y].iter().all(|c| c.is_finite()),
+            "there must be points with finite coordinates"
+        );
+        let bounds = Bounds::new(
+            min_x,
+            min_y,
+            if max_x > min_x { max_x } else { min_x + 1.0 },
+            if max_y > min_y { max_y } else { min_y + 1.0 },
+        );
+
+        Self::new(bounds, order)
+    }
+
+    /// Use the given rounding instead of [`Rounding::Floor`].
+    pub fn with_rounding(self, rounding: Rounding) -> Self {
+        GridMapper { rounding, ..self }
+    }
+
+    /// Use the given treatment of the points outside the bounds instead of [`OutOfRange::Clamp`].
+    pub fn with_out_of_range(self, out_of_range: OutOfRange) -> Self {
+        GridMapper {
+            out_of_range,
+            ..self
+        }
+    }
+
+    /// The bounds mapped onto the grid.
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The curve filling the grid.
+    pub fn curve(&self) -> HilbertCurve {
+        HilbertCurve::new(self.order)
+    }
+
+    /// Get the cell of the given point, or `None` if it is rejected as by [`quantize`].
+    pub fn point_to_cell(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        quantize(
+            x,
+            y,
+            &self.bounds,
+            self.side(),
+            self.rounding,
+            self.out_of_range,
+        )
+    }
+
+    /// Get the distance of the cell of the given point, or `None` if it is rejected as by
+    /// [`quantize`].
+    pub fn point_to_index(&self, x: f64, y: f64) -> Option<HilbertIndex> {
+        let (x, y) = self.point_to_cell(x, y)?;
+        Some(HilbertIndex(convert_2d_to_1d(x, y, self.side()) as u64))
+    }
+
+    /// Get the point represented by the given cell, i.e. the center of its share of the bounds,
+    /// or the point rounded to it exactly with [`Rounding::HalfEven`].
+    pub fn cell_center(&self, x: usize, y: usize) -> (f64, f64) {
+        let n = self.side();
+        let axis = |v: usize, min: f64, max: f64| match self.rounding {
+            Rounding::Floor => min + (v as f64 + 0.5) * (max - min) / n as f64,
+            Rounding::HalfEven if n == 1 => min,
+            Rounding::HalfEven => min + v as f64 * (max - min) / (n - 1) as f64,
+        };
+
+        (
+            axis(x, self.bounds.min_x, self.bounds.max_x),
+            axis(y, self.bounds.min_y, self.bounds.max_y),
+        )
+    }
+
+    /// Get the point represented by the cell at the given distance, as by
+    /// [`GridMapper::cell_center`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is not lower than the length of the curve.
+    pub fn index_to_cell_center(&self, index: HilbertIndex) -> (f64, f64) {
+        let n = self.side();
+        assert!(
+            index.0 < (n * n) as u64,
+            "the index must be lower than the length of the curve"
+        );
+        let (x, y) = convert_1d_to_2d(index.0 as usize, n);
+        self.cell_center(x, y)
+    }
+
+    fn side(&self) -> usize {
+        1 << self.order
+    }
+}
+
+// the bounds and the order are validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GridMapper {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "GridMapper")]
+        struct Raw {
+            bounds: Bounds,
+            order: u32,
+            rounding: Rounding,
+            out_of_range: OutOfRange,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        HilbertCurve::try_new(raw.order).map_err(serde::de::Error::custom)?;
+        Ok(GridMapper::new(raw.bounds, raw.order)
+            .with_rounding(raw.rounding)
+            .with_out_of_range(raw.out_of_range))
+    }
+}
+
+/// Find the lowest order of a curve whose grid covers the given integer points once they are
+/// translated by subtracting the returned offset from them, which is the lower left corner of
+/// their bounding box; there being no points, the result is `(0, (0, 0))`.
+///
+/// # Panics
+///
+/// Panics if covering the points requires an order greater than [`HilbertCurve::MAX_ORDER`].
+pub fn fit_order(points: &[(i64, i64)]) -> (u32, (i64, i64)) {
+    let mut iter = points.iter();
+    let Some(&(x, y)) = iter.next() else {
+        return (0, (0, 0));
+    };
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (x, y, x, y);
+    for &(x, y) in iter {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    // the differences fit in a u64 even if they overflow an i64
+    let span = (max_x.wrapping_sub(min_x) as u64).max(max_y.wrapping_sub(min_y) as u64);
+    let order = u64::BITS - span.leading_zeros();
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "the points are too far apart to be covered by a curve"
+    );
+
+    (order, (min_x, min_y))
+}
+
+/// A translation of integer points onto the grid of a curve, as found by [`fit_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Normalizer {
+    order: u32,
+    offset: (i64, i64),
+}
+
+impl Normalizer {
+    /// Create a normalizer mapping the point `offset` to the cell (0, 0) of the grid of a curve of
+    /// the given order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+    pub fn new(order: u32, offset: (i64, i64)) -> Self {
+        assert!(
+            order <= HilbertCurve::MAX_ORDER,
+            "order must not exceed HilbertCurve::MAX_ORDER"
+        );
+        Normalizer { order, offset }
+    }
+
+    /// Create the normalizer of the smallest grid covering the given points.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`fit_order`].
+    pub fn fit(points: &[(i64, i64)]) -> Self {
+        let (order, offset) = fit_order(points);
+        Normalizer { order, offset }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The point mapped to the cell (0, 0).
+    pub fn offset(&self) -> (i64, i64) {
+        self.offset
+    }
+
+    /// The curve filling the grid.
+    pub fn curve(&self) -> HilbertCurve {
+        HilbertCurve::new(self.order)
+    }
+
+    /// Get the cell of the given point, or `None` if it is outside the grid.
+    pub fn normalize(&self, x: i64, y: i64) -> Option<(usize, usize)> {
+        let side = 1i128 << self.order;
+        let axis = |v: i64, offset: i64| {
+            let v = v as i128 - offset as i128;
+            (0..side).contains(&v).then_some(v as usize)
+        };
+
+        Some((axis(x, self.offset.0)?, axis(y, self.offset.1)?))
+    }
+
+    /// Get the point of the given cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is not representable, i.e. the grid extends past `i64::MAX`.
+    pub fn denormalize(&self, x: usize, y: usize) -> (i64, i64) {
+        let axis = |v: usize, offset: i64| {
+            offset
+                .checked_add(v as i64)
+                .expect("the point must be representable")
+        };
+
+        (axis(x, self.offset.0), axis(y, self.offset.1))
+    }
+
+    /// Get the distance of the cell of the given point, or `None` if it is outside the grid.
+    pub fn point_to_d(&self, x: i64, y: i64) -> Option<usize> {
+        let (x, y) = self.normalize(x, y)?;
+        Some(convert_2d_to_1d(x, y, 1 << self.order))
+    }
+
+    /// Get the point of the cell at the given distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is not representable, i.e. the grid extends past `i64::MAX`.
+    pub fn d_to_point(&self, d: usize) -> (i64, i64) {
+        let (x, y) = convert_1d_to_2d(d, 1 << self.order);
+        self.denormalize(x, y)
+    }
+}
+
+// the order is validated when deserializing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Normalizer {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Normalizer")]
+        struct Raw {
+            order: u32,
+            offset: (i64, i64),
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        HilbertCurve::try_new(raw.order).map_err(serde::de::Error::custom)?;
+        Ok(Normalizer {
+            order: raw.order,
+            offset: raw.offset,
+        })
+    }
+}
+
+fn quantize_axis(
+    v: f64,
+    min: f64,
+    max: f64,
+    n: usize,
+    rounding: Rounding,
+    out_of_range: OutOfRange,
+) -> Option<usize> {
+    if v.is_nan() {
+        return None;
+    }
+    let last = n - 1;
+    let unit = (v - min) / (max - min);
+
+    match rounding {
+        Rounding::Floor => {
+            if !(0.0..1.0).contains(&unit) && out_of_range == OutOfRange::Reject {
+                return None;
+            }
+            // the cast saturates, so negative values become 0
+            Some(((unit * n as f64) as usize).min(last))
+        }
+        Rounding::HalfEven => {
+            if !(0.0..=1.0).contains(&unit) && out_of_range == OutOfRange::Reject {
+                return None;
+            }
+            let scaled = unit.max(0.0) * last as f64;
+            Some(round_half_even(scaled).min(last))
+        }
+    }
+}
+
+// Round a non-negative value to the nearest integer, with ties going to the even one
+fn round_half_even(v: f64) -> usize {
+    let int = v as usize;
+    let frac = v - int as f64;
+
+    if frac > 0.5 || (frac == 0.5 && int % 2 == 1) {
+        int.saturating_add(1)
+    } else {
+        int
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let bounds = Bounds::new(-1.0, 0.0, 1.0, 2.5);
+        let json = serde_json::to_string(&bounds).unwrap();
+        assert_eq!(serde_json::from_str::<Bounds>(&json).unwrap(), bounds);
+        let empty = json.replace("2.5", "0.0");
+        assert!(serde_json::from_str::<Bounds>(&empty).is_err());
+
+        let normalizer = Normalizer::new(5, (-3, 7));
+        let json = serde_json::to_string(&normalizer).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Normalizer>(&json).unwrap(),
+            normalizer
+        );
+        let large = json.replace(":5,", ":40,");
+        assert!(serde_json::from_str::<Normalizer>(&large).is_err());
+
+        let mapper = GridMapper::new(bounds, 6).with_rounding(Rounding::HalfEven);
+        let json = serde_json::to_string(&mapper).unwrap();
+        assert_eq!(serde_json::from_str::<GridMapper>(&json).unwrap(), mapper);
+        let large = json.replace(":6,", ":40,");
+        assert!(serde_json::from_str::<GridMapper>(&large).is_err());
+        let empty = json.replace("2.5", "0.0");
+        assert!(serde_json::from_str::<GridMapper>(&empty).is_err());
+    }
+
+    #[test]
+    fn floor() {
+        let bounds = Bounds::new(-1.0, 0.0, 1.0, 8.0);
+        let q = |x, y, oor| quantize(x, y, &bounds, 4, Rounding::Floor, oor);
+        assert_eq!(q(-1.0, 0.0, OutOfRange::Reject), Some((0, 0)));
+        assert_eq!(q(0.0, 3.99, OutOfRange::Reject), Some((2, 1)));
+        assert_eq!(q(0.99, 7.99, OutOfRange::Reject), Some((3, 3)));
+        assert_eq!(q(1.0, 0.0, OutOfRange::Reject), None);
+        assert_eq!(q(1.0, -5.0, OutOfRange::Clamp), Some((3, 0)));
+        assert_eq!(q(f64::NAN, 0.0, OutOfRange::Clamp), None);
+    }
+
+    #[test]
+    fn half_even() {
+        let bounds = Bounds::new(0.0, 0.0, 3.0, 3.0);
+        let q = |x, y, oor| quantize(x, y, &bounds, 4, Rounding::HalfEven, oor);
+        assert_eq!(q(0.0, 3.0, OutOfRange::Reject), Some((0, 3)));
+        assert_eq!(q(0.5, 1.5, OutOfRange::Reject), Some((0, 2)));
+        assert_eq!(q(2.5, 1.49, OutOfRange::Reject), Some((2, 1)));
+        assert_eq!(q(3.1, 0.0, OutOfRange::Reject), None);
+        assert_eq!(q(3.1, -0.1, OutOfRange::Clamp), Some((3, 0)));
+    }
+
+    #[test]
+    fn distance() {
+        let bounds = Bounds::new(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(
+            point_to_d(0.3, 0.9, &bounds, 8, Rounding::Floor, OutOfRange::Reject),
+            Some(convert_2d_to_1d(2, 7, 8))
+        );
+    }
+
+    #[test]
+    fn fitting() {
+        assert_eq!(fit_order(&[]), (0, (0, 0)));
+        assert_eq!(fit_order(&[(-3, 7)]), (0, (-3, 7)));
+        assert_eq!(fit_order(&[(0, 0), (1, 0)]), (1, (0, 0)));
+        assert_eq!(fit_order(&[(0, 0), (2, 0)]), (2, (0, 0)));
+        assert_eq!(fit_order(&[(-5, 10), (-2, 3), (-4, 4)]), (3, (-5, 3)));
+        assert_eq!(
+            fit_order(&[(i64::MIN, 0), (i64::MIN + (1 << 31) - 1, 0)]),
+            (31, (i64::MIN, 0))
+        );
+
+        let points = [(-5, 10), (-2, 3), (-4, 4)];
+        let normalizer = Normalizer::fit(&points);
+        assert_eq!(normalizer.curve().side(), 8);
+        for &(x, y) in &points {
+            let (cx, cy) = normalizer.normalize(x, y).unwrap();
+            assert_eq!(normalizer.denormalize(cx, cy), (x, y));
+            let d = normalizer.point_to_d(x, y).unwrap();
+            assert_eq!(normalizer.d_to_point(d), (x, y));
+        }
+        assert_eq!(normalizer.normalize(-5, 3), Some((0, 0)));
+        assert_eq!(normalizer.normalize(2, 3), Some((7, 0)));
+        assert_eq!(normalizer.normalize(3, 3), None);
+        assert_eq!(normalizer.normalize(-6, 3), None);
+        assert_eq!(
+            Normalizer::new(0, (i64::MAX, 0)).normalize(i64::MAX, 0),
+            Some((0, 0))
+        );
+    }
+
+    #[test]
+    fn mapping() {
+        let points = [
+            (-10.0, 5.0),
+            (10.0, 5.0),
+            (0.0, f64::NAN),
+            (f64::NEG_INFINITY, 5.5),
+            (2.5, 6.0),
+        ];
+        let mapper = GridMapper::fit(&points, 3);
+        assert_eq!(mapper.bounds(), Bounds::new(-10.0, 5.0, 10.0, 6.0));
+        assert_eq!(mapper.point_to_cell(-10.0, 5.0), Some((0, 0)));
+        assert_eq!(mapper.point_to_cell(10.0, 6.0), Some((7, 7)));
+        assert_eq!(mapper.point_to_cell(0.0, f64::NAN), None);
+        assert_eq!(
+            mapper
+                .with_out_of_range(OutOfRange::Reject)
+                .point_to_cell(10.0, 6.0),
+            None
+        );
+
+        let index = mapper.point_to_index(2.5, 5.5).unwrap();
+        assert_eq!(index, mapper.curve().point_to_index((5, 4).into()));
+        assert_eq!(mapper.index_to_cell_center(index), (3.75, 5.5625));
+        for d in 0..64 {
+            // the center of every cell maps back to it
+            let (x, y) = mapper.index_to_cell_center(HilbertIndex(d));
+            assert_eq!(mapper.point_to_index(x, y), Some(HilbertIndex(d)));
+        }
+
+        let mapper = mapper.with_rounding(Rounding::HalfEven);
+        assert_eq!(mapper.cell_center(0, 7), (-10.0, 6.0));
+        assert_eq!(mapper.point_to_cell(10.0, 6.0), Some((7, 7)));
+        assert_eq!(
+            GridMapper::fit(&[(1.0, 1.0)], 0).index_to_cell_center(HilbertIndex(0)),
+            (1.5, 1.5)
+        );
+    }
+}
@@ -0,0 +1,836 @@
+//! Translation of spatial queries to ranges of curve distances.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "alloc")]
+use core::ops::RangeInclusive;
+
+#[cfg(feature = "alloc")]
+use crate::{convert_2d_to_1d, HilbertError, HilbertIndex};
+use crate::{
+    lut::{descend, quadrant},
+    HilbertCurve,
+};
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into sorted, disjoint ranges of
+/// distances covering exactly its cells; if that takes more than `max_ranges` ranges, the ones
+/// separated by the smallest gaps are merged, so the result also covers some cells outside the
+/// rectangle.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2, the rectangle is not within the grid, or `max_ranges` is 0.
+#[cfg(feature = "alloc")]
+pub fn ranges_for_rect(
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    n: usize,
+    max_ranges: usize,
+) -> Vec<Range<usize>> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        x0 <= x1 && y0 <= y1 && x1 < n && y1 < n,
+        "the rectangle must be within the grid"
+    );
+    assert!(max_ranges > 0, "max_ranges must be positive");
+
+    let rect = Rect { x0, y0, x1, y1 };
+    let mut ranges = Vec::new();
+    Square::root(n).cover(&|square| rect.classify(square), 1, &mut ranges);
+    coalesce(&mut ranges, max_ranges);
+
+    ranges
+}
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into ranges of distances, like
+/// [`ranges_for_rect`], returning an error instead of panicking if the inputs are invalid.
+#[cfg(feature = "alloc")]
+pub fn try_ranges_for_rect(
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    n: usize,
+    max_ranges: usize,
+) -> Result<Vec<Range<usize>>, HilbertError> {
+    HilbertCurve::try_from_side(n)?;
+    if x1 >= n || y1 >= n {
+        return Err(HilbertError::CoordinateOutOfRange { x: x1, y: y1, n });
+    }
+    if x0 > x1 || y0 > y1 {
+        return Err(HilbertError::EmptyRect { x0, y0, x1, y1 });
+    }
+    if max_ranges == 0 {
+        return Err(HilbertError::ZeroMaxRanges);
+    }
+
+    Ok(ranges_for_rect(x0, y0, x1, y1, n, max_ranges))
+}
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into the sorted, disjoint ranges of
+/// distances covering exactly its cells, like [`ranges_for_rect`] without a limit on their number,
+/// but computing them lazily.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or the rectangle is not within the grid.
+#[cfg(feature = "alloc")]
+pub fn rect_ranges(x0: usize, y0: usize, x1: usize, y1: usize, n: usize) -> RectRanges {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        x0 <= x1 && y0 <= y1 && x1 < n && y1 < n,
+        "the rectangle must be within the grid"
+    );
+
+    RectRanges {
+        rect: Rect { x0, y0, x1, y1 },
+        stack: alloc::vec![Square::root(n)],
+        pending: None,
+    }
+}
+
+/// An iterator over the ranges of distances covering a rectangle, created by [`rect_ranges`].
+#[cfg(feature = "alloc")]
+pub struct RectRanges {
+    rect: Rect,
+    // the squares left to visit, the next one on top
+    stack: Vec<Square>,
+    pending: Option<Range<usize>>,
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for RectRanges {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while let Some(square) = self.stack.pop() {
+            let range = match self.rect.classify(&square) {
+                Coverage::Outside => continue,
+                Coverage::Partial if square.side > 1 => {
+                    let len = self.stack.len();
+                    self.stack.extend(square.children());
+                    self.stack[len..].reverse();
+                    continue;
+                }
+                _ => square.d..square.d + square.len(),
+            };
+            match &mut self.pending {
+                Some(pending) if pending.end == range.start => pending.end = range.end,
+                pending => {
+                    if let Some(done) = pending.replace(range) {
+                        return Some(done);
+                    }
+                }
+            }
+        }
+
+        self.pending.take()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::iter::FusedIterator for RectRanges {}
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into ranges of big-endian byte keys
+/// of `W` bytes, as encoded by [`HilbertIndex::to_be_bytes`], for scans over a key-value store;
+/// adjacent ranges are merged and their number is limited like in [`ranges_for_rect`]. The ranges
+/// are inclusive, so the key following the end of the curve doesn't need to be representable.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2, the rectangle is not within the grid, `max_ranges` is 0, or
+/// the distances of the curve don't fit in `W` bytes.
+#[cfg(feature = "alloc")]
+pub fn byte_ranges_for_rect<const W: usize>(
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    n: usize,
+    max_ranges: usize,
+) -> Vec<RangeInclusive<[u8; W]>> {
+    let key = |d: usize| HilbertIndex(d as u64).to_be_bytes::<W>();
+    ranges_for_rect(x0, y0, x1, y1, n, max_ranges)
+        .into_iter()
+        .map(|range| key(range.start)..=key(range.end - 1))
+        .collect()
+}
+
+/// Cover the given polygon, as a list of vertices in grid coordinates (the cell (x, y) spanning
+/// [x, x + 1) × [y, y + 1)), with sorted, disjoint ranges of distances; squares of `min_side`
+/// cells that are only partially within the polygon are covered whole, and if that takes more
+/// than `max_ranges` ranges, the ones separated by the smallest gaps are merged.
+///
+/// # Panics
+///
+/// Panics if `n` or `min_side` is not a power of 2, `min_side` is greater than `n`, the polygon
+/// has fewer than 3 vertices, or `max_ranges` is 0.
+#[cfg(feature = "alloc")]
+pub fn ranges_for_polygon(
+    polygon: &[(f64, f64)],
+    n: usize,
+    min_side: usize,
+    max_ranges: usize,
+) -> Vec<Range<usize>> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        min_side.is_power_of_two() && min_side <= n,
+        "min_side must be a power of 2 not greater than n"
+    );
+    assert!(
+        polygon.len() >= 3,
+        "the polygon must have at least 3 vertices"
+    );
+    assert!(max_ranges > 0, "max_ranges must be positive");
+
+    let mut ranges = Vec::new();
+    Square::root(n).cover(
+        &|square| classify_polygon(polygon, square),
+        min_side,
+        &mut ranges,
+    );
+    coalesce(&mut ranges, max_ranges);
+
+    ranges
+}
+
+/// Get the smallest rectangle, as inclusive (x0, y0, x1, y1) bounds, containing all the cells of
+/// the given segment of the curve; returns `None` if the segment is empty.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or the segment extends past n^2.
+pub fn segment_bounding_box(
+    segment: Range<usize>,
+    n: usize,
+) -> Option<(usize, usize, usize, usize)> {
+    let curve = HilbertCurve::try_from_side(n).expect("n must be a valid side");
+    assert!(
+        segment.end <= curve.len(),
+        "the segment must be within the curve"
+    );
+    if segment.start >= segment.end {
+        return None;
+    }
+
+    let mut bounds = Rect {
+        x0: usize::MAX,
+        y0: usize::MAX,
+        x1: 0,
+        y1: 0,
+    };
+    Square::root(n).extend_bounds(&segment, &mut bounds);
+
+    Some((bounds.x0, bounds.y0, bounds.x1, bounds.y1))
+}
+
+/// Count the cells of the given segment of the curve within the rectangle spanning `x0..=x1` and
+/// `y0..=y1`, without enumerating them; the whole curve (`0..n * n`) counts all the cells of the
+/// rectangle.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`], the rectangle is not within the grid,
+/// or the segment extends past n^2.
+pub fn count_in_rect(
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    segment: Range<usize>,
+    n: usize,
+) -> usize {
+    let curve = HilbertCurve::try_from_side(n).expect("n must be a valid side");
+    assert!(
+        x0 <= x1 && y0 <= y1 && x1 < n && y1 < n,
+        "the rectangle must be within the grid"
+    );
+    assert!(
+        segment.end <= curve.len(),
+        "the segment must be within the curve"
+    );
+
+    Square::root(n).count(&Rect { x0, y0, x1, y1 }, &segment)
+}
+
+/// Iterate over the cells crossed by the segment from `from` to `to`, in grid coordinates (the
+/// cell (x, y) spanning [x, x + 1) × [y, y + 1)), in curve order, yielding `(d, x, y)`; the parts
+/// of the segment outside the grid are ignored.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2.
+#[cfg(feature = "alloc")]
+pub fn cells_on_segment(
+    from: (f64, f64),
+    to: (f64, f64),
+    n: usize,
+) -> impl ExactSizeIterator<Item = (usize, usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let mut cells = Vec::new();
+    let side = n as f64;
+
+    if let Some((t0, t1)) = clip(from, to, (0.0, 0.0), (side, side), false) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let cell = |t: f64, delta: f64, start: f64| ((start + t * delta) as usize).min(n - 1);
+        let (mut x, mut y) = (cell(t0, dx, from.0), cell(t0, dy, from.1));
+
+        // the parameters of the next vertical and horizontal grid lines crossed by the segment
+        let next = |c: usize, delta: f64, start: f64| {
+            if delta > 0.0 {
+                ((c + 1) as f64 - start) / delta
+            } else if delta < 0.0 {
+                (c as f64 - start) / delta
+            } else {
+                f64::INFINITY
+            }
+        };
+        let (mut tx, mut ty) = (next(x, dx, from.0), next(y, dy, from.1));
+        let (step_x, step_y) = (1.0 / dx.abs(), 1.0 / dy.abs());
+
+        loop {
+            cells.push((convert_2d_to_1d(x, y, n), x, y));
+            if tx.min(ty) >= t1 {
+                break;
+            }
+            if tx < ty {
+                if dx > 0.0 && x + 1 < n {
+                    x += 1;
+                } else if dx < 0.0 && x > 0 {
+                    x -= 1;
+                } else {
+                    break;
+                }
+                tx += step_x;
+            } else {
+                if dy > 0.0 && y + 1 < n {
+                    y += 1;
+                } else if dy < 0.0 && y > 0 {
+                    y -= 1;
+                } else {
+                    break;
+                }
+                ty += step_y;
+            }
+        }
+    }
+
+    cells.sort_unstable();
+    cells.into_iter()
+}
+
+// An inclusive rectangle
+struct Rect {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+}
+
+impl Rect {
+    #[cfg(feature = "alloc")]
+    fn classify(&self, square: &Square) -> Coverage {
+        let (x_end, y_end) = (square.x + square.side - 1, square.y + square.side - 1);
+        if square.x > self.x1 || square.y > self.y1 || x_end < self.x0 || y_end < self.y0 {
+            Coverage::Outside
+        } else if square.x >= self.x0 && square.y >= self.y0 && x_end <= self.x1 && y_end <= self.y1
+        {
+            Coverage::Inside
+        } else {
+            Coverage::Partial
+        }
+    }
+}
+
+// The overlap of a square with a queried region
+#[cfg(feature = "alloc")]
+enum Coverage {
+    Outside,
+    Inside,
+    Partial,
+}
+
+// A square of the grid filled by a sub-curve that starts at distance `d` in the given state
+struct Square {
+    x: usize,
+    y: usize,
+    side: usize,
+    d: usize,
+    state: usize,
+}
+
+impl Square {
+    fn root(n: usize) -> Self {
+        Square {
+            x: 0,
+            y: 0,
+            side: n,
+            d: 0,
+            state: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.side * self.side
+    }
+
+    // The quadrants of the square, in curve order
+    fn children(&self) -> impl Iterator<Item = Square> + '_ {
+        let half = self.side / 2;
+        (0..4).map(move |digit| {
+            let (qx, qy) = quadrant(self.state, digit);
+            Square {
+                x: self.x + qx * half,
+                y: self.y + qy * half,
+                side: half,
+                d: self.d + digit * half * half,
+                state: self.state ^ descend(digit),
+            }
+        })
+    }
+
+    // Add the ranges of the cells overlapping the region; squares of `min_side` that overlap it
+    // partially are added whole
+    #[cfg(feature = "alloc")]
+    fn cover(
+        &self,
+        classify: &impl Fn(&Square) -> Coverage,
+        min_side: usize,
+        ranges: &mut Vec<Range<usize>>,
+    ) {
+        match classify(self) {
+            Coverage::Outside => {}
+            Coverage::Partial if self.side > min_side => {
+                for child in self.children() {
+                    child.cover(classify, min_side, ranges);
+                }
+            }
+            _ => {
+                let range = self.d..self.d + self.len();
+                match ranges.last_mut() {
+                    Some(last) if last.end == range.start => last.end = range.end,
+                    _ => ranges.push(range),
+                }
+            }
+        }
+    }
+
+    // Count the cells of the segment within both the square and the rectangle; only the squares
+    // partially covering both of them are split, and there are at most two of them per level
+    fn count(&self, rect: &Rect, segment: &Range<usize>) -> usize {
+        let (start, end) = (
+            segment.start.max(self.d),
+            segment.end.min(self.d + self.len()),
+        );
+        let (x_end, y_end) = (self.x + self.side - 1, self.y + self.side - 1);
+        let width = (x_end.min(rect.x1) + 1).saturating_sub(self.x.max(rect.x0));
+        let height = (y_end.min(rect.y1) + 1).saturating_sub(self.y.max(rect.y0));
+
+        if start >= end || width == 0 || height == 0 {
+            0
+        } else if end - start == self.len() {
+            width * height
+        } else if width * height == self.len() {
+            end - start
+        } else {
+            self.children()
+                .map(|child| child.count(rect, segment))
+                .sum()
+        }
+    }
+
+    // Extend the bounds with the cells of the segment within the square
+    fn extend_bounds(&self, segment: &Range<usize>, bounds: &mut Rect) {
+        if self.d >= segment.end || self.d + self.len() <= segment.start {
+            return;
+        }
+
+        if segment.start <= self.d && self.d + self.len() <= segment.end {
+            bounds.x0 = bounds.x0.min(self.x);
+            bounds.y0 = bounds.y0.min(self.y);
+            bounds.x1 = bounds.x1.max(self.x + self.side - 1);
+            bounds.y1 = bounds.y1.max(self.y + self.side - 1);
+            return;
+        }
+
+        for child in self.children() {
+            child.extend_bounds(segment, bounds);
+        }
+    }
+}
+
+// A square is partially within a polygon if an edge of the polygon crosses its interior, and
+// otherwise entirely within it or outside of it, as is its center
+#[cfg(feature = "alloc")]
+fn classify_polygon(polygon: &[(f64, f64)], square: &Square) -> Coverage {
+    let (x0, y0) = (square.x as f64, square.y as f64);
+    let (x1, y1) = (x0 + square.side as f64, y0 + square.side as f64);
+    let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+
+    for (&p, &q) in edges {
+        if crosses_interior(p, q, (x0, y0), (x1, y1)) {
+            return Coverage::Partial;
+        }
+    }
+
+    if contains(polygon, ((x0 + x1) / 2.0, (y0 + y1) / 2.0)) {
+        Coverage::Inside
+    } else {
+        Coverage::Outside
+    }
+}
+
+// Check whether the segment from `p` to `q` crosses the interior of the box spanning `min` to
+// `max`
+#[cfg(feature = "alloc")]
+fn crosses_interior(p: (f64, f64), q: (f64, f64), min: (f64, f64), max: (f64, f64)) -> bool {
+    clip(p, q, min, max, true).is_some_and(|(t0, t1)| t0 < t1)
+}
+
+// Clip the segment from `p` to `q` against every side of the box spanning `min` to `max`, getting
+// the interval of the segment's parameter within the box; segments running along a side are
+// outside of an open box
+#[cfg(feature = "alloc")]
+fn clip(
+    p: (f64, f64),
+    q: (f64, f64),
+    min: (f64, f64),
+    max: (f64, f64),
+    open: bool,
+) -> Option<(f64, f64)> {
+    let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+
+    for &(delta, dist) in &[
+        (-dx, p.0 - min.0),
+        (dx, max.0 - p.0),
+        (-dy, p.1 - min.1),
+        (dy, max.1 - p.1),
+    ] {
+        if delta == 0.0 {
+            if dist < 0.0 || open && dist == 0.0 {
+                return None;
+            }
+        } else {
+            let t = dist / delta;
+            if delta < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    (t0 <= t1).then_some((t0, t1))
+}
+
+// Check whether the point is within the polygon, using the even-odd rule
+#[cfg(feature = "alloc")]
+fn contains(polygon: &[(f64, f64)], (x, y): (f64, f64)) -> bool {
+    let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+    edges
+        .filter(|&(&(px, py), &(qx, qy))| {
+            (py > y) != (qy > y) && x < px + (y - py) / (qy - py) * (qx - px)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// Merge the given ranges of distances into sorted, disjoint ones covering the same distances;
+/// overlapping and adjacent ranges are joined, and empty ones dropped.
+#[cfg(feature = "alloc")]
+pub fn merge_ranges(ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut sorted: Vec<_> = ranges.iter().filter(|r| r.start < r.end).cloned().collect();
+    sorted.sort_unstable_by_key(|r| r.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+/// Simplify a covering made of the given ranges of distances, merged as by [`merge_ranges`], down
+/// to at most `max_ranges` ranges by closing the smallest gaps between them; returns the result
+/// together with the number of distances it covers in addition to the original ranges.
+///
+/// # Panics
+///
+/// Panics if `max_ranges` is 0.
+#[cfg(feature = "alloc")]
+pub fn simplify_ranges(ranges: &[Range<usize>], max_ranges: usize) -> (Vec<Range<usize>>, usize) {
+    assert!(max_ranges > 0, "max_ranges must be positive");
+    let mut ranges = merge_ranges(ranges);
+    let len = |ranges: &[Range<usize>]| ranges.iter().map(|r| r.len()).sum::<usize>();
+    let exact = len(&ranges);
+    coalesce(&mut ranges, max_ranges);
+    let extra = len(&ranges) - exact;
+
+    (ranges, extra)
+}
+
+// Merge the ranges separated by the smallest gaps until there are at most `max_ranges` of them
+#[cfg(feature = "alloc")]
+fn coalesce(ranges: &mut Vec<Range<usize>>, max_ranges: usize) {
+    if ranges.len() <= max_ranges {
+        return;
+    }
+
+    // the gaps to close, i.e. all but the `max_ranges - 1` largest ones
+    let mut gaps: Vec<usize> = (1..ranges.len()).collect();
+    gaps.sort_by_key(|&i| ranges[i].start - ranges[i - 1].end);
+    let mut closed = alloc::vec![false; ranges.len()];
+    for &i in &gaps[..ranges.len() - max_ranges] {
+        closed[i] = true;
+    }
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(max_ranges);
+    for (range, closed) in ranges.drain(..).zip(closed) {
+        match merged.last_mut() {
+            Some(last) if closed => last.end = range.end,
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn exact_cover() {
+        let n = 16;
+        for &(x0, y0, x1, y1) in &[(0, 0, 15, 15), (3, 2, 9, 12), (5, 5, 5, 5), (0, 7, 15, 8)] {
+            let ranges = ranges_for_rect(x0, y0, x1, y1, n, usize::MAX);
+            let mut expected: Vec<_> = (x0..=x1)
+                .flat_map(|x| (y0..=y1).map(move |y| convert_2d_to_1d(x, y, n)))
+                .collect();
+            expected.sort_unstable();
+            let covered: Vec<_> = ranges.iter().cloned().flatten().collect();
+            assert_eq!(covered, expected);
+            for pair in ranges.windows(2) {
+                assert!(pair[0].end < pair[1].start);
+            }
+        }
+        assert_eq!(ranges_for_rect(0, 0, 15, 15, n, 1), vec![0..n * n]);
+    }
+
+    #[test]
+    fn limited_ranges() {
+        let n = 32;
+        let exact = ranges_for_rect(3, 5, 20, 27, n, usize::MAX);
+        for max in 1..exact.len() {
+            let ranges = ranges_for_rect(3, 5, 20, 27, n, max);
+            assert_eq!(ranges.len(), max);
+            for range in &exact {
+                assert!(ranges
+                    .iter()
+                    .any(|r| r.start <= range.start && range.end <= r.end));
+            }
+        }
+    }
+
+    #[test]
+    fn fallible() {
+        assert_eq!(
+            try_ranges_for_rect(0, 0, 1, 1, 4, 8),
+            Ok(ranges_for_rect(0, 0, 1, 1, 4, 8))
+        );
+        assert_eq!(
+            try_ranges_for_rect(0, 0, 1, 1, 6, 8),
+            Err(HilbertError::NotPowerOfTwo { n: 6 })
+        );
+        assert_eq!(
+            try_ranges_for_rect(0, 0, 4, 1, 4, 8),
+            Err(HilbertError::CoordinateOutOfRange { x: 4, y: 1, n: 4 })
+        );
+        assert_eq!(
+            try_ranges_for_rect(2, 0, 1, 1, 4, 8),
+            Err(HilbertError::EmptyRect {
+                x0: 2,
+                y0: 0,
+                x1: 1,
+                y1: 1
+            })
+        );
+        assert_eq!(
+            try_ranges_for_rect(0, 0, 1, 1, 4, 0),
+            Err(HilbertError::ZeroMaxRanges)
+        );
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let n = 16;
+        for start in (0..n * n).step_by(7) {
+            for end in (start + 1..=n * n).step_by(5) {
+                let points: Vec<_> = (start..end).map(|d| convert_1d_to_2d(d, n)).collect();
+                let expected = (
+                    points.iter().map(|p| p.0).min().unwrap(),
+                    points.iter().map(|p| p.1).min().unwrap(),
+                    points.iter().map(|p| p.0).max().unwrap(),
+                    points.iter().map(|p| p.1).max().unwrap(),
+                );
+                assert_eq!(segment_bounding_box(start..end, n), Some(expected));
+            }
+        }
+        assert_eq!(segment_bounding_box(4..4, n), None);
+        assert_eq!(segment_bounding_box(0..4, n), Some((0, 0, 1, 1)));
+
+        let curve = HilbertCurve::new(HilbertCurve::MAX_ORDER);
+        let n = curve.side();
+        assert_eq!(
+            segment_bounding_box(0..curve.len(), n),
+            Some((0, 0, n - 1, n - 1))
+        );
+    }
+
+    #[test]
+    fn polygon_cover() {
+        let n = 32;
+        let rect = [(3.0, 5.0), (21.0, 5.0), (21.0, 28.0), (3.0, 28.0)];
+        assert_eq!(
+            ranges_for_polygon(&rect, n, 1, usize::MAX),
+            ranges_for_rect(3, 5, 20, 27, n, usize::MAX)
+        );
+
+        let triangle = [(2.5, 1.5), (29.0, 10.0), (9.5, 30.5)];
+        let fine: Vec<_> = ranges_for_polygon(&triangle, n, 1, usize::MAX)
+            .into_iter()
+            .flatten()
+            .collect();
+        let coarse: Vec<_> = ranges_for_polygon(&triangle, n, 4, usize::MAX)
+            .into_iter()
+            .flatten()
+            .collect();
+        for x in 0..n {
+            for y in 0..n {
+                let d = convert_2d_to_1d(x, y, n);
+                let center = (x as f64 + 0.5, y as f64 + 0.5);
+                if contains(&triangle, center) {
+                    assert!(fine.contains(&d));
+                }
+                if fine.contains(&d) {
+                    assert!(coarse.contains(&d));
+                }
+            }
+        }
+        assert!(fine.len() < coarse.len() && coarse.len() < n * n);
+        assert!(!fine.contains(&convert_2d_to_1d(30, 30, n)));
+        assert_eq!(ranges_for_polygon(&triangle, n, 1, 3).len(), 3);
+    }
+
+    #[test]
+    fn segment_cells() {
+        let n = 16;
+        for &(from, to) in &[
+            ((0.5, 0.5), (15.5, 12.3)),
+            ((14.2, 1.1), (3.7, 9.9)),
+            ((2.5, 3.5), (2.5, 13.5)),
+            ((-4.0, 2.3), (20.0, 7.1)),
+            ((5.3, 5.6), (5.7, 5.2)),
+        ] {
+            let cells: Vec<_> = cells_on_segment(from, to, n).collect();
+            let mut expected = Vec::new();
+            for x in 0..n {
+                for y in 0..n {
+                    let (min, max) = ((x as f64, y as f64), ((x + 1) as f64, (y + 1) as f64));
+                    if crosses_interior(from, to, min, max) {
+                        expected.push((convert_2d_to_1d(x, y, n), x, y));
+                    }
+                }
+            }
+            expected.sort_unstable();
+            assert_eq!(cells, expected);
+        }
+        assert_eq!(cells_on_segment((20.0, 0.0), (30.0, 5.0), n).len(), 0);
+    }
+
+    #[test]
+    fn counting() {
+        let n = 32;
+        for &(x0, y0, x1, y1) in &[
+            (0, 0, 31, 31),
+            (3, 5, 20, 27),
+            (7, 7, 7, 7),
+            (0, 12, 31, 13),
+        ] {
+            for &(start, end) in &[(0, 1024), (100, 900), (511, 513), (40, 40)] {
+                let expected = (start..end)
+                    .map(|d| convert_1d_to_2d(d, n))
+                    .filter(|&(x, y)| (x0..=x1).contains(&x) && (y0..=y1).contains(&y))
+                    .count();
+                assert_eq!(count_in_rect(x0, y0, x1, y1, start..end, n), expected);
+            }
+        }
+
+        let n = 1 << 14;
+        assert_eq!(
+            count_in_rect(5, 9, n - 10, n - 3, 0..n * n, n),
+            (n - 14) * (n - 11)
+        );
+
+        let curve = HilbertCurve::new(HilbertCurve::MAX_ORDER);
+        let n = curve.side();
+        assert_eq!(count_in_rect(0, 0, n - 1, 0, 0..curve.len(), n), n);
+    }
+
+    #[test]
+    fn byte_ranges() {
+        let n = 16;
+        let ranges = ranges_for_rect(3, 2, 9, 12, n, 5);
+        let bytes = byte_ranges_for_rect::<2>(3, 2, 9, 12, n, 5);
+        assert_eq!(bytes.len(), ranges.len());
+        for (range, bytes) in ranges.iter().zip(&bytes) {
+            assert_eq!(*bytes.start(), [0, range.start as u8]);
+            assert_eq!(*bytes.end(), [0, (range.end - 1) as u8]);
+        }
+        assert_eq!(
+            byte_ranges_for_rect::<1>(0, 0, 15, 15, n, 1),
+            vec![[0]..=[255]]
+        );
+    }
+
+    #[test]
+    fn simplification() {
+        let ranges = [8..10, 0..2, 2..3, 12..12, 20..25, 9..11, 27..31];
+        let merged = merge_ranges(&ranges);
+        assert_eq!(merged, [0..3, 8..11, 20..25, 27..31]);
+        assert_eq!(merge_ranges(&[]), []);
+
+        assert_eq!(simplify_ranges(&ranges, 4), (merged.clone(), 0));
+        assert_eq!(simplify_ranges(&ranges, 3), (vec![0..3, 8..11, 20..31], 2));
+        assert_eq!(simplify_ranges(&ranges, 2), (vec![0..11, 20..31], 7));
+        let (single, extra) = simplify_ranges(&ranges, 1);
+        assert_eq!((single.len(), single[0].clone(), extra), (1, 0..31, 16));
+
+        // the set of cells of a rectangle is simplified like its exact decomposition
+        let n = 16;
+        let exact = ranges_for_rect(3, 2, 9, 12, n, usize::MAX);
+        let (simplified, extra) = simplify_ranges(&exact, 5);
+        assert_eq!(simplified, ranges_for_rect(3, 2, 9, 12, n, 5));
+        let covered: usize = simplified.iter().map(|r| r.len()).sum();
+        assert_eq!(covered - extra, 7 * 11);
+    }
+
+    #[test]
+    fn lazy_ranges() {
+        let n = 32;
+        for (x0, y0, x1, y1) in [(0, 0, 31, 31), (3, 2, 9, 12), (5, 5, 5, 5), (1, 0, 30, 17)] {
+            let lazy: Vec<_> = rect_ranges(x0, y0, x1, y1, n).collect();
+            assert_eq!(lazy, ranges_for_rect(x0, y0, x1, y1, n, usize::MAX));
+        }
+        let mut ranges = rect_ranges(0, 0, 0, 0, 1);
+        assert_eq!((ranges.next(), ranges.next()), (Some(0..1), None));
+    }
+}
@@ -0,0 +1,79 @@
+//! Hilbert curves filling rectangular grids of 2^a by 2^b cells.
+//!
+//! The longer side is split into squares of the shorter side's length, visited one after
+//! another; each of them is filled by a regular Hilbert curve, which starts and ends in the
+//! corners on the side along the longer axis, so the curve of a square is continued by the one
+//! of the following square.
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+/// Convert a one-dimensional distance `d` to a pair of (x, y) coordinates in a grid `w` cells
+/// wide and `h` cells high.
+pub fn convert_1d_to_2d_rect(d: usize, w: usize, h: usize) -> (usize, usize) {
+    assert_sides(w, h);
+    if w == h {
+        return convert_1d_to_2d(d, w);
+    }
+
+    let side = w.min(h);
+    let block = d / (side * side);
+    let (x, y) = convert_1d_to_2d(d % (side * side), side);
+    if w > h {
+        (block * side + x, y)
+    } else {
+        (y, block * side + x)
+    }
+}
+
+/// Convert a pair of (x, y) coordinates in a grid `w` cells wide and `h` cells high to a
+/// one-dimensional distance.
+pub fn convert_2d_to_1d_rect(x: usize, y: usize, w: usize, h: usize) -> usize {
+    assert_sides(w, h);
+    if w == h {
+        return convert_2d_to_1d(x, y, w);
+    }
+
+    let side = w.min(h);
+    // the position along the longer axis and the other coordinate
+    let (along, across) = if w > h { (x, y) } else { (y, x) };
+    let block = along / side;
+    block * side * side + convert_2d_to_1d(along % side, across, side)
+}
+
+fn assert_sides(w: usize, h: usize) {
+    assert!(
+        w.is_power_of_two() && h.is_power_of_two(),
+        "w and h must be powers of 2"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuity() {
+        for &(w, h) in &[(1, 1), (2, 1), (1, 4), (8, 2), (4, 32), (16, 16), (64, 8)] {
+            let mut seen = vec![false; w * h];
+            let mut prev: Option<(usize, usize)> = None;
+            for d in 0..(w * h) {
+                let (x, y) = convert_1d_to_2d_rect(d, w, h);
+                assert!(x < w && y < h);
+                assert_eq!(convert_2d_to_1d_rect(x, y, w, h), d);
+                assert!(!seen[y * w + x]);
+                seen[y * w + x] = true;
+                if let Some((px, py)) = prev {
+                    assert_eq!(px.abs_diff(x) + py.abs_diff(y), 1);
+                }
+                prev = Some((x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn square() {
+        for d in 0..64 {
+            assert_eq!(convert_1d_to_2d_rect(d, 8, 8), convert_1d_to_2d(d, 8));
+        }
+    }
+}
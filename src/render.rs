@@ -0,0 +1,119 @@
+//! Text rendering of the curve, for debugging and documentation.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{convert_1d_to_2d, HilbertCurve};
+
+// the connections of a cell to its neighbors on the curve
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const DOWN: u8 = 4;
+const UP: u8 = 8;
+
+/// Draw the curve of the given order with box-drawing characters, one character per cell and one
+/// line per row, with the row of the highest y first.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn render_ascii(order: u32) -> String {
+    render_ascii_marked(order, &[])
+}
+
+/// Draw the curve like [`render_ascii`], using heavy lines for the marked (x, y) cells.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or a marked cell is outside the
+/// grid.
+pub fn render_ascii_marked(order: u32, marked: &[(usize, usize)]) -> String {
+    let curve = HilbertCurve::new(order);
+    let n = curve.side();
+    assert!(
+        marked.iter().all(|&(x, y)| x < n && y < n),
+        "marked cells must be within the grid"
+    );
+
+    let mut links = alloc::vec![0u8; n * n];
+    let mut prev = None;
+    for (_, x, y) in curve.iter() {
+        if let Some((px, py)) = prev {
+            let (from, to) = match (x as isize - px as isize, y as isize - py as isize) {
+                (1, 0) => (RIGHT, LEFT),
+                (-1, 0) => (LEFT, RIGHT),
+                (0, 1) => (UP, DOWN),
+                _ => (DOWN, UP),
+            };
+            links[py * n + px] |= from;
+            links[y * n + x] |= to;
+        }
+        prev = Some((x, y));
+    }
+
+    let mut heavy = alloc::vec![false; n * n];
+    for &(x, y) in marked {
+        heavy[y * n + x] = true;
+    }
+
+    let mut out = String::with_capacity(n * (3 * n + 1));
+    for y in (0..n).rev() {
+        let row: Vec<char> = (0..n)
+            .map(|x| glyph(links[y * n + x], heavy[y * n + x]))
+            .collect();
+        out.extend(row);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Get the (x, y) coordinates of the cells at the given distances, to be marked with
+/// [`render_ascii_marked`].
+pub fn cells_at(distances: impl IntoIterator<Item = usize>, n: usize) -> Vec<(usize, usize)> {
+    distances
+        .into_iter()
+        .map(|d| convert_1d_to_2d(d, n))
+        .collect()
+}
+
+fn glyph(links: u8, heavy: bool) -> char {
+    let (light, bold) = match links {
+        l if l == LEFT | RIGHT => ('─', '━'),
+        l if l == UP | DOWN => ('│', '┃'),
+        l if l == UP | RIGHT => ('└', '┗'),
+        l if l == UP | LEFT => ('┘', '┛'),
+        l if l == DOWN | RIGHT => ('┌', '┏'),
+        l if l == DOWN | LEFT => ('┐', '┓'),
+        LEFT => ('╴', '╸'),
+        RIGHT => ('╶', '╺'),
+        UP => ('╵', '╹'),
+        DOWN => ('╷', '╻'),
+        _ => ('·', '•'),
+    };
+
+    if heavy {
+        bold
+    } else {
+        light
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendering() {
+        assert_eq!(render_ascii(0), "·\n");
+        assert_eq!(render_ascii(1), "┌┐\n╵╵\n");
+        assert_eq!(render_ascii(2), "┌┐┌┐\n│└┘│\n└┐┌┘\n╶┘└╴\n");
+        assert_eq!(
+            render_ascii_marked(2, &cells_at(0..4, 4)),
+            "┌┐┌┐\n│└┘│\n┗┓┌┘\n╺┛└╴\n"
+        );
+
+        let text = render_ascii(4);
+        assert_eq!(text.lines().count(), 16);
+        assert!(text.lines().all(|line| line.chars().count() == 16));
+    }
+}
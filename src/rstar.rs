@@ -0,0 +1,169 @@
+//! Hilbert ordering of the objects of `rstar` R-trees.
+//!
+//! The order doesn't help build the trees themselves: `rstar` doesn't expose the construction of
+//! its nodes, its bulk loading partitions the objects regardless of their order, and inserting them
+//! one by one in Hilbert order yields more overlapping nodes than inserting them in an arbitrary
+//! order, which its R* splits expect. Packing consecutive runs of the order into leaves and bulk
+//! loading those doesn't pay off either: the queries test more envelopes than in the trees of
+//! either loading, for every run length up to 16. For nodes packed along the curve see
+//! [`pack_rtree`](crate::pack_rtree); the order of [`hilbert_sort_objects`] is meant for
+//! processing the objects with spatial locality, e.g. in batches of nearby objects.
+
+use ::rstar::{Envelope, RTreeObject, AABB};
+
+use crate::{
+    convert_2d_to_1d,
+    quantize::{quantize, Bounds, OutOfRange, Rounding},
+    HilbertCurve,
+};
+
+/// Sort objects by the Hilbert distances of the centers of their envelopes on the curve of the
+/// given order covering the envelope of all of them.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn hilbert_sort_objects<T>(objects: &mut [T], order: u32)
+where
+    T: RTreeObject<Envelope = AABB<[f64; 2]>>,
+{
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "order must not exceed HilbertCurve::MAX_ORDER"
+    );
+    if objects.len() < 2 {
+        return;
+    }
+
+    let n = 1 << order;
+    let envelope = objects
+        .iter()
+        .fold(AABB::new_empty(), |envelope: AABB<[f64; 2]>, object| {
+            envelope.merged(&object.envelope())
+        });
+    let ([min_x, min_y], [max_x, max_y]) = (envelope.lower(), envelope.upper());
+    // a degenerate extent is widened, since all the centers fall in the same cell anyway
+    let bounds = Bounds::new(
+        min_x,
+        min_y,
+        if max_x > min_x { max_x } else { min_x + 1.0 },
+        if max_y > min_y { max_y } else { min_y + 1.0 },
+    );
+    objects.sort_by_cached_key(|object| {
+        let [x, y] = object.envelope().center();
+        quantize(x, y, &bounds, n, Rounding::Floor, OutOfRange::Clamp)
+            .map_or(0, |(x, y)| convert_2d_to_1d(x, y, n))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rstar::{
+        primitives::Rectangle, DefaultParams, ParentNode, RTree, RTreeNode, RTreeParams,
+    };
+
+    // The number of envelopes tested by a query of the envelope, counting `run` tests for every
+    // intersecting object
+    fn tested<T: RTreeObject<Envelope = AABB<[f64; 2]>>>(
+        node: &ParentNode<T>,
+        query: &AABB<[f64; 2]>,
+        run: usize,
+    ) -> usize {
+        node.children()
+            .iter()
+            .map(|child| {
+                1 + match child {
+                    RTreeNode::Parent(parent) if parent.envelope().intersects(query) => {
+                        tested(parent, query, run)
+                    }
+                    RTreeNode::Leaf(object) if object.envelope().intersects(query) => run,
+                    _ => 0,
+                }
+            })
+            .sum::<usize>()
+    }
+
+    #[test]
+    fn loading() {
+        let rects: Vec<_> = (0..200)
+            .map(|i| {
+                let (x, y) = ((i * 37 % 100) as f64, (i * 53 % 100) as f64);
+                Rectangle::from_corners([x, y], [x + 1.5, y + 0.5])
+            })
+            .collect();
+
+        let mut sorted = rects.clone();
+        hilbert_sort_objects(&mut sorted, 10);
+        let steps: f64 = sorted
+            .windows(2)
+            .map(|w| w[0].envelope().distance_2(&w[1].envelope().center()))
+            .sum();
+        let unsorted: f64 = rects
+            .windows(2)
+            .map(|w| w[0].envelope().distance_2(&w[1].envelope().center()))
+            .sum();
+        assert!(steps < unsorted / 10.0);
+
+        let mut same = vec![Rectangle::from_corners([1.0, 1.0], [1.0, 1.0]); 2];
+        hilbert_sort_objects(&mut same, 4);
+        hilbert_sort_objects::<Rectangle<[f64; 2]>>(&mut [], 4);
+    }
+
+    #[test]
+    fn tree_building() {
+        let rects: Vec<_> = (0..2000u64)
+            .map(|i| {
+                let h = i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                let (x, y) = ((h >> 54) as f64, (h >> 44 & 0x3ff) as f64);
+                Rectangle::from_corners([x, y], [x + 2.0, y + 3.0])
+            })
+            .collect();
+        let mut sorted = rects.clone();
+        hilbert_sort_objects(&mut sorted, 16);
+        let queries: Vec<_> = (0..400)
+            .map(|i| {
+                let (x, y) = ((i * 37 % 200) as f64 * 5.0, (i * 91 % 200) as f64 * 5.0);
+                AABB::from_corners([x, y], [x + 20.0, y + 20.0])
+            })
+            .collect();
+        let cost = |tree: &RTree<Rectangle<[f64; 2]>>, run| {
+            queries
+                .iter()
+                .map(|query| tested(tree.root(), query, run))
+                .sum::<usize>()
+        };
+
+        // bulk loading ignores the order
+        let bulk = RTree::bulk_load(rects.clone());
+        assert_eq!(cost(&RTree::bulk_load(sorted.clone()), 0), cost(&bulk, 0));
+
+        // and inserting in Hilbert order doesn't beat inserting in an arbitrary one
+        let insert = |objects: &[Rectangle<[f64; 2]>]| {
+            let mut tree = RTree::new();
+            for object in objects {
+                tree.insert(*object);
+            }
+            tree
+        };
+        let inserted = cost(&insert(&rects), 0);
+        assert!(cost(&insert(&sorted), 0) > inserted);
+
+        // nor do leaves packed from runs of it, whose objects are tested once their run intersects
+        for run in [2, 4, DefaultParams::MAX_SIZE, 8, 16] {
+            let leaves: Vec<_> = sorted
+                .chunks(run)
+                .map(|objects| {
+                    let envelope = objects
+                        .iter()
+                        .fold(AABB::new_empty(), |envelope: AABB<[f64; 2]>, object| {
+                            envelope.merged(&object.envelope())
+                        });
+                    Rectangle::from_aabb(envelope)
+                })
+                .collect();
+            let packed = cost(&RTree::bulk_load(leaves), run);
+            assert!(packed > inserted && packed > cost(&bulk, 0));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Nearest-neighbor searches over sorted slices of distances, which act as a minimal spatial
+//! index.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d, ranges_for_rect};
+
+// The limit on the number of ranges of the query boxes; merged ranges are filtered afterwards
+const MAX_RANGES: usize = 64;
+
+/// Get the range of indices of the `m` keys before and the `m` keys after the position of the cell
+/// (x, y) in the sorted distances `keys` of points of an n by n grid; those points are close to it
+/// along the curve and usually in the grid too.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or the cell is outside the grid.
+pub fn curve_window(keys: &[usize], x: usize, y: usize, n: usize, m: usize) -> Range<usize> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(x < n && y < n, "coordinates must be lower than n");
+    let at = keys.partition_point(|&key| key < convert_2d_to_1d(x, y, n));
+
+    at.saturating_sub(m)..(at + m).min(keys.len())
+}
+
+/// Get the indices of the (up to) `k` keys in the sorted distances `keys` of points of an n by n
+/// grid whose cells are the nearest to the cell (x, y) by Euclidean distance, from the nearest;
+/// ties are broken by index.
+///
+/// The points of the [`curve_window`] of the query bound the distance of its `k`-th nearest
+/// neighbor, so a single square query box of that radius, decomposed into ranges of distances,
+/// is guaranteed to contain all the nearest neighbors.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or the cell is outside the grid.
+pub fn nearest_neighbors(keys: &[usize], x: usize, y: usize, n: usize, k: usize) -> Vec<usize> {
+    debug_assert!(
+        keys.windows(2).all(|w| w[0] <= w[1]),
+        "the keys must be sorted"
+    );
+    let window = curve_window(keys, x, y, n, k);
+    if k == 0 || keys.is_empty() {
+        return Vec::new();
+    }
+
+    // the squared radius containing at least k points, or the whole grid if there are fewer
+    let radius2 = if keys.len() >= k {
+        let mut distances: Vec<u128> = keys[window]
+            .iter()
+            .map(|&key| distance2(key, x, y, n))
+            .collect();
+        distances.sort_unstable();
+        distances[k - 1]
+    } else {
+        u128::MAX
+    };
+    let radius = match radius2.isqrt() {
+        r if r * r < radius2 => r + 1,
+        r => r,
+    }
+    .min(n as u128) as usize;
+
+    let (x0, y0) = (x.saturating_sub(radius), y.saturating_sub(radius));
+    let (x1, y1) = ((x + radius).min(n - 1), (y + radius).min(n - 1));
+    let mut found = Vec::new();
+    for range in ranges_for_rect(x0, y0, x1, y1, n, MAX_RANGES) {
+        let start = keys.partition_point(|&key| key < range.start);
+        let end = keys.partition_point(|&key| key < range.end);
+        found.extend((start..end).filter_map(|i| {
+            let distance = distance2(keys[i], x, y, n);
+            Some((distance, i)).filter(|_| distance <= radius2)
+        }));
+    }
+    found.sort_unstable();
+    found.truncate(k);
+
+    found.into_iter().map(|(_, i)| i).collect()
+}
+
+// The squared Euclidean distance between the cell at distance `d` and the cell (x, y)
+fn distance2(d: usize, x: usize, y: usize, n: usize) -> u128 {
+    let (px, py) = convert_1d_to_2d(d, n);
+    let (dx, dy) = (px.abs_diff(x) as u128, py.abs_diff(y) as u128);
+    dx * dx + dy * dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors() {
+        let n = 64;
+        let mut keys: Vec<_> = (0..500)
+            .map(|i: usize| convert_2d_to_1d((i * 37) % n, (i * 23 + i / 7) % n, n))
+            .collect();
+        keys.sort_unstable();
+
+        for qx in (0..n).step_by(7) {
+            for qy in (0..n).step_by(5) {
+                let found = nearest_neighbors(&keys, qx, qy, n, 5);
+                let mut expected: Vec<_> = (0..keys.len())
+                    .map(|i| (distance2(keys[i], qx, qy, n), i))
+                    .collect();
+                expected.sort_unstable();
+                let expected: Vec<_> = expected[..5].iter().map(|&(_, i)| i).collect();
+                assert_eq!(found, expected);
+            }
+        }
+
+        assert_eq!(nearest_neighbors(&keys[..3], 0, 0, n, 5).len(), 3);
+        assert!(nearest_neighbors(&keys, 0, 0, n, 0).is_empty());
+        assert!(nearest_neighbors(&[], 0, 0, n, 3).is_empty());
+        assert_eq!(curve_window(&keys, 0, 0, n, 2), 0..2);
+    }
+}
@@ -0,0 +1,102 @@
+//! Assignment of cells to shards owning contiguous segments of the curve.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{convert_2d_to_1d, HilbertCurve};
+
+/// Get the shard owning the cell (x, y) when the curve is split into `num_shards` contiguous
+/// segments whose numbers of cells differ by at most one, as returned by [`shard_ranges`].
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`], the cell is outside the grid, or
+/// `num_shards` is not within [1, n^2].
+pub fn shard_for_point(x: usize, y: usize, n: usize, num_shards: usize) -> usize {
+    assert!(x < n && y < n, "coordinates must be lower than n");
+    shard_for_d(convert_2d_to_1d(x, y, n), n, num_shards)
+}
+
+/// Get the shard owning the cell at distance `d`, like [`shard_for_point`].
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`], `d` is not lower than n^2, or
+/// `num_shards` is not within [1, n^2].
+pub fn shard_for_d(d: usize, n: usize, num_shards: usize) -> usize {
+    let len = check(n, num_shards);
+    assert!((d as u128) < len, "d must be lower than n^2");
+    // the last shard whose first distance is not greater than d
+    (((d as u128 + 1) * num_shards as u128 - 1) / len) as usize
+}
+
+/// Get the range of distances owned by the given shard out of `num_shards`.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`], `num_shards` is not within [1, n^2],
+/// or `shard` is not lower than `num_shards`.
+pub fn shard_range(shard: usize, n: usize, num_shards: usize) -> Range<usize> {
+    let len = check(n, num_shards);
+    assert!(shard < num_shards, "shard must be lower than num_shards");
+    boundary(shard, num_shards, len)..boundary(shard + 1, num_shards, len)
+}
+
+/// Get the ranges of distances owned by each of `num_shards` shards, in order.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or `num_shards` is not within [1, n^2].
+#[cfg(feature = "alloc")]
+pub fn shard_ranges(num_shards: usize, n: usize) -> Vec<Range<usize>> {
+    check(n, num_shards);
+    (0..num_shards)
+        .map(|shard| shard_range(shard, n, num_shards))
+        .collect()
+}
+
+// The first distance of the i-th of k equal shares of a curve of the given length
+pub(crate) fn boundary(i: usize, k: usize, len: u128) -> usize {
+    (i as u128 * len / k as u128) as usize
+}
+
+// Check the arguments and return the length of the curve
+fn check(n: usize, k: usize) -> u128 {
+    let curve = HilbertCurve::try_from_side(n).expect("n must be a valid side");
+    let len = curve.len() as u128;
+    assert!(
+        k > 0 && k as u128 <= len,
+        "the number of shards must be within [1, n^2]"
+    );
+    len
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn shards() {
+        let n = 8;
+        for &k in &[1, 3, 7, 10, 64] {
+            let ranges = shard_ranges(k, n);
+            assert_eq!(ranges.len(), k);
+            assert_eq!(ranges[0].start, 0);
+            assert_eq!(ranges[k - 1].end, n * n);
+            for (shard, range) in ranges.iter().enumerate() {
+                assert!(range.len() == 64 / k || range.len() == 64 / k + 1);
+                for d in range.clone() {
+                    let (x, y) = convert_1d_to_2d(d, n);
+                    assert_eq!(shard_for_point(x, y, n, k), shard);
+                }
+            }
+        }
+
+        let curve = HilbertCurve::new(HilbertCurve::MAX_ORDER);
+        let n = curve.side();
+        assert_eq!(shard_range(2, n, 3), 2 * (curve.len() / 3)..curve.len());
+        assert_eq!(shard_for_d(curve.len() - 1, n, 3), 2);
+    }
+}
@@ -0,0 +1,178 @@
+//! The Sierpiński curve, which visits the triangles of a triangulated grid.
+//!
+//! The n by n grid (n being a power of 2) is split along its diagonal from (0, 0) to (n, n) into
+//! two right triangles, and every triangle is recursively bisected from its right angle to the
+//! middle of its hypotenuse, until each one is half of a cell. The resulting 2n^2 triangles are
+//! visited so that consecutive ones share an edge, and the last one shares an edge with the first
+//! one.
+
+use crate::Corner;
+
+/// A triangle covering half of the cell (x, y), with its right angle at the given corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle {
+    pub x: usize,
+    pub y: usize,
+    pub corner: Corner,
+}
+
+// A triangle traversed from the vertex `r + a` to the vertex `r + b`, with the right angle at `r`
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    r: (isize, isize),
+    a: (isize, isize),
+    b: (isize, isize),
+}
+
+impl Span {
+    // The two halves of the triangle in curve order
+    fn bisect(&self) -> [Span; 2] {
+        let Span { r, a, b } = *self;
+        let m = (r.0 + (a.0 + b.0) / 2, r.1 + (a.1 + b.1) / 2);
+        let to_r = (-(a.0 + b.0) / 2, -(a.1 + b.1) / 2);
+
+        [
+            Span {
+                r: m,
+                a: ((a.0 - b.0) / 2, (a.1 - b.1) / 2),
+                b: to_r,
+            },
+            Span {
+                r: m,
+                a: to_r,
+                b: ((b.0 - a.0) / 2, (b.1 - a.1) / 2),
+            },
+        ]
+    }
+}
+
+/// Convert a one-dimensional distance `d` lower than 2n^2 to the triangle it represents.
+pub fn convert_1d_to_triangle(d: usize, n: usize) -> Triangle {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(d < 2 * n * n, "d must be lower than 2 * n^2");
+    let mut span = halves(n)[d / (n * n)];
+
+    for level in (0..2 * n.trailing_zeros()).rev() {
+        span = span.bisect()[(d >> level) & 1];
+    }
+
+    let Span { r, a, b } = span;
+    let (x, y) = (r.0.min(r.0 + a.0 + b.0), r.1.min(r.1 + a.1 + b.1));
+    let corner = match (r.0 == x, r.1 == y) {
+        (true, true) => Corner::LowerLeft,
+        (false, true) => Corner::LowerRight,
+        (true, false) => Corner::UpperLeft,
+        (false, false) => Corner::UpperRight,
+    };
+
+    Triangle {
+        x: x as usize,
+        y: y as usize,
+        corner,
+    }
+}
+
+/// Convert a triangle to a one-dimensional distance.
+pub fn convert_triangle_to_1d(triangle: Triangle, n: usize) -> usize {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    assert!(
+        triangle.x < n && triangle.y < n,
+        "coordinates must be within the grid"
+    );
+
+    // a point inside the triangle, in thirds of a cell
+    let (x, y) = (3 * triangle.x as isize, 3 * triangle.y as isize);
+    let p = match triangle.corner {
+        Corner::LowerLeft => (x + 1, y + 1),
+        Corner::LowerRight => (x + 2, y + 1),
+        Corner::UpperLeft => (x + 1, y + 2),
+        Corner::UpperRight => (x + 2, y + 2),
+    };
+    let half = usize::from(p.1 > p.0);
+    let mut span = halves(n)[half];
+    let mut d = half;
+
+    for _ in 0..2 * n.trailing_zeros() {
+        let [first, second] = span.bisect();
+        // the halves are separated by the line from the right angle to the middle of the
+        // hypotenuse, and the first one contains the vertex `r + a`
+        let (mx, my) = (first.r.0 - span.r.0, first.r.1 - span.r.1);
+        let (px, py) = (p.0 - 3 * span.r.0, p.1 - 3 * span.r.1);
+        let side = mx * py - my * px;
+        let a_side = mx * span.a.1 - my * span.a.0;
+        let (bit, next) = if (side > 0) == (a_side > 0) {
+            (0, first)
+        } else {
+            (1, second)
+        };
+        d = 2 * d + bit;
+        span = next;
+    }
+
+    d
+}
+
+// The two halves of the grid, below and above its diagonal
+fn halves(n: usize) -> [Span; 2] {
+    let n = n as isize;
+    [
+        Span {
+            r: (n, 0),
+            a: (-n, 0),
+            b: (0, n),
+        },
+        Span {
+            r: (0, n),
+            a: (n, 0),
+            b: (0, -n),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the vertices of a triangle
+    fn vertices(t: Triangle) -> [(usize, usize); 3] {
+        let (x, y) = (t.x, t.y);
+        let corners = [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)];
+        let skip = match t.corner {
+            Corner::LowerLeft => 3,
+            Corner::LowerRight => 2,
+            Corner::UpperLeft => 1,
+            Corner::UpperRight => 0,
+        };
+        let mut vertices = [(0, 0); 3];
+        let others = corners.iter().enumerate().filter(|&(i, _)| i != skip);
+        for (v, (_, &c)) in vertices.iter_mut().zip(others) {
+            *v = c;
+        }
+        vertices
+    }
+
+    #[test]
+    fn reversibility() {
+        for &n in &[1, 2, 4, 8, 16, 32] {
+            for d in 0..(2 * n * n) {
+                let triangle = convert_1d_to_triangle(d, n);
+                assert!(triangle.x < n && triangle.y < n);
+                assert_eq!(convert_triangle_to_1d(triangle, n), d);
+            }
+        }
+    }
+
+    #[test]
+    fn shared_edges() {
+        for &n in &[1, 2, 4, 8, 16] {
+            let len = 2 * n * n;
+            for d in 0..len {
+                let curr = vertices(convert_1d_to_triangle(d, n));
+                let next = vertices(convert_1d_to_triangle((d + 1) % len, n));
+                let shared = curr.iter().filter(|v| next.contains(v)).count();
+                assert_eq!(shared, 2);
+            }
+        }
+    }
+}
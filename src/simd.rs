@@ -0,0 +1,303 @@
+//! Vectorized batch conversions.
+//!
+//! The distances or coordinates are processed in groups of [`LANES`] with a branchless variant of
+//! the conversion loop. On x86_64 with the `std` feature, kernels written with AVX2 intrinsics,
+//! holding 4 lanes per register, are selected at runtime if the CPU supports them; elsewhere the
+//! portable loop is used.
+
+use alloc::vec::Vec;
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+/// The number of values converted at once.
+pub const LANES: usize = 8;
+
+/// Convert a slice of one-dimensional distances to their (x, y) coordinates.
+pub fn convert_1d_to_2d_batch(ds: &[usize], n: usize) -> Vec<(usize, usize)> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let mut out = Vec::with_capacity(ds.len());
+
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports AVX2
+            unsafe { extend_1d_to_2d_avx2(&mut out, ds, n) };
+            return out;
+        }
+    }
+    extend_1d_to_2d(&mut out, ds, n);
+
+    out
+}
+
+/// Convert a slice of (x, y) coordinates to their one-dimensional distances.
+pub fn convert_2d_to_1d_batch(points: &[(usize, usize)], n: usize) -> Vec<usize> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let mut out = Vec::with_capacity(points.len());
+
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports AVX2
+            unsafe { extend_2d_to_1d_avx2(&mut out, points, n) };
+            return out;
+        }
+    }
+    extend_2d_to_1d(&mut out, points, n);
+
+    out
+}
+
+// The conversions of whole slices, in groups of lanes followed by the scalar conversions of the
+// remainder; the AVX2 variants are compiled with the feature enabled, so that their kernels are
+// inlined into the loops
+fn extend_1d_to_2d(out: &mut Vec<(usize, usize)>, ds: &[usize], n: usize) {
+    let mut chunks = ds.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        let (xs, ys) = kernel_1d_to_2d(to_lanes(chunk), n);
+        out.extend(xs.iter().copied().zip(ys.iter().copied()));
+    }
+    out.extend(chunks.remainder().iter().map(|&d| convert_1d_to_2d(d, n)));
+}
+
+fn extend_2d_to_1d(out: &mut Vec<usize>, points: &[(usize, usize)], n: usize) {
+    let mut chunks = points.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        let (xs, ys) = split_lanes(chunk);
+        out.extend_from_slice(&kernel_2d_to_1d(xs, ys, n));
+    }
+    out.extend(
+        chunks
+            .remainder()
+            .iter()
+            .map(|&(x, y)| convert_2d_to_1d(x, y, n)),
+    );
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "avx2")]
+unsafe fn extend_1d_to_2d_avx2(out: &mut Vec<(usize, usize)>, ds: &[usize], n: usize) {
+    let mut chunks = ds.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        let (xs, ys) = lanes_1d_to_2d_avx2(to_lanes(chunk), n);
+        out.extend(xs.iter().copied().zip(ys.iter().copied()));
+    }
+    out.extend(chunks.remainder().iter().map(|&d| convert_1d_to_2d(d, n)));
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "avx2")]
+unsafe fn extend_2d_to_1d_avx2(out: &mut Vec<usize>, points: &[(usize, usize)], n: usize) {
+    let mut chunks = points.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        let (xs, ys) = split_lanes(chunk);
+        out.extend_from_slice(&lanes_2d_to_1d_avx2(xs, ys, n));
+    }
+    out.extend(
+        chunks
+            .remainder()
+            .iter()
+            .map(|&(x, y)| convert_2d_to_1d(x, y, n)),
+    );
+}
+
+#[inline(always)]
+fn to_lanes(chunk: &[usize]) -> [usize; LANES] {
+    let mut lanes = [0; LANES];
+    lanes.copy_from_slice(chunk);
+    lanes
+}
+
+#[inline(always)]
+fn split_lanes(chunk: &[(usize, usize)]) -> ([usize; LANES], [usize; LANES]) {
+    let (mut xs, mut ys) = ([0; LANES], [0; LANES]);
+    for (i, &(x, y)) in chunk.iter().enumerate() {
+        xs[i] = x;
+        ys[i] = y;
+    }
+    (xs, ys)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn lanes_1d_to_2d_avx2(ds: [usize; LANES], n: usize) -> ([usize; LANES], [usize; LANES]) {
+    use core::arch::x86_64::*;
+
+    let (mut xs, mut ys) = ([0; LANES], [0; LANES]);
+    for half in 0..LANES / 4 {
+        let one = _mm256_set1_epi64x(1);
+        let mut t = _mm256_loadu_si256(ds.as_ptr().add(4 * half) as *const __m256i);
+        let (mut x, mut y) = (_mm256_setzero_si256(), _mm256_setzero_si256());
+        let mut s = 1;
+
+        while s < n {
+            let low = _mm256_set1_epi64x(s as i64 - 1);
+            let rx = _mm256_and_si256(_mm256_srli_epi64::<1>(t), one);
+            let ry = _mm256_and_si256(_mm256_xor_si256(t, rx), one);
+            let not_ry = _mm256_xor_si256(ry, one);
+            // the masks are all ones in the lanes where the condition bit is set
+            let flip = _mm256_and_si256(
+                _mm256_sub_epi64(_mm256_setzero_si256(), _mm256_and_si256(rx, not_ry)),
+                low,
+            );
+            let (fx, fy) = (_mm256_xor_si256(x, flip), _mm256_xor_si256(y, flip));
+            let swap = _mm256_and_si256(
+                _mm256_xor_si256(fx, fy),
+                _mm256_sub_epi64(_mm256_setzero_si256(), not_ry),
+            );
+            let step = _mm256_set1_epi64x(s as i64);
+            x = _mm256_or_si256(
+                _mm256_xor_si256(fx, swap),
+                _mm256_and_si256(step, _mm256_sub_epi64(_mm256_setzero_si256(), rx)),
+            );
+            y = _mm256_or_si256(
+                _mm256_xor_si256(fy, swap),
+                _mm256_and_si256(step, _mm256_sub_epi64(_mm256_setzero_si256(), ry)),
+            );
+            t = _mm256_srli_epi64::<2>(t);
+            s <<= 1;
+        }
+
+        _mm256_storeu_si256(xs.as_mut_ptr().add(4 * half) as *mut __m256i, x);
+        _mm256_storeu_si256(ys.as_mut_ptr().add(4 * half) as *mut __m256i, y);
+    }
+
+    (xs, ys)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn lanes_2d_to_1d_avx2(xs: [usize; LANES], ys: [usize; LANES], n: usize) -> [usize; LANES] {
+    use core::arch::x86_64::*;
+
+    let mut ds = [0; LANES];
+    for half in 0..LANES / 4 {
+        let one = _mm256_set1_epi64x(1);
+        let mut x = _mm256_loadu_si256(xs.as_ptr().add(4 * half) as *const __m256i);
+        let mut y = _mm256_loadu_si256(ys.as_ptr().add(4 * half) as *const __m256i);
+        let mut d = _mm256_setzero_si256();
+        let mut s = n / 2;
+
+        while s > 0 {
+            let shift = _mm_set_epi64x(0, s.trailing_zeros() as i64);
+            let low = _mm256_set1_epi64x(s as i64 - 1);
+            let rx = _mm256_and_si256(_mm256_srl_epi64(x, shift), one);
+            let ry = _mm256_and_si256(_mm256_srl_epi64(y, shift), one);
+            let not_ry = _mm256_xor_si256(ry, one);
+            // the digit (3 * rx) ^ ry lands at bit 2 * shift of the distance
+            let digit = _mm256_xor_si256(_mm256_or_si256(rx, _mm256_slli_epi64::<1>(rx)), ry);
+            d = _mm256_or_si256(d, _mm256_sll_epi64(digit, _mm_add_epi64(shift, shift)));
+            let flip = _mm256_and_si256(
+                _mm256_sub_epi64(_mm256_setzero_si256(), _mm256_and_si256(rx, not_ry)),
+                low,
+            );
+            let fx = _mm256_xor_si256(_mm256_and_si256(x, low), flip);
+            let fy = _mm256_xor_si256(_mm256_and_si256(y, low), flip);
+            let swap = _mm256_and_si256(
+                _mm256_xor_si256(fx, fy),
+                _mm256_sub_epi64(_mm256_setzero_si256(), not_ry),
+            );
+            x = _mm256_xor_si256(fx, swap);
+            y = _mm256_xor_si256(fy, swap);
+            s /= 2;
+        }
+
+        _mm256_storeu_si256(ds.as_mut_ptr().add(4 * half) as *mut __m256i, d);
+    }
+
+    ds
+}
+
+// A branchless `convert_1d_to_2d` over all the lanes, used where there are no vector kernels;
+// since the coordinates are always lower than `s`, the quadrant flip is an XOR with `s - 1` and
+// the quadrant offset is an OR with `s`
+#[inline(always)]
+fn kernel_1d_to_2d(ds: [usize; LANES], n: usize) -> ([usize; LANES], [usize; LANES]) {
+    let (mut xs, mut ys) = ([0; LANES], [0; LANES]);
+    let mut ts = ds;
+    let mut s = 1;
+
+    while s < n {
+        for i in 0..LANES {
+            let rx = 1 & (ts[i] >> 1);
+            let ry = 1 & (ts[i] ^ rx);
+            let flip = 0usize.wrapping_sub(rx & (ry ^ 1)) & (s - 1);
+            let (x, y) = (xs[i] ^ flip, ys[i] ^ flip);
+            let swap = (x ^ y) & 0usize.wrapping_sub(ry ^ 1);
+            xs[i] = (x ^ swap) | (s & 0usize.wrapping_sub(rx));
+            ys[i] = (y ^ swap) | (s & 0usize.wrapping_sub(ry));
+            ts[i] >>= 2;
+        }
+        s <<= 1;
+    }
+
+    (xs, ys)
+}
+
+// A branchless `convert_2d_to_1d` over all the lanes; the bits at and above `s` are dropped once
+// they are accounted for, so that the quadrant flip can be an XOR with `s - 1`
+#[inline(always)]
+fn kernel_2d_to_1d(xs: [usize; LANES], ys: [usize; LANES], n: usize) -> [usize; LANES] {
+    let mut ds = [0; LANES];
+    let (mut xs, mut ys) = (xs, ys);
+    let mut s = n / 2;
+
+    while s > 0 {
+        let shift = s.trailing_zeros();
+        for i in 0..LANES {
+            let rx = (xs[i] >> shift) & 1;
+            let ry = (ys[i] >> shift) & 1;
+            ds[i] += (s * s) * ((3 * rx) ^ ry);
+            let flip = 0usize.wrapping_sub(rx & (ry ^ 1)) & (s - 1);
+            let (x, y) = ((xs[i] & (s - 1)) ^ flip, (ys[i] & (s - 1)) ^ flip);
+            let swap = (x ^ y) & 0usize.wrapping_sub(ry ^ 1);
+            xs[i] = x ^ swap;
+            ys[i] = y ^ swap;
+        }
+        s /= 2;
+    }
+
+    ds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_scalar() {
+        for &n in &[1, 2, 4, 64, 1 << 16] {
+            let ds: Vec<usize> = (0..1000).map(|i| (i * 7919) % (n * n)).collect();
+            let points = convert_1d_to_2d_batch(&ds, n);
+            assert_eq!(points, crate::convert_1d_to_2d_batch(&ds, n));
+            assert_eq!(convert_2d_to_1d_batch(&points, n), ds);
+        }
+    }
+
+    #[test]
+    fn kernels() {
+        // every kernel matches the scalar conversions, including at the highest order
+        for order in [0, 1, 7, 16, crate::HilbertCurve::MAX_ORDER] {
+            let n = 1usize << order;
+            let ds: [usize; LANES] = core::array::from_fn(|i| {
+                ((i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) % (n * n) as u64) as usize
+            });
+            let (xs, ys) = kernel_1d_to_2d(ds, n);
+            for i in 0..LANES {
+                assert_eq!((xs[i], ys[i]), convert_1d_to_2d(ds[i], n));
+            }
+            assert_eq!(kernel_2d_to_1d(xs, ys, n), ds);
+
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            if is_x86_feature_detected!("avx2") {
+                // SAFETY: the CPU supports AVX2
+                unsafe {
+                    assert_eq!(lanes_1d_to_2d_avx2(ds, n), (xs, ys));
+                    assert_eq!(lanes_2d_to_1d_avx2(xs, ys, n), ds);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Ordering of points along the curve.
+
+use alloc::vec::Vec;
+
+use crate::{convert_2d_to_1d, HilbertIndexable};
+
+/// Sort the points in place by their distances along the curve, computing every distance once.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or any point is outside the grid.
+pub fn hilbert_sort<T: HilbertIndexable>(points: &mut [T], n: usize) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    points.sort_by_cached_key(|point| {
+        let (x, y) = point.cell(n);
+        key(x, y, n)
+    });
+}
+
+/// Sort the items in place by the distances along the curve of the points they are mapped to by
+/// `f`, computing every distance once.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or any point is outside the grid.
+pub fn sort_by_hilbert_key<T>(items: &mut [T], n: usize, f: impl Fn(&T) -> (usize, usize)) {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    items.sort_by_cached_key(|item| {
+        let (x, y) = f(item);
+        key(x, y, n)
+    });
+}
+
+/// Get the permutation ordering the points along the curve, without moving them: the point at
+/// `points[order[i]]` is the `i`-th one in curve order, with ties kept in their original order.
+///
+/// # Panics
+///
+/// Panics if `n` is not a power of 2 or any point is outside the grid.
+pub fn hilbert_argsort<T: HilbertIndexable>(points: &[T], n: usize) -> Vec<usize> {
+    assert!((n & (n - 1)) == 0, "n must be a power of 2");
+    let keys: Vec<usize> = points
+        .iter()
+        .map(|point| {
+            let (x, y) = point.cell(n);
+            key(x, y, n)
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by_key(|&i| keys[i]);
+
+    order
+}
+
+fn key(x: usize, y: usize, n: usize) -> usize {
+    assert!(x < n && y < n, "points must be within the grid");
+    convert_2d_to_1d(x, y, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn sorting() {
+        let n = 16;
+        let mut points: Vec<_> = (0..n * n).rev().map(|d| convert_1d_to_2d(d, n)).collect();
+        hilbert_sort(&mut points, n);
+        for (d, &(x, y)) in points.iter().enumerate() {
+            assert_eq!(convert_2d_to_1d(x, y, n), d);
+        }
+    }
+
+    #[test]
+    fn sorting_by_key() {
+        #[derive(Debug, PartialEq)]
+        struct Particle {
+            position: (usize, usize),
+            mass: u32,
+        }
+
+        let n = 8;
+        let mut particles: Vec<_> = (0..n * n)
+            .map(|d| Particle {
+                position: convert_1d_to_2d((d * 37) % (n * n), n),
+                mass: d as u32,
+            })
+            .collect();
+        sort_by_hilbert_key(&mut particles, n, |p| p.position);
+        for (d, particle) in particles.iter().enumerate() {
+            assert_eq!(particle.position, convert_1d_to_2d(d, n));
+            assert_eq!((particle.mass as usize * 37) % (n * n), d);
+        }
+    }
+
+    #[test]
+    fn indexable() {
+        struct Tree {
+            row: usize,
+            col: usize,
+        }
+
+        impl HilbertIndexable for Tree {
+            fn cell(&self, n: usize) -> (usize, usize) {
+                (self.col % n, self.row % n)
+            }
+        }
+
+        let n = 4;
+        let mut trees: Vec<_> = (0..n * n)
+            .map(|d| {
+                let (x, y) = convert_1d_to_2d(n * n - 1 - d, n);
+                Tree { row: y + n, col: x }
+            })
+            .collect();
+        assert_eq!(hilbert_argsort(&trees, n)[0], n * n - 1);
+        hilbert_sort(&mut trees, n);
+        for (d, tree) in trees.iter().enumerate() {
+            assert_eq!(convert_2d_to_1d(tree.col, tree.row - n, n), d);
+        }
+    }
+
+    #[test]
+    fn argsorting() {
+        let n = 4;
+        let points = [(3, 0), (0, 0), (1, 1), (0, 0), (0, 3)];
+        let order = hilbert_argsort(&points, n);
+        assert_eq!(order, [1, 3, 2, 4, 0]);
+
+        let mut sorted = points;
+        hilbert_sort(&mut sorted, n);
+        let permuted: Vec<_> = order.iter().map(|&i| points[i]).collect();
+        assert_eq!(permuted, sorted);
+    }
+}
@@ -0,0 +1,92 @@
+//! A common interface for the curves provided by the crate.
+
+use crate::{AdaptiveHilbert, GridPoint, HilbertCurve, LutHilbert};
+
+/// A curve visiting every cell of a grid exactly once.
+///
+/// Code generic over this trait can switch between orderings through a type parameter or a trait
+/// object; the curve-specific inherent methods with the same names take precedence when the type
+/// of the curve is known.
+#[allow(clippy::len_without_is_empty)]
+pub trait SpaceFillingCurve {
+    /// The number of cells visited by the curve.
+    fn len(&self) -> usize;
+
+    /// Convert a one-dimensional distance lower than [`SpaceFillingCurve::len`] to the cell it
+    /// corresponds to.
+    fn index_to_point(&self, index: usize) -> GridPoint;
+
+    /// Convert a cell of the grid to its one-dimensional distance.
+    fn point_to_index(&self, point: GridPoint) -> usize;
+}
+
+impl SpaceFillingCurve for HilbertCurve {
+    fn len(&self) -> usize {
+        HilbertCurve::len(self)
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        self.d_to_xy(index).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        self.xy_to_d(point.x, point.y)
+    }
+}
+
+impl SpaceFillingCurve for LutHilbert {
+    fn len(&self) -> usize {
+        LutHilbert::len(self)
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        self.d_to_xy(index).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        self.xy_to_d(point.x, point.y)
+    }
+}
+
+impl SpaceFillingCurve for AdaptiveHilbert {
+    fn len(&self) -> usize {
+        AdaptiveHilbert::len(self)
+    }
+
+    fn index_to_point(&self, index: usize) -> GridPoint {
+        self.d_to_xy(index).into()
+    }
+
+    fn point_to_index(&self, point: GridPoint) -> usize {
+        self.xy_to_d(point.x, point.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gray::GrayCurve, moore::MooreCurve, morton::MortonCurve, peano::PeanoCurve};
+
+    fn assert_bijection(curve: &dyn SpaceFillingCurve) {
+        for d in 0..curve.len() {
+            assert_eq!(curve.point_to_index(curve.index_to_point(d)), d);
+        }
+    }
+
+    #[test]
+    fn trait_objects() {
+        let curves: [&dyn SpaceFillingCurve; 6] = [
+            &HilbertCurve::new(3),
+            &LutHilbert::new(3),
+            &MortonCurve::new(3),
+            &MooreCurve::new(3),
+            &GrayCurve::new(3),
+            &PeanoCurve::new(2),
+        ];
+        for curve in curves.iter() {
+            assert_bijection(*curve);
+        }
+        assert_eq!(curves[5].len(), 81);
+        assert_eq!(curves[2].index_to_point(3), GridPoint::new(1, 1));
+    }
+}
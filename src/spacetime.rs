@@ -0,0 +1,197 @@
+//! Composite keys combining the distance of a cell along the curve with a time bucket.
+//!
+//! A key is a single integer made of the bits of the time bucket and of the distance, so that
+//! sorting keys sorts the entries by time and then along the curve, or along the curve and then by
+//! time; the layout is fixed by a [`SpaceTimeEncoder`], which checks the ranges of its inputs.
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d, HilbertCurve};
+
+/// The component occupying the most significant bits of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyOrdering {
+    /// Keys are ordered by time bucket first, so every bucket is a contiguous range of keys.
+    #[default]
+    TimeMajor,
+    /// Keys are ordered by distance first, so the history of every cell is a contiguous range of
+    /// keys.
+    SpaceMajor,
+}
+
+/// An encoder of keys made of the `2 * order` bits of a distance along the curve of the given
+/// order and `time_bits` bits of a time bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpaceTimeEncoder {
+    order: u32,
+    time_bits: u32,
+    ordering: KeyOrdering,
+}
+
+impl SpaceTimeEncoder {
+    /// Create an encoder for the given curve order, number of bits of the time buckets and
+    /// ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`] or the keys would take more
+    /// than 128 bits.
+    pub fn new(order: u32, time_bits: u32, ordering: KeyOrdering) -> Self {
+        assert!(
+            order <= HilbertCurve::MAX_ORDER,
+            "order must not exceed HilbertCurve::MAX_ORDER"
+        );
+        assert!(
+            2 * order + time_bits <= 128,
+            "the keys must take at most 128 bits"
+        );
+
+        SpaceTimeEncoder {
+            order,
+            time_bits,
+            ordering,
+        }
+    }
+
+    /// The order of the curve.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// The number of bits of the time buckets.
+    pub fn time_bits(&self) -> u32 {
+        self.time_bits
+    }
+
+    /// The ordering of the keys.
+    pub fn ordering(&self) -> KeyOrdering {
+        self.ordering
+    }
+
+    /// The number of bits taken by the keys.
+    pub fn bits(&self) -> u32 {
+        2 * self.order + self.time_bits
+    }
+
+    /// Encode the key of the cell (x, y) in the given time bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate is not lower than `2^order` or `bucket` doesn't fit in
+    /// `time_bits` bits.
+    pub fn encode(&self, x: usize, y: usize, bucket: u64) -> u128 {
+        let n = 1 << self.order;
+        assert!(x < n && y < n, "coordinates must be lower than 2^order");
+        self.encode_distance(convert_2d_to_1d(x, y, n), bucket)
+    }
+
+    /// Encode the key of the cell at distance `d` in the given time bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is not lower than `4^order` or `bucket` doesn't fit in `time_bits` bits.
+    pub fn encode_distance(&self, d: usize, bucket: u64) -> u128 {
+        let space_bits = 2 * self.order;
+        assert!(
+            (d as u128) >> space_bits == 0,
+            "d must be lower than 4^order"
+        );
+        assert!(
+            (bucket as u128) >> self.time_bits == 0,
+            "the bucket must fit in time_bits bits"
+        );
+
+        match self.ordering {
+            KeyOrdering::TimeMajor => (bucket as u128) << space_bits | d as u128,
+            KeyOrdering::SpaceMajor => (d as u128) << self.time_bits | bucket as u128,
+        }
+    }
+
+    /// Encode a key like [`SpaceTimeEncoder::encode`], as a `u64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys take more than 64 bits, or under the conditions of
+    /// [`SpaceTimeEncoder::encode`].
+    pub fn encode_u64(&self, x: usize, y: usize, bucket: u64) -> u64 {
+        assert!(self.bits() <= 64, "the keys must take at most 64 bits");
+        self.encode(x, y, bucket) as u64
+    }
+
+    /// Decode a key into the distance of its cell and its time bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key doesn't fit in [`SpaceTimeEncoder::bits`] bits.
+    pub fn decode_distance(&self, key: u128) -> (usize, u64) {
+        assert!(
+            self.bits() == 128 || key >> self.bits() == 0,
+            "the key must fit in the bits of the encoder"
+        );
+        let space_bits = 2 * self.order;
+
+        match self.ordering {
+            KeyOrdering::TimeMajor => (
+                (key & mask(space_bits)) as usize,
+                (key >> space_bits) as u64,
+            ),
+            KeyOrdering::SpaceMajor => (
+                (key >> self.time_bits) as usize,
+                (key & mask(self.time_bits)) as u64,
+            ),
+        }
+    }
+
+    /// Decode a key into the coordinates of its cell and its time bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key doesn't fit in [`SpaceTimeEncoder::bits`] bits.
+    pub fn decode(&self, key: u128) -> (usize, usize, u64) {
+        let (d, bucket) = self.decode_distance(key);
+        let (x, y) = convert_1d_to_2d(d, 1 << self.order);
+        (x, y, bucket)
+    }
+}
+
+// A mask with the lowest `bits` bits set
+fn mask(bits: u32) -> u128 {
+    if bits >= 128 {
+        u128::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts() {
+        let time_major = SpaceTimeEncoder::new(2, 8, KeyOrdering::TimeMajor);
+        let space_major = SpaceTimeEncoder::new(2, 8, KeyOrdering::SpaceMajor);
+        assert_eq!(time_major.bits(), 12);
+        // the cell (0, 3) is at distance 5 on the curve of order 2
+        assert_eq!(time_major.encode(0, 3, 0xab), 0xab5);
+        assert_eq!(space_major.encode(0, 3, 0xab), 0x5ab);
+        assert_eq!(time_major.encode_u64(0, 3, 0xab), 0xab5);
+        for encoder in [time_major, space_major] {
+            for d in 0..16 {
+                for bucket in [0, 1, 200, 255] {
+                    let key = encoder.encode_distance(d, bucket);
+                    assert_eq!(encoder.decode_distance(key), (d, bucket));
+                    let (x, y) = convert_1d_to_2d(d, 4);
+                    assert_eq!(encoder.decode(key), (x, y, bucket));
+                }
+            }
+        }
+        // a time-major key sorts by bucket first and a space-major one by distance first
+        assert!(time_major.encode_distance(15, 1) < time_major.encode_distance(0, 2));
+        assert!(space_major.encode_distance(1, 255) < space_major.encode_distance(2, 0));
+
+        let wide = SpaceTimeEncoder::new(31, 66, KeyOrdering::TimeMajor);
+        let key = wide.encode_distance(usize::MAX >> 2, u64::MAX);
+        assert_eq!(wide.decode_distance(key), (usize::MAX >> 2, u64::MAX));
+    }
+}
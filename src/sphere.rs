@@ -0,0 +1,241 @@
+//! Mapping of latitudes and longitudes onto the six faces of a cube, each one filled by a
+//! Hilbert curve, as in the S2 geometry library.
+//!
+//! A position is projected from the center of the sphere onto the face of the enclosing cube it
+//! faces, and a quadratic transform of the face coordinates makes the areas of the cells more
+//! uniform; faces 0 to 5 face the +x, +y, +z, −x, −y and −z axes, where +z is the north pole and
+//! +x the intersection of the equator and the prime meridian.
+//!
+//! The curves of the odd faces are transposed, so that the curve of every face ends next to the
+//! start of the curve of the following one and the faces form a single curve over the whole
+//! sphere: the global index of a cell is its face times 4^order plus its distance on the face.
+
+use core::f64::consts::PI;
+
+use crate::{convert_1d_to_2d_u64, convert_2d_to_1d_u64};
+
+/// The highest order of the curves of the faces, the one of the leaf cells of S2.
+pub const MAX_ORDER: u32 = 30;
+
+/// Get the face of the cube and the (i, j) coordinates on the face with a side of `2^order`
+/// cells of the cell containing the given latitude and longitude in degrees; returns `None` if
+/// the position is not finite or the latitude is not within `-90.0..=90.0`.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`MAX_ORDER`].
+pub fn lat_lon_to_face_ij(lat: f64, lon: f64, order: u32) -> Option<(u8, u32, u32)> {
+    let n = side(order);
+    if !lat.is_finite() || !lon.is_finite() || !(-90.0..=90.0).contains(&lat) {
+        return None;
+    }
+
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    let (x, y, z) = (lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin());
+    let (face, u, v) = xyz_to_face_uv(x, y, z);
+    let cell = |t: f64| ((uv_to_st(t) * n as f64) as u64).min(n - 1) as u32;
+
+    Some((face, cell(u), cell(v)))
+}
+
+/// Get the latitude and longitude in degrees of the center of the cell (i, j) of the given face
+/// of the cube, with a side of `2^order` cells.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`MAX_ORDER`], `face` is greater than 5 or the coordinates
+/// are not lower than `2^order`.
+pub fn face_ij_to_lat_lon(face: u8, i: u32, j: u32, order: u32) -> (f64, f64) {
+    let n = side(order);
+    assert!(face < 6, "face must be lower than 6");
+    assert!(
+        u64::from(i) < n && u64::from(j) < n,
+        "coordinates must be lower than 2^order"
+    );
+
+    let center = |c: u32| st_to_uv((f64::from(c) + 0.5) / n as f64);
+    let (x, y, z) = face_uv_to_xyz(face, center(i), center(j));
+    let lat = z.atan2((x * x + y * y).sqrt());
+    let lon = y.atan2(x);
+
+    (lat * 180.0 / PI, lon * 180.0 / PI)
+}
+
+/// Get the global index of the cell (i, j) of the given face of the cube, with a side of
+/// `2^order` cells.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`MAX_ORDER`], `face` is greater than 5 or the coordinates
+/// are not lower than `2^order`.
+pub fn face_ij_to_index(face: u8, i: u32, j: u32, order: u32) -> u64 {
+    let n = side(order);
+    assert!(face < 6, "face must be lower than 6");
+    let (i, j) = (u64::from(i), u64::from(j));
+    assert!(i < n && j < n, "coordinates must be lower than 2^order");
+
+    let d = if face % 2 == 0 {
+        convert_2d_to_1d_u64(i, j, n)
+    } else {
+        convert_2d_to_1d_u64(j, i, n)
+    };
+
+    u64::from(face) * n * n + d
+}
+
+/// Get the face of the cube and the (i, j) coordinates on the face of the cell with the given
+/// global index.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`MAX_ORDER`] or `index` is not lower than 6 · 4^order.
+pub fn index_to_face_ij(index: u64, order: u32) -> (u8, u32, u32) {
+    let n = side(order);
+    assert!(
+        index < 6 * n * n,
+        "the index must be lower than 6 * 4^order"
+    );
+
+    let face = (index / (n * n)) as u8;
+    let (i, j) = convert_1d_to_2d_u64(index % (n * n), n);
+    let (i, j) = if face % 2 == 0 { (i, j) } else { (j, i) };
+
+    (face, i as u32, j as u32)
+}
+
+/// Get the global index of the cell containing the given latitude and longitude in degrees on
+/// the curves of the given order; returns `None` if the position is not finite or the latitude is
+/// not within `-90.0..=90.0`.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`MAX_ORDER`].
+pub fn lat_lon_to_index(lat: f64, lon: f64, order: u32) -> Option<u64> {
+    let (face, i, j) = lat_lon_to_face_ij(lat, lon, order)?;
+    Some(face_ij_to_index(face, i, j, order))
+}
+
+/// Get the latitude and longitude in degrees of the center of the cell with the given global
+/// index.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`MAX_ORDER`] or `index` is not lower than 6 · 4^order.
+pub fn index_to_lat_lon(index: u64, order: u32) -> (f64, f64) {
+    let (face, i, j) = index_to_face_ij(index, order);
+    face_ij_to_lat_lon(face, i, j, order)
+}
+
+// The face of the largest component of the vector and the coordinates of its projection onto it,
+// within -1.0..=1.0
+fn xyz_to_face_uv(x: f64, y: f64, z: f64) -> (u8, f64, f64) {
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    let axis = if ax >= ay && ax >= az {
+        0
+    } else if ay >= az {
+        1
+    } else {
+        2
+    };
+    let negative = [x, y, z][axis] < 0.0;
+
+    match (axis, negative) {
+        (0, false) => (0, y / x, z / x),
+        (1, false) => (1, -x / y, z / y),
+        (2, false) => (2, -x / z, -y / z),
+        (0, true) => (3, z / x, y / x),
+        (1, true) => (4, z / y, -x / y),
+        _ => (5, -y / z, -x / z),
+    }
+}
+
+// The point on the given face of the cube with the given coordinates
+fn face_uv_to_xyz(face: u8, u: f64, v: f64) -> (f64, f64, f64) {
+    match face {
+        0 => (1.0, u, v),
+        1 => (-u, 1.0, v),
+        2 => (-u, -v, 1.0),
+        3 => (-1.0, -v, -u),
+        4 => (v, -1.0, -u),
+        _ => (v, u, -1.0),
+    }
+}
+
+// The quadratic transform of S2, from -1.0..=1.0 to 0.0..=1.0
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0.0 {
+        0.5 * (1.0 + 3.0 * u).sqrt()
+    } else {
+        1.0 - 0.5 * (1.0 - 3.0 * u).sqrt()
+    }
+}
+
+fn st_to_uv(s: f64) -> f64 {
+    if s >= 0.5 {
+        (4.0 * s * s - 1.0) / 3.0
+    } else {
+        (1.0 - 4.0 * (1.0 - s) * (1.0 - s)) / 3.0
+    }
+}
+
+fn side(order: u32) -> u64 {
+    assert!(order <= MAX_ORDER, "order must not exceed MAX_ORDER");
+    1 << order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the angle between two positions, in degrees
+    fn angle((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+        let (lat1, lon1, lat2, lon2) = (
+            lat1.to_radians(),
+            lon1.to_radians(),
+            lat2.to_radians(),
+            lon2.to_radians(),
+        );
+        let cos = lat1.sin() * lat2.sin() + lat1.cos() * lat2.cos() * (lon1 - lon2).cos();
+        cos.min(1.0).acos().to_degrees()
+    }
+
+    #[test]
+    fn faces() {
+        assert_eq!(lat_lon_to_face_ij(0.0, 0.0, 1), Some((0, 1, 1)));
+        assert_eq!(lat_lon_to_face_ij(0.0, 90.0, 0), Some((1, 0, 0)));
+        assert_eq!(lat_lon_to_face_ij(90.0, 0.0, 0), Some((2, 0, 0)));
+        assert_eq!(lat_lon_to_face_ij(0.0, 180.0, 0), Some((3, 0, 0)));
+        assert_eq!(lat_lon_to_face_ij(0.0, -90.0, 0), Some((4, 0, 0)));
+        assert_eq!(lat_lon_to_face_ij(-90.0, 0.0, 0), Some((5, 0, 0)));
+        assert_eq!(lat_lon_to_face_ij(91.0, 0.0, 3), None);
+        assert_eq!(lat_lon_to_index(0.0, f64::INFINITY, 3), None);
+        assert_eq!(
+            lat_lon_to_index(10.0, 370.0, 8),
+            lat_lon_to_index(10.0, 10.0, 8)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let order = 4;
+        for index in 0..6 * 256 {
+            let (face, i, j) = index_to_face_ij(index, order);
+            assert_eq!(face_ij_to_index(face, i, j, order), index);
+            let (lat, lon) = index_to_lat_lon(index, order);
+            assert_eq!(lat_lon_to_index(lat, lon, order), Some(index));
+        }
+    }
+
+    #[test]
+    fn continuity() {
+        // consecutive cells are adjacent, also across the edges of the faces
+        let order = 3;
+        for index in 1..6 * 64 {
+            let step = angle(
+                index_to_lat_lon(index - 1, order),
+                index_to_lat_lon(index, order),
+            );
+            assert!(step < 15.0);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! SQL functions exposing the conversions and range decompositions to SQLite.
+//!
+//! The functions can be added to a connection with [`register`], or with the `sqlite-extension`
+//! feature the crate can be built as a loadable extension with
+//! `cargo rustc --release --features sqlite-extension --crate-type cdylib` and loaded with
+//! `.load path/to/libhilbert_curve` or `SELECT load_extension(...)`. They are:
+//!
+//! - `hilbert_d2xy(d, n)`: the coordinates of the cell at distance `d`, as a JSON array `[x, y]`
+//! - `hilbert_xy2d(x, y, n)`: the distance of the cell (x, y)
+//! - `hilbert_ranges(x0, y0, x1, y1, n[, max_ranges])`: the ranges of distances covering the
+//!   rectangle spanning `x0..=x1` and `y0..=y1`, as a JSON array of `[start, end)` pairs, which
+//!   can be joined with `json_each`
+//!
+//! Invalid arguments are reported as SQL errors.
+
+use alloc::string::{String, ToString};
+use core::{convert::TryFrom, fmt::Write};
+#[cfg(feature = "sqlite-extension")]
+use std::os::raw::{c_char, c_int};
+
+#[cfg(feature = "sqlite-extension")]
+use rusqlite::ffi;
+use rusqlite::{
+    functions::{Context, FunctionFlags},
+    Connection, Error, Result,
+};
+
+use crate::{try_convert_1d_to_2d, try_convert_2d_to_1d, try_ranges_for_rect};
+
+/// The entry point of the extension, called by SQLite when it is loaded.
+///
+/// # Safety
+///
+/// The arguments must be the ones passed by SQLite to its loadable extensions.
+#[cfg(feature = "sqlite-extension")]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_extension_init(
+    db: *mut ffi::sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *mut ffi::sqlite3_api_routines,
+) -> c_int {
+    Connection::extension_init2(db, pz_err_msg, p_api, |db| register(&db).map(|()| false))
+}
+
+/// Register the functions on a connection.
+pub fn register(db: &Connection) -> Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8
+        | FunctionFlags::SQLITE_DETERMINISTIC
+        | FunctionFlags::SQLITE_INNOCUOUS;
+
+    db.create_scalar_function("hilbert_d2xy", 2, flags, |ctx| {
+        let (x, y) = to_point(ctx.get(0)?, ctx.get(1)?).map_err(sql_error)?;
+        Ok(format!("[{},{}]", x, y))
+    })?;
+    db.create_scalar_function("hilbert_xy2d", 3, flags, |ctx| {
+        to_distance(ctx.get(0)?, ctx.get(1)?, ctx.get(2)?).map_err(sql_error)
+    })?;
+    for n_arg in [5, 6] {
+        db.create_scalar_function("hilbert_ranges", n_arg, flags, hilbert_ranges)?;
+    }
+
+    Ok(())
+}
+
+fn hilbert_ranges(ctx: &Context<'_>) -> Result<String> {
+    let max_ranges = if ctx.len() == 6 {
+        ctx.get(5)?
+    } else {
+        i64::MAX
+    };
+    ranges(
+        [ctx.get(0)?, ctx.get(1)?, ctx.get(2)?, ctx.get(3)?],
+        ctx.get(4)?,
+        max_ranges,
+    )
+    .map_err(sql_error)
+}
+
+fn sql_error(message: String) -> Error {
+    Error::UserFunctionError(message.into())
+}
+
+// SQLite integers are signed, so the negative arguments are rejected before the conversions
+fn to_usize(value: i64) -> Result<usize, String> {
+    usize::try_from(value).map_err(|_| format!("{} is out of range", value))
+}
+
+fn to_point(d: i64, n: i64) -> Result<(i64, i64), String> {
+    let (x, y) = try_convert_1d_to_2d(to_usize(d)?, to_usize(n)?).map_err(|e| e.to_string())?;
+    Ok((x as i64, y as i64))
+}
+
+fn to_distance(x: i64, y: i64, n: i64) -> Result<i64, String> {
+    let d = try_convert_2d_to_1d(to_usize(x)?, to_usize(y)?, to_usize(n)?)
+        .map_err(|e| e.to_string())?;
+    Ok(d as i64)
+}
+
+fn ranges(rect: [i64; 4], n: i64, max_ranges: i64) -> Result<String, String> {
+    let mut bounds = [0; 4];
+    for (bound, &c) in bounds.iter_mut().zip(&rect) {
+        *bound = to_usize(c)?;
+    }
+    let [x0, y0, x1, y1] = bounds;
+    // negative limits are rejected like 0, and limits beyond a usize are equivalent to the largest
+    let max_ranges = usize::try_from(max_ranges.max(0)).unwrap_or(usize::MAX);
+    let ranges =
+        try_ranges_for_rect(x0, y0, x1, y1, to_usize(n)?, max_ranges).map_err(|e| e.to_string())?;
+
+    let mut json = String::from("[");
+    for (i, range) in ranges.into_iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        write!(json, "{}[{},{}]", separator, range.start, range.end).expect("writing to a string");
+    }
+    json.push(']');
+
+    Ok(json)
+}
+
+// an extension build calls SQLite through the routines of the process loading it, so it can't open
+// connections of its own
+#[cfg(all(test, not(feature = "sqlite-extension")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions() {
+        let db = Connection::open_in_memory().unwrap();
+        register(&db).unwrap();
+        let query = |sql: &str| db.query_row(sql, [], |row| row.get::<_, String>(0));
+
+        assert_eq!(query("SELECT hilbert_d2xy(5, 4)").unwrap(), "[0,3]");
+        assert_eq!(
+            db.query_row("SELECT hilbert_xy2d(0, 3, 4)", [], |row| row
+                .get::<_, i64>(0))
+                .unwrap(),
+            5
+        );
+        assert_eq!(
+            query("SELECT hilbert_ranges(0, 0, 0, 3, 4)").unwrap(),
+            "[[0,1],[3,6]]"
+        );
+        assert_eq!(
+            query("SELECT hilbert_ranges(0, 0, 0, 3, 4, 1)").unwrap(),
+            "[[0,6]]"
+        );
+        assert_eq!(
+            db.query_row(
+                "SELECT count(*) FROM json_each(hilbert_ranges(0, 0, 3, 0, 4, 8))",
+                [],
+                |row| row.get::<_, i64>(0)
+            )
+            .unwrap(),
+            2
+        );
+
+        for sql in [
+            "SELECT hilbert_d2xy(16, 4)",
+            "SELECT hilbert_d2xy(-1, 4)",
+            "SELECT hilbert_d2xy(0, 3)",
+            "SELECT hilbert_xy2d(4, 0, 4)",
+            "SELECT hilbert_xy2d(0, -1, 4)",
+            "SELECT hilbert_ranges(1, 0, 0, 0, 4)",
+            "SELECT hilbert_ranges(0, 0, 1, 1, 4, 0)",
+        ] {
+            assert!(query(sql).is_err(), "{}", sql);
+        }
+        assert!(query("SELECT hilbert_d2xy(16, 4)")
+            .unwrap_err()
+            .to_string()
+            .contains("distance 16 is out of range for a 4x4 grid"));
+    }
+}
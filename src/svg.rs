@@ -0,0 +1,132 @@
+//! SVG drawings of the curve.
+
+use alloc::string::String;
+use core::{fmt::Write, ops::Range};
+
+use crate::HilbertCurve;
+
+/// A part of the grid emphasized in an SVG drawing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    /// A segment of the curve, drawn with a thicker line.
+    Range(Range<usize>),
+    /// The rectangle spanning `x0..=x1` and `y0..=y1`, drawn as a translucent area.
+    Rect {
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+    },
+}
+
+/// Draw the curve of the given order as an SVG polyline through the centers of the cells, each
+/// cell being a square of `cell_size` units, with the row of the highest y at the top.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`], `cell_size` is 0, or a
+/// highlight is not within the curve or the grid.
+pub fn curve_svg(order: u32, cell_size: usize, highlights: &[Highlight]) -> String {
+    let curve = HilbertCurve::new(order);
+    let n = curve.side();
+    assert!(cell_size > 0, "cells must have a positive size");
+    let size = n * cell_size;
+    let half = cell_size as f64 / 2.0;
+    // the center of a cell, with y pointing down
+    let center = |x: usize, y: usize| {
+        (
+            (x * cell_size) as f64 + half,
+            ((n - 1 - y) * cell_size) as f64 + half,
+        )
+    };
+
+    let mut svg = String::new();
+    // writing to a string cannot fail
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+        size
+    );
+
+    for highlight in highlights {
+        if let Highlight::Rect { x0, y0, x1, y1 } = *highlight {
+            assert!(
+                x0 <= x1 && y0 <= y1 && x1 < n && y1 < n,
+                "the rectangle must be within the grid"
+            );
+            let _ = write!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="orange" fill-opacity="0.4"/>"#,
+                x0 * cell_size,
+                (n - 1 - y1) * cell_size,
+                (x1 - x0 + 1) * cell_size,
+                (y1 - y0 + 1) * cell_size
+            );
+        }
+    }
+
+    let mut polyline = |range: Range<usize>, style: &str| {
+        svg.push_str("<polyline points=\"");
+        for (i, (_, x, y)) in curve.range(range).enumerate() {
+            let (cx, cy) = center(x, y);
+            let _ = write!(svg, "{}{},{}", if i == 0 { "" } else { " " }, cx, cy);
+        }
+        let _ = write!(svg, "\" fill=\"none\" {}/>", style);
+    };
+
+    let width = (cell_size as f64 / 8.0).max(1.0);
+    polyline(0..curve.len(), &format_style("black", width));
+    for highlight in highlights {
+        if let Highlight::Range(range) = highlight {
+            polyline(range.clone(), &format_style("red", 3.0 * width));
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn format_style(color: &str, width: f64) -> String {
+    let mut style = String::new();
+    let _ = write!(
+        style,
+        r#"stroke="{}" stroke-width="{}" stroke-linejoin="round" stroke-linecap="round""#,
+        color, width
+    );
+    style
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drawing() {
+        let svg = curve_svg(1, 10, &[]);
+        assert_eq!(
+            svg,
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 20 20">"#,
+                r#"<polyline points="5,15 5,5 15,5 15,15" fill="none" stroke="black" "#,
+                r#"stroke-width="1.25" stroke-linejoin="round" stroke-linecap="round"/></svg>"#
+            )
+        );
+
+        let svg = curve_svg(
+            3,
+            4,
+            &[
+                Highlight::Range(2..9),
+                Highlight::Rect {
+                    x0: 1,
+                    y0: 2,
+                    x1: 3,
+                    y1: 2,
+                },
+            ],
+        );
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(r#"<rect x="4" y="20" width="12" height="4""#));
+        assert!(svg.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,96 @@
+//! Lookup tables of whole curves, built at compile time.
+//!
+//! The tables are built by `const fn`s, so they can initialize `static`s or `const`s without any
+//! work at runtime; [`hilbert_table!`](crate::hilbert_table) spells out their lengths.
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d};
+
+/// Build the table of the coordinates of the cells of the curve with `LEN` cells, indexed by
+/// distance.
+///
+/// # Panics
+///
+/// Panics (at compile time, when used in a constant) if `LEN` is not a power of 4 lower than or
+/// equal to 4^8.
+pub const fn d_to_xy_table<const LEN: usize>() -> [(u8, u8); LEN] {
+    let n = side(LEN);
+    let mut table = [(0, 0); LEN];
+    let mut d = 0;
+    while d < LEN {
+        let (x, y) = convert_1d_to_2d(d, n);
+        table[d] = (x as u8, y as u8);
+        d += 1;
+    }
+
+    table
+}
+
+/// Build the table of the distances of the cells of the curve with `LEN` cells, indexed by
+/// `y * n + x`.
+///
+/// # Panics
+///
+/// Panics (at compile time, when used in a constant) if `LEN` is not a power of 4 lower than or
+/// equal to 4^8.
+pub const fn xy_to_d_table<const LEN: usize>() -> [u16; LEN] {
+    let n = side(LEN);
+    let mut table = [0; LEN];
+    let mut i = 0;
+    while i < LEN {
+        table[i] = convert_2d_to_1d(i % n, i / n, n) as u16;
+        i += 1;
+    }
+
+    table
+}
+
+// The side of the grid of a curve with `len` cells that fits in the tables
+const fn side(len: usize) -> usize {
+    assert!(
+        len.is_power_of_two() && len.trailing_zeros() % 2 == 0 && len <= 1 << 16,
+        "the length must be a power of 4 not greater than 4^8"
+    );
+    1 << (len.trailing_zeros() / 2)
+}
+
+/// Build the lookup table of the curve of the given order at compile time: `order = k` expands
+/// to the `[(u8, u8); 4^k]` table of [`d_to_xy_table`](crate::table::d_to_xy_table) and
+/// `inverse, order = k` to the `[u16; 4^k]` table of
+/// [`xy_to_d_table`](crate::table::xy_to_d_table).
+///
+/// ```
+/// use hilbert_curve::hilbert_table;
+///
+/// static D_TO_XY: [(u8, u8); 256] = hilbert_table!(order = 4);
+/// static XY_TO_D: [u16; 256] = hilbert_table!(inverse, order = 4);
+///
+/// let (x, y) = D_TO_XY[100];
+/// assert_eq!(XY_TO_D[y as usize * 16 + x as usize], 100);
+/// ```
+#[macro_export]
+macro_rules! hilbert_table {
+    (order = $order:expr) => {
+        $crate::table::d_to_xy_table::<{ 1 << (2 * $order) }>()
+    };
+    (inverse, order = $order:expr) => {
+        $crate::table::xy_to_d_table::<{ 1 << (2 * $order) }>()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: [(u8, u8); 64] = crate::hilbert_table!(order = 3);
+    const INVERSE: [u16; 64] = crate::hilbert_table!(inverse, order = 3);
+
+    #[test]
+    fn tables() {
+        for (d, &(x, y)) in TABLE.iter().enumerate() {
+            assert_eq!(convert_1d_to_2d(d, 8), (x as usize, y as usize));
+            assert_eq!(INVERSE[y as usize * 8 + x as usize] as usize, d);
+        }
+        assert_eq!(d_to_xy_table::<1>(), [(0, 0)]);
+        assert_eq!(xy_to_d_table::<4>(), [0, 3, 1, 2]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Distances and neighbors on a periodic grid, whose opposite edges are adjacent.
+//!
+//! The curve is treated as a cycle, its last cell being followed by its first one; the
+//! [Moore curve](crate::moore), whose ends are adjacent in the grid, fits this topology best,
+//! but the cyclic distances only depend on the length of the curve.
+
+use core::convert::TryFrom;
+
+use crate::{convert_2d_to_1d, HilbertCurve};
+
+/// Get the distance between the distances `d1` and `d2` along the curve of side `n` treated as a
+/// cycle, i.e. the number of steps between them in the shorter direction.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or a distance is not lower than n^2.
+pub fn curve_distance_torus(d1: usize, d2: usize, n: usize) -> usize {
+    let len = check(n);
+    assert!(d1 < len && d2 < len, "distances must be lower than n^2");
+    let diff = d1.abs_diff(d2);
+
+    diff.min(len - diff)
+}
+
+/// Get the distance following `d` on the curve of side `n` treated as a cycle.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or `d` is not lower than n^2.
+pub fn cyclic_next(d: usize, n: usize) -> usize {
+    let len = check(n);
+    assert!(d < len, "distances must be lower than n^2");
+
+    if d + 1 == len {
+        0
+    } else {
+        d + 1
+    }
+}
+
+/// Get the distance preceding `d` on the curve of side `n` treated as a cycle.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or `d` is not lower than n^2.
+pub fn cyclic_prev(d: usize, n: usize) -> usize {
+    let len = check(n);
+    assert!(d < len, "distances must be lower than n^2");
+
+    d.checked_sub(1).unwrap_or(len - 1)
+}
+
+/// Wrap the signed coordinates (x, y) around the edges of an n by n grid.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`].
+pub fn wrap_point(x: isize, y: isize, n: usize) -> (usize, usize) {
+    check(n);
+    // valid sides are lower than the square root of usize::MAX
+    let side = isize::try_from(n).unwrap();
+
+    (x.rem_euclid(side) as usize, y.rem_euclid(side) as usize)
+}
+
+/// Iterate over the cells adjacent to the cell (x, y) on the wrapped grid, on the left, right,
+/// bottom and top, yielding their `(d, x, y)`; on grids with a side of 2 or less some of them are
+/// the same cell.
+///
+/// # Panics
+///
+/// Panics if `n` is not a valid side of a [`HilbertCurve`] or the coordinates are not lower than
+/// `n`.
+pub fn torus_neighbors_with_d(
+    x: usize,
+    y: usize,
+    n: usize,
+) -> impl Iterator<Item = (usize, usize, usize)> {
+    check(n);
+    assert!(x < n && y < n, "coordinates must be lower than n");
+    let (left, right) = ((x + n - 1) % n, (x + 1) % n);
+    let (down, up) = ((y + n - 1) % n, (y + 1) % n);
+    let neighbors = [(left, y), (right, y), (x, down), (x, up)];
+
+    IntoIterator::into_iter(neighbors).map(move |(x, y)| (convert_2d_to_1d(x, y, n), x, y))
+}
+
+// Check the side and return the length of the curve
+fn check(n: usize) -> usize {
+    HilbertCurve::try_from_side(n)
+        .expect("n must be a valid side")
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moore;
+
+    #[test]
+    fn cyclic_distances() {
+        let n = 4;
+        assert_eq!(curve_distance_torus(0, 15, n), 1);
+        assert_eq!(curve_distance_torus(3, 11, n), 8);
+        assert_eq!(curve_distance_torus(2, 12, n), 6);
+        assert_eq!(curve_distance_torus(5, 5, n), 0);
+        assert_eq!((cyclic_next(15, n), cyclic_next(3, n)), (0, 4));
+        assert_eq!((cyclic_prev(0, n), cyclic_prev(3, n)), (15, 2));
+
+        // the ends of the Moore curve are adjacent, so every cyclic step is a grid step
+        for d in 0..n * n {
+            let (x, y) = moore::convert_1d_to_2d(d, n);
+            let (nx, ny) = moore::convert_1d_to_2d(cyclic_next(d, n), n);
+            assert_eq!(x.abs_diff(nx) + y.abs_diff(ny), 1);
+        }
+
+        let curve = HilbertCurve::new(HilbertCurve::MAX_ORDER);
+        let (n, len) = (curve.side(), curve.len());
+        assert_eq!((cyclic_prev(0, n), cyclic_next(len - 1, n)), (len - 1, 0));
+        assert_eq!(curve_distance_torus(1, len - 1, n), 2);
+    }
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(wrap_point(-1, 8, 8), (7, 0));
+        assert_eq!(wrap_point(-17, 3, 8), (7, 3));
+
+        let neighbors: Vec<_> = torus_neighbors_with_d(0, 7, 8).collect();
+        let cells: Vec<_> = neighbors.iter().map(|&(_, x, y)| (x, y)).collect();
+        assert_eq!(cells, [(7, 7), (1, 7), (0, 6), (0, 0)]);
+        assert!(neighbors
+            .iter()
+            .all(|&(d, x, y)| d == convert_2d_to_1d(x, y, 8)));
+    }
+}
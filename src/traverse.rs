@@ -0,0 +1,137 @@
+//! Traversals of row-major two-dimensional data in the order of the curve, which makes nearby
+//! accesses share cache lines regardless of the size of the cache.
+//!
+//! The data can have any shape: the cells are visited along the pseudo-Hilbert path of
+//! [`pseudo`](crate::pseudo), which is the Hilbert curve itself for square power-of-two shapes.
+
+use crate::pseudo::for_each_point;
+
+/// Call `f` with the coordinates and the value of every element of the row-major data `width`
+/// elements wide and `height` elements high, in curve order.
+///
+/// # Panics
+///
+/// Panics if the length of `data` is not `width * height`.
+pub fn for_each_hilbert<T>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    f: impl FnMut(usize, usize, &T),
+) {
+    assert_eq!(
+        data.len(),
+        width * height,
+        "the data must have width * height elements"
+    );
+    for_each_hilbert_strided(data, width, height, width, f);
+}
+
+/// Like [`for_each_hilbert`], but with mutable access to the values.
+///
+/// # Panics
+///
+/// Panics if the length of `data` is not `width * height`.
+pub fn for_each_hilbert_mut<T>(
+    data: &mut [T],
+    width: usize,
+    height: usize,
+    f: impl FnMut(usize, usize, &mut T),
+) {
+    assert_eq!(
+        data.len(),
+        width * height,
+        "the data must have width * height elements"
+    );
+    for_each_hilbert_strided_mut(data, width, height, width, f);
+}
+
+/// Call `f` with the coordinates and the value of every element of a `width` by `height` region
+/// of row-major data whose rows start every `stride` elements, in curve order; the element (x, y)
+/// is at index `y * stride + x`.
+///
+/// # Panics
+///
+/// Panics if `stride` is lower than `width` or `data` is too short to hold the region.
+pub fn for_each_hilbert_strided<T>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    stride: usize,
+    mut f: impl FnMut(usize, usize, &T),
+) {
+    check(data.len(), width, height, stride);
+    for_each_point(width, height, |x, y| f(x, y, &data[y * stride + x]));
+}
+
+/// Like [`for_each_hilbert_strided`], but with mutable access to the values.
+///
+/// # Panics
+///
+/// Panics if `stride` is lower than `width` or `data` is too short to hold the region.
+pub fn for_each_hilbert_strided_mut<T>(
+    data: &mut [T],
+    width: usize,
+    height: usize,
+    stride: usize,
+    mut f: impl FnMut(usize, usize, &mut T),
+) {
+    check(data.len(), width, height, stride);
+    for_each_point(width, height, |x, y| f(x, y, &mut data[y * stride + x]));
+}
+
+fn check(len: usize, width: usize, height: usize, stride: usize) {
+    assert!(
+        stride >= width,
+        "the stride must not be lower than the width"
+    );
+    assert!(
+        height == 0 || width == 0 || len >= (height - 1) * stride + width,
+        "the data is too short for the region"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn traversal() {
+        let n = 8;
+        let data: Vec<usize> = (0..n * n).collect();
+        let mut d = 0;
+        for_each_hilbert(&data, n, n, |x, y, &value| {
+            assert_eq!((x, y), convert_1d_to_2d(d, n));
+            assert_eq!(value, y * n + x);
+            d += 1;
+        });
+        assert_eq!(d, n * n);
+    }
+
+    #[test]
+    fn transpose() {
+        let (w, h) = (5, 3);
+        let data: Vec<usize> = (0..w * h).collect();
+        let mut transposed = vec![0; w * h];
+        for_each_hilbert(&data, w, h, |x, y, &value| transposed[x * h + y] = value);
+        for y in 0..h {
+            for x in 0..w {
+                assert_eq!(transposed[x * h + y], data[y * w + x]);
+            }
+        }
+    }
+
+    #[test]
+    fn strided() {
+        // a 3 by 2 region of a buffer with rows of 4 elements
+        let mut data = [0u8; 7];
+        let mut visited = 0;
+        for_each_hilbert_strided_mut(&mut data, 3, 2, 4, |_, _, value| {
+            *value += 1;
+            visited += 1;
+        });
+        assert_eq!(visited, 6);
+        assert_eq!(data, [1, 1, 1, 0, 1, 1, 1]);
+        for_each_hilbert_mut(&mut [] as &mut [u8], 0, 4, |_, _, _| unreachable!());
+    }
+}
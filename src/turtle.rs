@@ -0,0 +1,183 @@
+//! Turtle-graphics descriptions of the curve.
+//!
+//! The turtle starts in the cell at `(0, 0)` facing the positive x axis and moves one cell at a
+//! time, so it visits the cells in the same order as [`convert_1d_to_2d`](crate::convert_1d_to_2d).
+//! The L-system string includes redundant turns, while the [`moves`] don't.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::iter::FusedIterator;
+
+use crate::HilbertCurve;
+
+/// A relative motion of the turtle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    /// Move forward by one cell.
+    Forward,
+    /// Turn left by 90 degrees.
+    TurnLeft,
+    /// Turn right by 90 degrees.
+    TurnRight,
+}
+
+/// An iterator over the moves that trace a [`HilbertCurve`], created by [`moves`].
+///
+/// Every turn is followed by a move forward, so the curve of order `k` is traced by 4^k − 1
+/// forward moves.
+#[derive(Debug, Clone)]
+pub struct Moves {
+    curve: HilbertCurve,
+    // the distance of the cell the turtle is in
+    d: usize,
+    heading: (isize, isize),
+}
+
+impl Iterator for Moves {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        if self.d + 1 >= self.curve.len() {
+            return None;
+        }
+
+        let step = self.curve.step(self.d);
+        let (hx, hy) = self.heading;
+        if step == self.heading {
+            self.d += 1;
+            return Some(Move::Forward);
+        }
+
+        // the curve never turns back
+        self.heading = step;
+        if step == (-hy, hx) {
+            Some(Move::TurnLeft)
+        } else {
+            Some(Move::TurnRight)
+        }
+    }
+}
+
+impl FusedIterator for Moves {}
+
+/// Get an iterator over the moves that trace the curve of the given order, without redundant
+/// turns.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn moves(order: u32) -> Moves {
+    Moves {
+        curve: HilbertCurve::new(order),
+        d: 0,
+        heading: (1, 0),
+    }
+}
+
+/// Get the instructions of the L-system of the curve of the given order: `F` moves the turtle
+/// forward by one cell, `+` turns it left by 90 degrees and `-` turns it right.
+///
+/// The string is the axiom `A` rewritten `order` times with the
+/// productions `A → +BF-AFA-FB+` and `B → -AF+BFB+FA-`, with the variables `A` and `B` removed.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+#[cfg(feature = "alloc")]
+pub fn l_system(order: u32) -> String {
+    assert!(
+        order <= HilbertCurve::MAX_ORDER,
+        "the order must not be greater than the maximum order"
+    );
+    let mut out = String::new();
+    expand(true, order, &mut out);
+
+    out
+}
+
+// The productions of A and B, where B is A mirrored
+#[cfg(feature = "alloc")]
+fn expand(a: bool, depth: u32, out: &mut String) {
+    if depth == 0 {
+        return;
+    }
+    let (left, right) = if a { ('+', '-') } else { ('-', '+') };
+    out.push(left);
+    expand(!a, depth - 1, out);
+    out.push('F');
+    out.push(right);
+    expand(a, depth - 1, out);
+    out.push('F');
+    expand(a, depth - 1, out);
+    out.push(right);
+    out.push('F');
+    expand(!a, depth - 1, out);
+    out.push(left);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_1d_to_2d;
+
+    #[test]
+    fn moves_trace_curve() {
+        assert_eq!(moves(0).next(), None);
+        let expected = [
+            Move::TurnLeft,
+            Move::Forward,
+            Move::TurnRight,
+            Move::Forward,
+            Move::TurnRight,
+            Move::Forward,
+        ];
+        assert!(moves(1).eq(expected));
+
+        for order in 1..=5 {
+            let n = 1 << order;
+            let (mut x, mut y, mut heading) = (0isize, 0isize, (1isize, 0isize));
+            let mut d = 0;
+            for m in moves(order) {
+                match m {
+                    Move::TurnLeft => heading = (-heading.1, heading.0),
+                    Move::TurnRight => heading = (heading.1, -heading.0),
+                    Move::Forward => {
+                        x += heading.0;
+                        y += heading.1;
+                        d += 1;
+                        assert_eq!(convert_1d_to_2d(d, n), (x as usize, y as usize));
+                    }
+                }
+            }
+            assert_eq!(d, n * n - 1);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn l_system_matches_curve() {
+        assert_eq!(l_system(0), "");
+        assert_eq!(l_system(1), "+F-F-F+");
+
+        for order in 1..=5 {
+            let n = 1 << order;
+            let (mut x, mut y, mut heading) = (0isize, 0isize, (1isize, 0isize));
+            let mut d = 0;
+            for c in l_system(order).chars() {
+                match c {
+                    '+' => heading = (-heading.1, heading.0),
+                    '-' => heading = (heading.1, -heading.0),
+                    'F' => {
+                        x += heading.0;
+                        y += heading.1;
+                        d += 1;
+                        assert_eq!(convert_1d_to_2d(d, n), (x as usize, y as usize));
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(d, n * n - 1);
+        }
+    }
+}
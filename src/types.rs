@@ -0,0 +1,126 @@
+//! Typed wrappers for curve distances and grid cells.
+
+use core::ops::{Add, Sub};
+
+/// A one-dimensional distance along a Hilbert curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HilbertIndex(pub u64);
+
+/// A cell of the grid filled by a Hilbert curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridPoint {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl HilbertIndex {
+    /// The number of bytes needed to encode the indices of a curve of the given order.
+    pub const fn byte_width(order: u32) -> usize {
+        (2 * order as usize).div_ceil(8)
+    }
+
+    /// Encode the index as `W` big-endian bytes, so that the lexicographic order of the encoded
+    /// indices matches their numeric order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `W` is greater than 8 or the index doesn't fit in `W` bytes.
+    pub fn to_be_bytes<const W: usize>(&self) -> [u8; W] {
+        assert!(W <= 8, "the width must not exceed 8 bytes");
+        assert!(
+            W == 8 || self.0 >> (8 * W) == 0,
+            "the index doesn't fit in the given width"
+        );
+        let mut bytes = [0; W];
+        bytes.copy_from_slice(&self.0.to_be_bytes()[8 - W..]);
+        bytes
+    }
+
+    /// Decode an index from `W` big-endian bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `W` is greater than 8.
+    pub fn from_be_bytes<const W: usize>(bytes: [u8; W]) -> Self {
+        assert!(W <= 8, "the width must not exceed 8 bytes");
+        let mut padded = [0; 8];
+        padded[8 - W..].copy_from_slice(&bytes);
+        HilbertIndex(u64::from_be_bytes(padded))
+    }
+}
+
+impl GridPoint {
+    /// Create a point with the given coordinates.
+    pub fn new(x: usize, y: usize) -> Self {
+        GridPoint { x, y }
+    }
+}
+
+impl From<u64> for HilbertIndex {
+    fn from(d: u64) -> Self {
+        HilbertIndex(d)
+    }
+}
+
+impl From<HilbertIndex> for u64 {
+    fn from(index: HilbertIndex) -> Self {
+        index.0
+    }
+}
+
+impl From<(usize, usize)> for GridPoint {
+    fn from((x, y): (usize, usize)) -> Self {
+        GridPoint { x, y }
+    }
+}
+
+impl From<GridPoint> for (usize, usize) {
+    fn from(point: GridPoint) -> Self {
+        (point.x, point.y)
+    }
+}
+
+/// Move forward along the curve.
+impl Add<u64> for HilbertIndex {
+    type Output = HilbertIndex;
+
+    fn add(self, rhs: u64) -> Self::Output {
+        HilbertIndex(self.0 + rhs)
+    }
+}
+
+/// Move backward along the curve.
+impl Sub<u64> for HilbertIndex {
+    type Output = HilbertIndex;
+
+    fn sub(self, rhs: u64) -> Self::Output {
+        HilbertIndex(self.0 - rhs)
+    }
+}
+
+/// The distance between two indices along the curve.
+impl Sub for HilbertIndex {
+    type Output = u64;
+
+    fn sub(self, rhs: HilbertIndex) -> Self::Output {
+        self.0 - rhs.0
+    }
+}
+
+impl Add for GridPoint {
+    type Output = GridPoint;
+
+    fn add(self, rhs: GridPoint) -> Self::Output {
+        GridPoint::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for GridPoint {
+    type Output = GridPoint;
+
+    fn sub(self, rhs: GridPoint) -> Self::Output {
+        GridPoint::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
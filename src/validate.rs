@@ -0,0 +1,115 @@
+//! Checks of distances and of the defining properties of the curve.
+
+use core::fmt;
+
+use crate::{convert_1d_to_2d, convert_2d_to_1d, HilbertCurve};
+
+/// A violation of the defining properties of the curve, as found by [`verify_curve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CurveDefect {
+    /// The distance `d` is converted to a cell outside the grid.
+    OutsideGrid { d: usize },
+    /// The cell of the distance `d` is not converted back to `d`.
+    NotReversible { d: usize },
+    /// The cells of the distances `d` and `d + 1` are not adjacent.
+    NotContinuous { d: usize },
+    /// The curve doesn't start in the lower left corner or doesn't end in the lower right one.
+    WrongEnds,
+}
+
+impl fmt::Display for CurveDefect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CurveDefect::OutsideGrid { d } => write!(f, "distance {} is outside the grid", d),
+            CurveDefect::NotReversible { d } => write!(f, "distance {} is not reversible", d),
+            CurveDefect::NotContinuous { d } => {
+                write!(f, "distances {} and {} are not adjacent", d, d + 1)
+            }
+            CurveDefect::WrongEnds => write!(f, "the curve doesn't start or end in its corners"),
+        }
+    }
+}
+
+/// Check whether `d` is a distance along a curve of side `n`, i.e. whether `n` is a valid side of
+/// a [`HilbertCurve`] and `d` is lower than n^2.
+pub fn is_valid_index(d: usize, n: usize) -> bool {
+    HilbertCurve::try_from_side(n).is_ok_and(|curve| d < curve.len())
+}
+
+/// Check whether the cells at the distances `d1` and `d2` along a curve of side `n` share an edge
+/// in the grid, which is always the case if they are consecutive along the curve.
+///
+/// # Panics
+///
+/// Panics if either distance is not valid for `n`.
+pub fn are_curve_adjacent(d1: usize, d2: usize, n: usize) -> bool {
+    assert!(
+        is_valid_index(d1, n) && is_valid_index(d2, n),
+        "the distances must be valid for n"
+    );
+    if d1.abs_diff(d2) == 1 {
+        return true;
+    }
+    let ((x1, y1), (x2, y2)) = (convert_1d_to_2d(d1, n), convert_1d_to_2d(d2, n));
+
+    x1.abs_diff(x2) + y1.abs_diff(y2) == 1
+}
+
+/// Verify that the conversions of the curve of the given order are mutually inverse, stay within
+/// the grid and take unit steps from the lower left corner to the lower right one, returning the
+/// first defect found; this takes time proportional to the length of the curve.
+///
+/// # Panics
+///
+/// Panics if `order` is greater than [`HilbertCurve::MAX_ORDER`].
+pub fn verify_curve(order: u32) -> Result<(), CurveDefect> {
+    let n = HilbertCurve::new(order).side();
+    let mut prev = None;
+
+    for d in 0..n * n {
+        let (x, y) = convert_1d_to_2d(d, n);
+        if x >= n || y >= n {
+            return Err(CurveDefect::OutsideGrid { d });
+        }
+        if convert_2d_to_1d(x, y, n) != d {
+            return Err(CurveDefect::NotReversible { d });
+        }
+        if let Some((px, py)) = prev {
+            if x.abs_diff(px) + y.abs_diff(py) != 1 {
+                return Err(CurveDefect::NotContinuous { d: d - 1 });
+            }
+        }
+        prev = Some((x, y));
+    }
+
+    // the cells are then distinct, so the curve visits all of them
+    if convert_1d_to_2d(0, n) != (0, 0) || prev != Some((n - 1, 0)) {
+        return Err(CurveDefect::WrongEnds);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation() {
+        assert!(is_valid_index(15, 4));
+        assert!(!is_valid_index(16, 4));
+        assert!(!is_valid_index(0, 3));
+        assert!(!is_valid_index(0, 0));
+        assert!(!is_valid_index(0, 1 << (usize::BITS / 2)));
+
+        // the cell (1, 1) at distance 2 is adjacent to (1, 2) at distance 13 on the curve of side 4
+        assert!(are_curve_adjacent(2, 13, 4));
+        assert!(are_curve_adjacent(6, 5, 4));
+        assert!(!are_curve_adjacent(0, 2, 4));
+        assert!(!are_curve_adjacent(3, 3, 4));
+
+        for order in 0..=6 {
+            assert_eq!(verify_curve(order), Ok(()));
+        }
+    }
+}
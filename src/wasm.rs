@@ -0,0 +1,162 @@
+//! `wasm-bindgen` exports of the conversions, iteration and range decompositions.
+//!
+//! Distances are JavaScript numbers, which represent them exactly for curves of up to 2^53 cells,
+//! and the batch functions take and return typed arrays, with the coordinates of the cells
+//! interleaved as `[x0, y0, x1, y1, ...]`. Invalid arguments are reported as JavaScript errors.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{try_convert_1d_to_2d, try_convert_2d_to_1d, try_ranges_for_rect, HilbertCurve};
+
+// the largest distance represented exactly by a JavaScript number
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Convert a distance `d` to the `[x, y]` coordinates of its cell.
+#[wasm_bindgen]
+pub fn d2xy(d: f64, n: u32) -> Result<Vec<u32>, JsError> {
+    d2xy_batch(&[d], n)
+}
+
+/// Convert the coordinates of a cell to its distance.
+#[wasm_bindgen]
+pub fn xy2d(x: u32, y: u32, n: u32) -> Result<f64, JsError> {
+    Ok(xy2d_batch(&[x, y], n)?[0])
+}
+
+/// Convert a `Float64Array` of distances to a `Uint32Array` of interleaved coordinates.
+#[wasm_bindgen]
+pub fn d2xy_batch(ds: &[f64], n: u32) -> Result<Vec<u32>, JsError> {
+    to_points(ds, n).map_err(js_error)
+}
+
+/// Convert a `Uint32Array` of interleaved coordinates to a `Float64Array` of distances.
+#[wasm_bindgen]
+pub fn xy2d_batch(xy: &[u32], n: u32) -> Result<Vec<f64>, JsError> {
+    to_distances(xy, n).map_err(js_error)
+}
+
+/// Get the interleaved coordinates of the cells at the distances `start..end`, in curve order.
+#[wasm_bindgen]
+pub fn segment_points(start: f64, end: f64, n: u32) -> Result<Vec<u32>, JsError> {
+    segment(start, end, n).map_err(js_error)
+}
+
+/// Decompose the rectangle spanning `x0..=x1` and `y0..=y1` into ranges of distances, as
+/// described by [`ranges_for_rect`](crate::ranges_for_rect), returned as a `Float64Array` of
+/// interleaved `[start, end)` bounds.
+#[wasm_bindgen]
+pub fn ranges_for_rect(
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+    n: u32,
+    max_ranges: u32,
+) -> Result<Vec<f64>, JsError> {
+    ranges(x0, y0, x1, y1, n, max_ranges).map_err(js_error)
+}
+
+fn js_error(message: String) -> JsError {
+    JsError::new(&message)
+}
+
+// Check that the distances along a curve of side n are represented exactly; the validated
+// functions check the rest
+fn side(n: u32) -> Result<usize, String> {
+    if n as f64 * n as f64 > MAX_SAFE_INTEGER {
+        return Err(format!("{} is too large", n));
+    }
+    Ok(n as usize)
+}
+
+fn distance(d: f64) -> Result<usize, String> {
+    if d.fract() != 0.0 || !(0.0..=MAX_SAFE_INTEGER).contains(&d) {
+        return Err(format!("distance {} is not a safe integer", d));
+    }
+    usize::try_from(d as u64).map_err(|_| format!("distance {} is too large", d))
+}
+
+fn to_points(ds: &[f64], n: u32) -> Result<Vec<u32>, String> {
+    let n = side(n)?;
+    let mut xy = Vec::with_capacity(2 * ds.len());
+    for &d in ds {
+        let (x, y) = try_convert_1d_to_2d(distance(d)?, n).map_err(|e| e.to_string())?;
+        xy.extend_from_slice(&[x as u32, y as u32]);
+    }
+    Ok(xy)
+}
+
+fn to_distances(xy: &[u32], n: u32) -> Result<Vec<f64>, String> {
+    let n = side(n)?;
+    if xy.len() % 2 != 0 {
+        return Err("the coordinates must come in pairs".into());
+    }
+    xy.chunks_exact(2)
+        .map(|pair| {
+            let d = try_convert_2d_to_1d(pair[0] as usize, pair[1] as usize, n)
+                .map_err(|e| e.to_string())?;
+            Ok(d as f64)
+        })
+        .collect()
+}
+
+fn segment(start: f64, end: f64, n: u32) -> Result<Vec<u32>, String> {
+    let curve = HilbertCurve::try_from_side(side(n)?).map_err(|e| e.to_string())?;
+    let (start, end) = (distance(start)?, distance(end)?);
+    if start > end || end > curve.len() {
+        return Err(format!(
+            "the segment from {} to {} is not within the curve",
+            start, end
+        ));
+    }
+    Ok(curve
+        .range(start..end)
+        .flat_map(|(_, x, y)| [x as u32, y as u32])
+        .collect())
+}
+
+fn ranges(x0: u32, y0: u32, x1: u32, y1: u32, n: u32, max_ranges: u32) -> Result<Vec<f64>, String> {
+    let n = side(n)?;
+    let [x0, y0, x1, y1] = [x0, y0, x1, y1].map(|c| c as usize);
+    let ranges =
+        try_ranges_for_rect(x0, y0, x1, y1, n, max_ranges as usize).map_err(|e| e.to_string())?;
+    Ok(ranges
+        .into_iter()
+        .flat_map(|range| [range.start as f64, range.end as f64])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the exports themselves can only be called from JavaScript, so the tests cover the functions
+    // they wrap
+    #[test]
+    fn conversions() {
+        assert_eq!(to_points(&[0.0, 5.0], 4), Ok(vec![0, 0, 0, 3]));
+        assert_eq!(to_distances(&[0, 0, 0, 3], 4), Ok(vec![0.0, 5.0]));
+        assert!(to_points(&[16.0], 4).is_err());
+        assert!(to_points(&[1.5], 4).is_err());
+        assert!(to_points(&[1.0], 3).is_err());
+        assert!(to_distances(&[0, 0, 1], 4).is_err());
+        assert!(to_distances(&[4, 0], 4).is_err());
+
+        assert_eq!(segment(1.0, 3.0, 2), Ok(vec![0, 1, 1, 1]));
+        assert_eq!(segment(0.0, 4.0, 2).map(|xy| xy.len()), Ok(8));
+        assert!(segment(3.0, 1.0, 2).is_err());
+        assert_eq!(ranges(0, 0, 1, 1, 4, 8), Ok(vec![0.0, 4.0]));
+        assert_eq!(
+            ranges(0, 0, 4, 1, 4, 8),
+            Err("coordinates (4, 1) are out of range for a 4x4 grid".into())
+        );
+        assert!(ranges(0, 0, 1, 1, 4, 0).is_err());
+        assert!(to_points(&[0.0], 1 << 27).is_err());
+    }
+}
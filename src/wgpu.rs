@@ -0,0 +1,334 @@
+//! Batch conversions on the GPU using `wgpu` compute shaders.
+//!
+//! A [`GpuConverter`] runs the bitwise conversion loop of the crate on every element of a batch
+//! in parallel, in chunks of up to [`CHUNK_LEN`] elements; the results match the CPU batch
+//! conversions. Distances are `u64`s and coordinates `u32`s, which covers curves of up to 2^32
+//! by 2^32 cells. Transferring the buffers dominates for small batches, so the GPU is only worth
+//! it for batches of millions of elements.
+
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+use std::{error, sync::mpsc};
+
+use ::wgpu::util::DeviceExt;
+
+use crate::HilbertError;
+
+/// The largest number of elements converted by a single dispatch of the shaders.
+pub const CHUNK_LEN: usize = 64 * 65535;
+
+/// An error of the GPU conversions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GpuError {
+    /// No suitable GPU adapter or device could be obtained.
+    Unavailable(alloc::string::String),
+    /// The inputs of a conversion are invalid.
+    Input(HilbertError),
+    /// The results could not be read back from the GPU.
+    Readback,
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::Unavailable(reason) => write!(f, "no GPU is available: {}", reason),
+            GpuError::Input(e) => write!(f, "{}", e),
+            GpuError::Readback => write!(f, "the results could not be read back from the GPU"),
+        }
+    }
+}
+
+impl error::Error for GpuError {}
+
+impl From<HilbertError> for GpuError {
+    fn from(e: HilbertError) -> Self {
+        GpuError::Input(e)
+    }
+}
+
+/// A converter running the conversions on a GPU device.
+#[derive(Debug)]
+pub struct GpuConverter {
+    device: ::wgpu::Device,
+    queue: ::wgpu::Queue,
+    layout: ::wgpu::BindGroupLayout,
+    d_to_xy: ::wgpu::ComputePipeline,
+    xy_to_d: ::wgpu::ComputePipeline,
+}
+
+impl GpuConverter {
+    /// Create a converter on the default GPU adapter of the system, blocking until it is ready.
+    pub fn new() -> Result<Self, GpuError> {
+        let instance = ::wgpu::Instance::new(&::wgpu::InstanceDescriptor::from_env_or_default());
+        let adapter =
+            pollster::block_on(instance.request_adapter(&::wgpu::RequestAdapterOptions::default()))
+                .map_err(|e| GpuError::Unavailable(e.to_string()))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&Default::default()))
+            .map_err(|e| GpuError::Unavailable(e.to_string()))?;
+
+        Ok(Self::from_device(device, queue))
+    }
+
+    /// Create a converter on an existing device, e.g. the one of a renderer.
+    pub fn from_device(device: ::wgpu::Device, queue: ::wgpu::Queue) -> Self {
+        let module = device.create_shader_module(::wgpu::include_wgsl!("hilbert.wgsl"));
+        let entry = |binding, ty| ::wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: ::wgpu::ShaderStages::COMPUTE,
+            ty: ::wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&::wgpu::BindGroupLayoutDescriptor {
+            label: Some("hilbert_curve"),
+            entries: &[
+                entry(0, ::wgpu::BufferBindingType::Uniform),
+                entry(1, ::wgpu::BufferBindingType::Storage { read_only: true }),
+                entry(2, ::wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&::wgpu::PipelineLayoutDescriptor {
+            label: Some("hilbert_curve"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point| {
+            device.create_compute_pipeline(&::wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let (d_to_xy, xy_to_d) = (pipeline("d_to_xy"), pipeline("xy_to_d"));
+
+        GpuConverter {
+            device,
+            queue,
+            layout,
+            d_to_xy,
+            xy_to_d,
+        }
+    }
+
+    /// Convert a slice of one-dimensional distances to their (x, y) coordinates.
+    ///
+    /// Returns an error if `n` is not a power of 2 of at most 2^32 or a distance is not lower than
+    /// n^2, or if the GPU fails.
+    pub fn convert_1d_to_2d_batch(&self, ds: &[u64], n: u64) -> Result<Vec<(u32, u32)>, GpuError> {
+        let order = validate_side(n)?;
+        if let Some(&d) = ds.iter().find(|&&d| d as u128 >= n as u128 * n as u128) {
+            return Err(HilbertError::DistanceOutOfRange {
+                d: d as usize,
+                n: n as usize,
+            }
+            .into());
+        }
+
+        let words: Vec<u32> = ds
+            .iter()
+            .flat_map(|&d| [d as u32, (d >> 32) as u32])
+            .collect();
+        let out = self.run(&self.d_to_xy, &words, order)?;
+
+        Ok(out.chunks_exact(2).map(|xy| (xy[0], xy[1])).collect())
+    }
+
+    /// Convert a slice of (x, y) coordinates to their one-dimensional distances.
+    ///
+    /// Returns an error if `n` is not a power of 2 of at most 2^32 or a coordinate is not lower
+    /// than `n`, or if the GPU fails.
+    pub fn convert_2d_to_1d_batch(
+        &self,
+        points: &[(u32, u32)],
+        n: u64,
+    ) -> Result<Vec<u64>, GpuError> {
+        let order = validate_side(n)?;
+        if let Some(&(x, y)) = points
+            .iter()
+            .find(|&&(x, y)| x as u64 >= n || y as u64 >= n)
+        {
+            return Err(HilbertError::CoordinateOutOfRange {
+                x: x as usize,
+                y: y as usize,
+                n: n as usize,
+            }
+            .into());
+        }
+
+        let words: Vec<u32> = points.iter().flat_map(|&(x, y)| [x, y]).collect();
+        let out = self.run(&self.xy_to_d, &words, order)?;
+
+        Ok(out
+            .chunks_exact(2)
+            .map(|d| d[0] as u64 | (d[1] as u64) << 32)
+            .collect())
+    }
+
+    // Run a conversion over pairs of words, a chunk at a time
+    fn run(
+        &self,
+        pipeline: &::wgpu::ComputePipeline,
+        words: &[u32],
+        order: u32,
+    ) -> Result<Vec<u32>, GpuError> {
+        let mut out = Vec::with_capacity(words.len());
+        if words.is_empty() {
+            return Ok(out);
+        }
+
+        let size = (2 * 4 * CHUNK_LEN.min(words.len() / 2)) as u64;
+        let buffer = |label, usage| {
+            self.device.create_buffer(&::wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage,
+                mapped_at_creation: false,
+            })
+        };
+        let input = buffer(
+            "input",
+            ::wgpu::BufferUsages::STORAGE | ::wgpu::BufferUsages::COPY_DST,
+        );
+        let output = buffer(
+            "output",
+            ::wgpu::BufferUsages::STORAGE | ::wgpu::BufferUsages::COPY_SRC,
+        );
+        let staging = buffer(
+            "staging",
+            ::wgpu::BufferUsages::MAP_READ | ::wgpu::BufferUsages::COPY_DST,
+        );
+
+        for chunk in words.chunks(2 * CHUNK_LEN) {
+            let count = (chunk.len() / 2) as u32;
+            let bytes: Vec<u8> = chunk.iter().flat_map(|w| w.to_le_bytes()).collect();
+            self.queue.write_buffer(&input, 0, &bytes);
+            let params = self
+                .device
+                .create_buffer_init(&::wgpu::util::BufferInitDescriptor {
+                    label: Some("params"),
+                    contents: &[order.to_le_bytes(), count.to_le_bytes()].concat(),
+                    usage: ::wgpu::BufferUsages::UNIFORM,
+                });
+            let bind_group = self.device.create_bind_group(&::wgpu::BindGroupDescriptor {
+                label: Some("hilbert_curve"),
+                layout: &self.layout,
+                entries: &[
+                    ::wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params.as_entire_binding(),
+                    },
+                    ::wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: input.as_entire_binding(),
+                    },
+                    ::wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: output.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let mut encoder = self.device.create_command_encoder(&Default::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&Default::default());
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(count.div_ceil(64), 1, 1);
+            }
+            let len = bytes.len() as u64;
+            encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, len);
+            self.queue.submit([encoder.finish()]);
+
+            let (sender, receiver) = mpsc::channel();
+            staging.map_async(::wgpu::MapMode::Read, 0..len, move |result| {
+                let _ = sender.send(result);
+            });
+            self.device
+                .poll(::wgpu::PollType::Wait)
+                .map_err(|_| GpuError::Readback)?;
+            match receiver.recv() {
+                Ok(Ok(())) => {}
+                _ => return Err(GpuError::Readback),
+            }
+            {
+                let view = staging.get_mapped_range(0..len);
+                out.extend(
+                    view.chunks_exact(4)
+                        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])),
+                );
+            }
+            staging.unmap();
+        }
+
+        Ok(out)
+    }
+}
+
+fn validate_side(n: u64) -> Result<u32, HilbertError> {
+    if !n.is_power_of_two() {
+        return Err(HilbertError::NotPowerOfTwo { n: n as usize });
+    }
+    let order = n.trailing_zeros();
+    if order > 32 {
+        return Err(HilbertError::OrderTooLarge { order });
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert_1d_to_2d_u64, convert_2d_to_1d_u64};
+
+    #[test]
+    fn matches_cpu() {
+        // the tests run on machines without a GPU too
+        let gpu = match GpuConverter::new() {
+            Ok(gpu) => gpu,
+            Err(_) => return,
+        };
+
+        for order in [0, 1, 5, 16, 17, 32] {
+            let n = 1u64 << order;
+            let len = if order == 32 { u64::MAX } else { n * n - 1 };
+            let ds: Vec<u64> = (0..10_000u64)
+                .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) % len.max(1))
+                .chain([0, len])
+                .collect();
+            let points = gpu.convert_1d_to_2d_batch(&ds, n).unwrap();
+            for (&d, &(x, y)) in ds.iter().zip(&points) {
+                assert_eq!(convert_1d_to_2d_u64(d, n), (x as u64, y as u64));
+                assert_eq!(convert_2d_to_1d_u64(x as u64, y as u64, n), d);
+            }
+            assert_eq!(gpu.convert_2d_to_1d_batch(&points, n).unwrap(), ds);
+        }
+
+        assert!(gpu.convert_1d_to_2d_batch(&[16], 4).is_err());
+        assert!(gpu.convert_2d_to_1d_batch(&[(0, 4)], 4).is_err());
+        assert!(gpu.convert_1d_to_2d_batch(&[0], 3).is_err());
+        assert_eq!(gpu.convert_1d_to_2d_batch(&[], 4), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn chunks() {
+        let gpu = match GpuConverter::new() {
+            Ok(gpu) => gpu,
+            Err(_) => return,
+        };
+        let n = 1 << 12;
+        let ds: Vec<u64> = (0..CHUNK_LEN as u64 + 1000).collect();
+        let points = gpu.convert_1d_to_2d_batch(&ds, n).unwrap();
+        assert_eq!(points.len(), ds.len());
+        assert_eq!(points[CHUNK_LEN + 999], {
+            let (x, y) = convert_1d_to_2d_u64(CHUNK_LEN as u64 + 999, n);
+            (x as u32, y as u32)
+        });
+    }
+}